@@ -0,0 +1,493 @@
+//! LZH (LHA) Archive
+//!
+//! ヘッダ level 0/1/2、圧縮形式 -lh0- (無圧縮), -lh4-, -lh5-, -lh6-, -lh7- に対応する。
+//!
+use crate::archive::Entry;
+
+// Extended Header Type
+const EXT_FILENAME: u8 = 0x01;
+const EXT_DIRNAME: u8 = 0x02;
+
+/// Read all File Entries
+///
+/// # Argument
+///
+///   * `buf` LZH Archive
+///
+/// # Return
+///
+///   * Ok(Vec<Entry>)
+///   * Err(String)    Broken archive, unsupported method, CRC error
+///
+pub fn read_entries(buf: &[u8]) -> Result<Vec<Entry>, String> {
+    let mut entries = Vec::new();
+    let mut pos = 0;
+    while pos < buf.len() && buf[pos] != 0 {
+        let header = Header::parse(buf, pos)?;
+        let end = header.data_pos + header.packed_size;
+        let packed = buf
+            .get(header.data_pos..end)
+            .ok_or_else(|| format!("\"{}\" is truncated", header.name))?;
+
+        if !header.is_directory() {
+            let data = match header.method.as_str() {
+                "-lh0-" | "-lz4-" => packed.to_vec(),
+                "-lh4-" => decode(packed, header.original_size, 12, 4)?,
+                "-lh5-" => decode(packed, header.original_size, 13, 4)?,
+                "-lh6-" => decode(packed, header.original_size, 15, 5)?,
+                "-lh7-" => decode(packed, header.original_size, 16, 5)?,
+                method => return Err(format!("\"{}\" unsupported method {}", header.name, method)),
+            };
+            if data.len() != header.original_size || crc16(&data) != header.crc {
+                return Err(format!("\"{}\" CRC error", header.name));
+            }
+            entries.push(Entry {
+                name: header.name,
+                data,
+            });
+        }
+        pos = end;
+    }
+    Ok(entries)
+}
+
+/// LZH Header (Helper struct)
+///
+struct Header {
+    method: String,
+    packed_size: usize,
+    original_size: usize,
+    crc: u16,
+    name: String,
+    /// Position of compressed data
+    data_pos: usize,
+}
+
+impl Header {
+    fn parse(buf: &[u8], pos: usize) -> Result<Header, String> {
+        let broken = || "Broken LZH header".to_string();
+        let b = buf.get(pos..pos + 24).ok_or_else(broken)?;
+        let word = |b: &[u8], n: usize| u16::from_le_bytes([b[n], b[n + 1]]) as usize;
+        let dword = |b: &[u8], n: usize| u32::from_le_bytes([b[n], b[n + 1], b[n + 2], b[n + 3]]);
+
+        let method = String::from_utf8_lossy(&b[2..7]).to_string();
+        let mut packed_size = dword(b, 7) as usize;
+        let original_size = dword(b, 11) as usize;
+        let level = b[20];
+
+        let (mut name, crc, mut ext_pos, data_pos) = match level {
+            0 | 1 => {
+                let header_size = b[0] as usize + 2;
+                let name_len = b[21] as usize;
+                if header_size < 22 + name_len + 2 {
+                    return Err(broken());
+                }
+                let h = buf.get(pos..pos + header_size).ok_or_else(broken)?;
+                let name = h.get(22..22 + name_len).ok_or_else(broken)?;
+                let crc = h
+                    .get(22 + name_len..24 + name_len)
+                    .map(|b| word(b, 0) as u16)
+                    .ok_or_else(broken)?;
+                if level == 0 {
+                    (name.to_vec(), crc, None, pos + header_size)
+                } else {
+                    // next size is at the end of the base header
+                    (
+                        name.to_vec(),
+                        crc,
+                        Some(pos + header_size - 2),
+                        pos + header_size,
+                    )
+                }
+            }
+            2 => {
+                let header_size = word(b, 0);
+                if header_size < 24 {
+                    return Err(broken());
+                }
+                (
+                    Vec::new(),
+                    word(b, 21) as u16,
+                    Some(pos + 24),
+                    pos + header_size,
+                )
+            }
+            _ => return Err(format!("Unsupported LZH header level {}", level)),
+        };
+
+        // Extended Headers
+        let mut dir = Vec::new();
+        let mut data_pos = data_pos;
+        while let Some(next_pos) = ext_pos {
+            let size = buf
+                .get(next_pos..next_pos + 2)
+                .map(|b| word(b, 0))
+                .ok_or_else(broken)?;
+            if size == 0 {
+                break;
+            }
+            if size < 3 {
+                return Err(broken());
+            }
+            let ext_start = next_pos + 2;
+            let ext = buf.get(ext_start..ext_start + size).ok_or_else(broken)?;
+            match ext[0] {
+                EXT_FILENAME => name = ext[1..size - 2].to_vec(),
+                EXT_DIRNAME => dir = ext[1..size - 2].to_vec(),
+                _ => (),
+            }
+            if level == 1 {
+                // level 1: extended headers are counted in the packed size
+                packed_size = packed_size.checked_sub(size).ok_or_else(broken)?;
+                data_pos += size;
+            }
+            ext_pos = Some(ext_start + size - 2);
+        }
+
+        // Directory (0xff separated) + File Name
+        let mut path: Vec<u8> = dir
+            .iter()
+            .map(|c| if *c == 0xff { b'/' } else { *c })
+            .collect();
+        if !path.is_empty() && !path.ends_with(b"/") {
+            path.push(b'/');
+        }
+        path.extend(name.iter().map(|c| if *c == b'\\' { b'/' } else { *c }));
+
+        Ok(Header {
+            method,
+            packed_size,
+            original_size,
+            crc,
+            name: String::from_utf8_lossy(&path).to_string(),
+            data_pos,
+        })
+    }
+
+    fn is_directory(&self) -> bool {
+        self.method == "-lhd-"
+    }
+}
+
+/// CRC-16 (Polynomial 0xA001)
+///
+fn crc16(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0;
+    for byte in data.iter() {
+        crc ^= *byte as u16;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xa001
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    crc
+}
+
+// ================================================================================
+//
+//  -lh4- .. -lh7- Decoder (LZSS + Static Huffman)
+//
+// ================================================================================
+
+/// Literals (256) + Match Lengths (3..=256)
+const NC: usize = 256 + 256 - 3 + 2;
+const NT: usize = 19;
+const TBIT: usize = 5;
+const CBIT: usize = 9;
+const THRESHOLD: usize = 3;
+/// Max Preallocation of decoded Data (the size in the header isn't trusted)
+const MAX_PREALLOC: usize = 4 * 1024 * 1024;
+
+/// MSB first Bit Reader (Helper struct)
+///
+/// 入力の終わり以降は0として読む。
+///
+struct BitReader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn bit(&mut self) -> usize {
+        let byte = self.buf.get(self.pos / 8).copied().unwrap_or(0);
+        self.pos += 1;
+        ((byte >> (7 - (self.pos - 1) % 8)) & 1) as usize
+    }
+
+    fn bits(&mut self, n: usize) -> usize {
+        (0..n).fold(0, |value, _| (value << 1) | self.bit())
+    }
+}
+
+/// Canonical Huffman Code (Helper struct)
+///
+enum Huffman {
+    /// Only one symbol (no bits)
+    Single(usize),
+    /// (count of each code length, symbols sorted by code length)
+    Canonical(Vec<usize>, Vec<usize>),
+}
+
+impl Huffman {
+    fn new(lengths: &[usize]) -> Result<Huffman, String> {
+        let mut count = [0usize; 17];
+        for len in lengths.iter() {
+            if *len > 16 {
+                return Err("Broken LZH data (code length)".to_string());
+            }
+            count[*len] += 1;
+        }
+        count[0] = 0;
+        let mut symbols = Vec::new();
+        for len in 1..=16 {
+            symbols.extend((0..lengths.len()).filter(|sym| lengths[*sym] == len));
+        }
+        Ok(Huffman::Canonical(count.to_vec(), symbols))
+    }
+
+    fn decode(&self, reader: &mut BitReader) -> Result<usize, String> {
+        match self {
+            Huffman::Single(sym) => Ok(*sym),
+            Huffman::Canonical(count, symbols) => {
+                let (mut code, mut first, mut index) = (0, 0, 0);
+                for n in count.iter().skip(1) {
+                    code |= reader.bit();
+                    if code < first + n {
+                        return Ok(symbols[index + code - first]);
+                    }
+                    index += n;
+                    first = (first + n) << 1;
+                    code <<= 1;
+                }
+                Err("Broken LZH data (huffman code)".to_string())
+            }
+        }
+    }
+}
+
+/// Read Code Lengths of Position / Length Table
+///
+fn read_pt_len(
+    reader: &mut BitReader,
+    nn: usize,
+    nbit: usize,
+    special: Option<usize>,
+) -> Result<Huffman, String> {
+    let n = reader.bits(nbit);
+    if n == 0 {
+        return Ok(Huffman::Single(reader.bits(nbit)));
+    }
+    if n > nn {
+        return Err("Broken LZH data (table size)".to_string());
+    }
+
+    let mut lengths = vec![0; nn];
+    let mut i = 0;
+    while i < n {
+        let mut len = reader.bits(3);
+        if len == 7 {
+            while reader.bit() == 1 {
+                len += 1;
+            }
+        }
+        lengths[i] = len;
+        i += 1;
+        if Some(i) == special {
+            i += reader.bits(2);
+        }
+    }
+    Huffman::new(&lengths)
+}
+
+/// Read Code Lengths of Character / Length Table
+///
+fn read_c_len(reader: &mut BitReader, pt: &Huffman) -> Result<Huffman, String> {
+    let n = reader.bits(CBIT);
+    if n == 0 {
+        return Ok(Huffman::Single(reader.bits(CBIT)));
+    }
+    if n > NC {
+        return Err("Broken LZH data (table size)".to_string());
+    }
+
+    let mut lengths = vec![0; NC];
+    let mut i = 0;
+    while i < n {
+        match pt.decode(reader)? {
+            0 => i += 1,
+            1 => i += reader.bits(4) + 3,
+            2 => i += reader.bits(CBIT) + 20,
+            c => {
+                lengths[i] = c - 2;
+                i += 1;
+            }
+        }
+    }
+    Huffman::new(&lengths)
+}
+
+/// Decode -lh4- .. -lh7-
+///
+/// # Argument
+///
+///   * `packed`        Compressed Data
+///   * `original_size` Size of decoded Data
+///   * `dicbit`        Dictionary Size (bit)
+///   * `pbit`          Bits of Position Table Size
+///
+fn decode(
+    packed: &[u8],
+    original_size: usize,
+    dicbit: usize,
+    pbit: usize,
+) -> Result<Vec<u8>, String> {
+    let np = dicbit + 1;
+    let mut reader = BitReader {
+        buf: packed,
+        pos: 0,
+    };
+    let mut data: Vec<u8> = Vec::with_capacity(original_size.min(MAX_PREALLOC));
+
+    let mut block_size = 0;
+    let mut c_table = Huffman::Single(0);
+    let mut p_table = Huffman::Single(0);
+    while data.len() < original_size {
+        if reader.pos > packed.len() * 8 {
+            return Err("Broken LZH data (unexpected end)".to_string());
+        }
+        if block_size == 0 {
+            block_size = reader.bits(16);
+            if block_size == 0 {
+                return Err("Broken LZH data (block size)".to_string());
+            }
+            let pt = read_pt_len(&mut reader, NT, TBIT, Some(3))?;
+            c_table = read_c_len(&mut reader, &pt)?;
+            p_table = read_pt_len(&mut reader, np, pbit, None)?;
+        }
+        block_size -= 1;
+
+        let c = c_table.decode(&mut reader)?;
+        if c < 256 {
+            data.push(c as u8);
+            continue;
+        }
+
+        let len = c - 256 + THRESHOLD;
+        let dist = match p_table.decode(&mut reader)? {
+            0 => 0,
+            p => (1 << (p - 1)) + reader.bits(p - 1),
+        } + 1;
+        for _ in 0..len {
+            // before the beginning of data is filled with spaces
+            let byte = data.len().checked_sub(dist).map_or(b' ', |n| data[n]);
+            data.push(byte);
+        }
+    }
+    data.truncate(original_size);
+    Ok(data)
+}
+
+// ================================================================================
+//
+//  Test Code
+//
+// ================================================================================
+#[cfg(test)]
+mod test {
+    use crate::archive::lzh::{crc16, decode, read_entries};
+
+    const HUBASIC_LZH: &str = "../../sample/HuBASIC_Format_2D.lzh";
+
+    /// Level 0 header (Helper function)
+    fn level0(method: &[u8], name: &[u8], original: &[u8], packed: &[u8]) -> Vec<u8> {
+        let mut h = vec![0u8; 22];
+        h[2..7].copy_from_slice(method);
+        h[7..11].copy_from_slice(&(packed.len() as u32).to_le_bytes());
+        h[11..15].copy_from_slice(&(original.len() as u32).to_le_bytes());
+        h[21] = name.len() as u8;
+        h.extend_from_slice(name);
+        h.extend_from_slice(&crc16(original).to_le_bytes());
+        h[0] = (h.len() - 2) as u8;
+        h.extend_from_slice(packed);
+        h
+    }
+
+    #[test]
+    fn test_stored() {
+        let mut buf = level0(b"-lh0-", b"DISK.D88", b"HELLO", b"HELLO");
+        buf.push(0);
+        let entries = read_entries(&buf).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].name, "DISK.D88");
+        assert_eq!(entries[0].data, b"HELLO");
+
+        let mut broken = level0(b"-lh0-", b"DISK.D88", b"HELLO", b"HELLX");
+        broken.push(0);
+        assert!(read_entries(&broken).is_err());
+    }
+
+    #[test]
+    fn test_lh5() {
+        let buf = std::fs::read(HUBASIC_LZH).unwrap();
+        let entries = read_entries(&buf).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].name, "DISKS/HUBASIC.D88");
+        assert_eq!(
+            entries[0].data,
+            std::fs::read("../../sample/HuBASIC_Format_2D.d88").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_lh5_broken() {
+        // Block size 0
+        assert_eq!(
+            decode(&[0; 8], 5, 13, 4),
+            Err("Broken LZH data (block size)".to_string())
+        );
+        let mut buf = level0(b"-lh5-", b"DISK.D88", b"HELLO", &[0; 8]);
+        buf.push(0);
+        assert!(read_entries(&buf).is_err());
+
+        // Original size 4GB
+        let mut buf = level0(b"-lh5-", b"DISK.D88", b"HELLO", &[0xff; 8]);
+        buf[11..15].copy_from_slice(&u32::MAX.to_le_bytes());
+        buf.push(0);
+        assert!(read_entries(&buf).is_err());
+        assert!(decode(&[0x00, 0x01], u32::MAX as usize, 13, 4).is_err());
+
+        // Header size too small for the CRC (level 0) and the base header (level 2)
+        let mut buf = level0(b"-lh0-", b"", b"", b"");
+        assert_eq!(buf.len(), 24);
+        buf[0] = 20;
+        assert_eq!(read_entries(&buf), Err("Broken LZH header".to_string()));
+        let mut buf = level0(b"-lh0-", b"DISK.D88", b"", b"");
+        buf[0] -= 1;
+        assert!(read_entries(&buf).is_err());
+        let mut buf = level0(b"-lh0-", b"", b"", b"");
+        buf[0..2].copy_from_slice(&10u16.to_le_bytes());
+        buf[20] = 2;
+        buf.extend_from_slice(&[0; 8]);
+        assert_eq!(read_entries(&buf), Err("Broken LZH header".to_string()));
+
+        // Truncated
+        let sample = std::fs::read(HUBASIC_LZH).unwrap();
+        for len in [10, 40, sample.len() / 2, sample.len() - 2] {
+            assert!(read_entries(&sample[..len]).is_err());
+        }
+
+        // Broken bytes (header and compressed data)
+        let expected = std::fs::read("../../sample/HuBASIC_Format_2D.d88").unwrap();
+        for pos in (0..64).chain((64..sample.len()).step_by(509)) {
+            let mut broken = sample.clone();
+            broken[pos] ^= 0x5a;
+            if let Ok(entries) = read_entries(&broken) {
+                // unused bits, name, ..
+                assert!(entries.iter().all(|entry| entry.data == expected));
+            }
+        }
+    }
+}
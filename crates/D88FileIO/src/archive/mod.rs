@@ -0,0 +1,238 @@
+//! Archives and Image Sources
+//!
+//! `.zip`、`.gz`、`.lzh` の中のディスクイメージと標準入力(`-`)を読み込む。
+//!
+//!   * `disk.d88`              File
+//!   * `-`                     Stdin
+//!   * `archive.zip`           All disk images in the archive
+//!   * `archive.zip:disk2.d88` One entry in the archive
+//!
+pub mod lzh;
+
+use flate2::read::MultiGzDecoder;
+use std::fs;
+use std::io::{self, Cursor, Read};
+use std::path::Path;
+
+use crate::fileio::D88FileIO;
+
+/// Archive Format
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Zip,
+    Gzip,
+    Lzh,
+}
+
+impl ArchiveFormat {
+    /// Archive Format from File Extension
+    ///
+    /// # Return
+    ///
+    ///   * Some(ArchiveFormat)  "*.zip", "*.gz", "*.lzh", "*.lha"
+    ///   * None                 Not an archive
+    ///
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<ArchiveFormat> {
+        let ext = path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+
+        match ext.as_str() {
+            "zip" => Some(ArchiveFormat::Zip),
+            "gz" => Some(ArchiveFormat::Gzip),
+            "lzh" | "lha" => Some(ArchiveFormat::Lzh),
+            _ => None,
+        }
+    }
+}
+
+/// File in Archive (or Source)
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub name: String,
+    pub data: Vec<u8>,
+}
+
+/// Split "archive.zip:disk2.d88" into Archive Path and Entry Name
+///
+/// アーカイブの拡張子の直後の':'で分ける(Windowsのドライブ名"C:"は分けない)。
+///
+/// # Return
+///
+///   * (path, Some(entry))  Archive and Entry Name
+///   * (path, None)         No entry name
+///
+pub fn split_source(source: &str) -> (&str, Option<&str>) {
+    for (pos, _) in source.match_indices(':') {
+        let (path, entry) = (&source[..pos], &source[pos + 1..]);
+        if ArchiveFormat::from_path(path).is_some() && !entry.is_empty() {
+            return (path, Some(entry));
+        }
+    }
+    (source, None)
+}
+
+/// Source is an Archive or Stdin (not a plain file)
+///
+pub fn is_archive_source(source: &str) -> bool {
+    let (path, _) = split_source(source);
+    source == "-" || ArchiveFormat::from_path(path).is_some()
+}
+
+/// Read all Files in Archive
+///
+/// # Argument
+///
+///   * `format` Archive Format
+///   * `name`   Archive File Name (for the entry name of ".gz")
+///   * `buf`    Archive
+///
+/// # Return
+///
+///   * Ok(Vec<Entry>)
+///   * Err(String)
+///
+pub fn read_archive(format: ArchiveFormat, name: &str, buf: &[u8]) -> Result<Vec<Entry>, String> {
+    match format {
+        ArchiveFormat::Zip => {
+            let mut zip = zip::ZipArchive::new(Cursor::new(buf)).map_err(|e| e.to_string())?;
+            let mut entries = Vec::new();
+            for idx in 0..zip.len() {
+                let mut file = zip.by_index(idx).map_err(|e| e.to_string())?;
+                if file.is_dir() {
+                    continue;
+                }
+                let mut data = Vec::new();
+                file.read_to_end(&mut data).map_err(|e| e.to_string())?;
+                entries.push(Entry {
+                    name: file.name().to_string(),
+                    data,
+                });
+            }
+            Ok(entries)
+        }
+        ArchiveFormat::Gzip => {
+            let mut data = Vec::new();
+            MultiGzDecoder::new(buf)
+                .read_to_end(&mut data)
+                .map_err(|e| e.to_string())?;
+            // "game.d88.gz" -> "game.d88"
+            let name = Path::new(name)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default();
+            Ok(vec![Entry { name, data }])
+        }
+        ArchiveFormat::Lzh => lzh::read_entries(buf),
+    }
+}
+
+/// Read Disk Images of Source
+///
+/// アーカイブでエントリ名がなければ、ディスクイメージ("*.d88", "*.d77")を全て返す。
+/// アーカイブ内のエントリ名は"archive.zip:disk2.d88"の形になる。
+///
+/// # Argument
+///
+///   * `source` File Path, "-" (Stdin), "archive.zip" or "archive.zip:disk2.d88"
+///
+/// # Return
+///
+///   * Ok(Vec<Entry>)
+///   * Err(String)
+///
+pub fn read_source(source: &str) -> Result<Vec<Entry>, String> {
+    if source == "-" {
+        let mut data = Vec::new();
+        io::stdin()
+            .read_to_end(&mut data)
+            .map_err(|e| e.to_string())?;
+        return Ok(vec![Entry {
+            name: source.to_string(),
+            data,
+        }]);
+    }
+
+    let (path, entry_name) = split_source(source);
+    let buf = fs::read(path).map_err(|_| format!("File Not Found \"{}\"", path))?;
+    let format = match ArchiveFormat::from_path(path) {
+        Some(format) => format,
+        None => {
+            return Ok(vec![Entry {
+                name: source.to_string(),
+                data: buf,
+            }])
+        }
+    };
+
+    let entries: Vec<Entry> = read_archive(format, path, &buf)?
+        .into_iter()
+        .filter(|entry| match entry_name {
+            Some(name) => {
+                entry.name.eq_ignore_ascii_case(name)
+                    || Path::new(&entry.name)
+                        .file_name()
+                        .is_some_and(|file_name| file_name.eq_ignore_ascii_case(name))
+            }
+            None => D88FileIO::image_format_from_path(&entry.name).is_some(),
+        })
+        .map(|entry| Entry {
+            name: format!("{}:{}", path, entry.name),
+            data: entry.data,
+        })
+        .collect();
+
+    if entries.is_empty() {
+        return Err(match entry_name {
+            Some(name) => format!("\"{}\" Not Found in \"{}\"", name, path),
+            None => format!("No disk image in \"{}\"", path),
+        });
+    }
+    Ok(entries)
+}
+
+// ================================================================================
+//
+//  Test Code
+//
+// ================================================================================
+#[cfg(test)]
+mod test {
+    use crate::archive::{read_archive, split_source, ArchiveFormat};
+    use std::io::Write;
+
+    #[test]
+    fn test_split_source() {
+        assert_eq!(
+            split_source("games.zip:disk2.d88"),
+            ("games.zip", Some("disk2.d88"))
+        );
+        assert_eq!(split_source("C:\\games\\a.zip"), ("C:\\games\\a.zip", None));
+        assert_eq!(split_source("a.d88"), ("a.d88", None));
+        assert_eq!(ArchiveFormat::from_path("A.LZH"), Some(ArchiveFormat::Lzh));
+    }
+
+    #[test]
+    fn test_read_archive() {
+        let data = std::fs::read("../../sample/HuBASIC_Format_2D.d88").unwrap();
+
+        let mut gz = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        gz.write_all(&data).unwrap();
+        let entries = read_archive(ArchiveFormat::Gzip, "x/a.d88.gz", &gz.finish().unwrap());
+        assert_eq!(entries.unwrap()[0].name, "a.d88");
+
+        let mut buf = std::io::Cursor::new(Vec::new());
+        let mut zip = zip::ZipWriter::new(&mut buf);
+        let options = zip::write::SimpleFileOptions::default();
+        zip.start_file("readme.txt", options).unwrap();
+        zip.write_all(b"README").unwrap();
+        zip.start_file("disk/b.d88", options).unwrap();
+        zip.write_all(&data).unwrap();
+        zip.finish().unwrap();
+
+        let entries = read_archive(ArchiveFormat::Zip, "a.zip", buf.get_ref()).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].name, "disk/b.d88");
+        assert_eq!(entries[1].data, data);
+    }
+}
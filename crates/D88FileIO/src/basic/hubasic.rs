@@ -0,0 +1,40 @@
+//! Hu-BASIC (Sharp X1)
+//!
+//! 行は「行の長さ(2バイト、この4バイトと終端の0x00を含む)、行番号(2バイト)、本体、0x00」。
+//! 最後は長さ0の行。コメントの `'` は `:'` として格納される。
+//!
+//! 中間コードの表は、サンプル(`sample/HuBASIC_Format_2D.d88` の StopTheClock.Bas と .Asc)で
+//! 確かめたものだけを登録している。表にないコードは `{xx}` と表示し、`tokenize()` で同じバイトに戻る。
+//! 確かめていないコードを推測で登録すると、違うキーワードを黙って表示してしまうので登録しない。
+//!
+//! 数値は 0x01-0x0a (0-9)、0x12 + 2バイト(整数)、0x0b + 2バイト(行番号)。
+//! 浮動小数点の定数はサンプルに無く形式を確かめていないので、小数点はそのまま文字として扱う。
+//!
+use crate::basic::{NumberFormat, Spec};
+
+/// Hu-BASIC Specification
+///
+pub const SPEC: Spec = Spec {
+    name: "Hu-BASIC",
+    tokens: TOKENS,
+    prefix: 0xfe,
+    linked: false,
+    header: None,
+    base_addr: 0,
+    apostrophe: b":'",
+    else_code: &[],
+    numbers: NumberFormat::HuBasic,
+};
+
+/// Intermediate Codes
+///
+const TOKENS: &[(u16, &str)] = &[
+    (0x80, "GOTO"),
+    (0x93, "IF"),
+    (0x98, "END"),
+    (0xca, "LABEL"),
+    (0xe2, "THEN"),
+    (0xf4, "="),
+    (0xf7, "+"),
+    (0xfe8b, "PLAY"),
+];
@@ -0,0 +1,867 @@
+//! Tokenized BASIC Programs
+//!
+//! 中間コード形式で保存されたBASICプログラムのリスト表示(detokenize)と、
+//! テキストから中間コードへの変換(tokenize)。
+//!
+//!   * `n88`      N88-BASIC (PC-8801)
+//!   * `hubasic`  Hu-BASIC (X1)
+//!   * `msx`      MSX-BASIC
+//!
+//! 表にない中間コードや制御コードは `{xx}` (16進)で表示し、tokenize はそれをそのまま書き戻す。
+//!
+pub mod hubasic;
+pub mod msx;
+pub mod n88;
+pub mod number;
+
+use encoding_rs::SHIFT_JIS;
+use std::collections::HashMap;
+
+use crate::basic::number::*;
+use crate::charset::Charset;
+
+/// BASIC Dialect
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    N88,
+    HuBasic,
+    Msx,
+}
+
+impl Dialect {
+    /// Dialect of Name ("n88", "hubasic", "msx")
+    ///
+    pub fn from_name(name: &str) -> Option<Dialect> {
+        match name.to_ascii_lowercase().as_str() {
+            "n88" | "n88basic" | "n88-basic" => Some(Dialect::N88),
+            "hubasic" | "hu-basic" => Some(Dialect::HuBasic),
+            "msx" | "msxbasic" | "msx-basic" => Some(Dialect::Msx),
+            _ => None,
+        }
+    }
+
+    /// Dialect of Filesystem ("Hu-BASIC", "N88-BASIC")
+    ///
+    pub fn from_filesystem(fs_name: &str) -> Option<Dialect> {
+        match fs_name {
+            "Hu-BASIC" => Some(Dialect::HuBasic),
+            "N88-BASIC" => Some(Dialect::N88),
+            _ => None,
+        }
+    }
+
+    /// Dialect Name
+    ///
+    pub fn get_name(&self) -> &'static str {
+        self.spec().name
+    }
+
+    fn spec(&self) -> &'static Spec {
+        match self {
+            Dialect::N88 => &n88::SPEC,
+            Dialect::HuBasic => &hubasic::SPEC,
+            Dialect::Msx => &msx::SPEC,
+        }
+    }
+}
+
+/// Numeric Constant Format
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumberFormat {
+    /// N88-BASIC (Microsoft BASIC-80, MBF)
+    Microsoft,
+    /// MSX-BASIC (Microsoft BASIC-80 codes, BCD)
+    MsxBcd,
+    /// Hu-BASIC
+    HuBasic,
+}
+
+/// Dialect Specification
+///
+pub struct Spec {
+    pub name: &'static str,
+    /// (Code, Keyword), 2 byte code is (`prefix` << 8 | code)
+    pub tokens: &'static [(u16, &'static str)],
+    /// Prefix of 2 byte Codes
+    pub prefix: u8,
+    /// true: Link to the next line, false: Line length
+    pub linked: bool,
+    /// First Byte of File
+    pub header: Option<u8>,
+    /// Address of the first line (`linked`)
+    pub base_addr: u16,
+    /// Stored Form of "'"
+    pub apostrophe: &'static [u8],
+    /// Stored Form of "ELSE"
+    pub else_code: &'static [u8],
+    pub numbers: NumberFormat,
+}
+
+impl Spec {
+    fn find_code(&self, code: u16) -> Option<&'static str> {
+        self.tokens
+            .iter()
+            .find(|(c, _)| *c == code)
+            .map(|(_, keyword)| *keyword)
+    }
+
+    fn find_keyword(&self, keyword: &str) -> Option<u16> {
+        self.tokens
+            .iter()
+            .find(|(_, k)| *k == keyword)
+            .map(|(code, _)| *code)
+    }
+}
+
+/// BASIC Line
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Line {
+    pub number: u16,
+    pub text: String,
+}
+
+/// Keywords followed by Line Numbers
+const LINE_KEYWORDS: [&str; 13] = [
+    "GOTO", "GOSUB", "THEN", "ELSE", "RESTORE", "RUN", "LIST", "LLIST", "DELETE", "RENUM",
+    "RESUME", "AUTO", "EDIT",
+];
+
+// ================================================================================
+//  Detokenize
+// ================================================================================
+
+/// Detokenize
+///
+/// # Argument
+///
+///   * `dialect` BASIC Dialect
+///   * `data`    Tokenized Program (File)
+///   * `charset` Character Set of Strings and Comments
+///
+/// # Return
+///
+///   * Ok(Vec<Line>)
+///   * Err(String)
+///
+pub fn detokenize(dialect: Dialect, data: &[u8], charset: Charset) -> Result<Vec<Line>, String> {
+    let spec = dialect.spec();
+    let mut pos = match spec.header {
+        Some(header) if data.first() == Some(&header) => 1,
+        _ => 0,
+    };
+
+    // (Offset of Body, Line Number)
+    let mut bodies = Vec::new();
+    let mut first_link = None;
+    loop {
+        let link = read_u16(data, pos).ok_or("Unexpected end of program")?;
+        if link == 0 {
+            break;
+        }
+        let number = read_u16(data, pos + 2).ok_or("Unexpected end of program")?;
+        let end = if spec.linked {
+            find_line_end(spec, data, pos + 4)
+        } else {
+            Some(pos + link as usize - 1).filter(|end| *end >= pos + 4)
+        };
+        let end = end
+            .filter(|end| data.get(*end) == Some(&0x00))
+            .ok_or(format!("Line {} is not terminated", number))?;
+        first_link.get_or_insert(link);
+        bodies.push((pos, number, &data[pos + 4..end]));
+        pos = end + 1;
+    }
+
+    // Address -> Line Number (for 0x0d Line Pointer)
+    let mut addr_map = HashMap::new();
+    if let (true, Some(first_link), Some((first_pos, _, _))) =
+        (spec.linked, first_link, bodies.first())
+    {
+        let second_pos = bodies.get(1).map_or(pos, |(pos, _, _)| *pos);
+        let base = first_link as i64 - (second_pos - first_pos) as i64;
+        // ポインタは行の先頭、または直前の0x00を指す
+        for (pos, number, _) in bodies.iter() {
+            addr_map.insert(base + (pos - first_pos) as i64, *number);
+        }
+        for (pos, number, _) in bodies.iter() {
+            addr_map
+                .entry(base + (pos - first_pos) as i64 - 1)
+                .or_insert(*number);
+        }
+    }
+
+    Ok(bodies
+        .iter()
+        .map(|(_, number, body)| Line {
+            number: *number,
+            text: detokenize_line(spec, body, charset, &addr_map),
+        })
+        .collect())
+}
+
+/// Detokenize a Line (Helper function)
+///
+fn detokenize_line(
+    spec: &Spec,
+    body: &[u8],
+    charset: Charset,
+    addr_map: &HashMap<i64, u16>,
+) -> String {
+    let mut text = Text::new(charset);
+    let mut pos = 0;
+    while pos < body.len() {
+        let byte = body[pos];
+
+        // String
+        if byte == b'"' {
+            let len = body[pos + 1..]
+                .iter()
+                .position(|c| *c == b'"')
+                .map_or(body.len() - pos, |len| len + 2);
+            text.push_raw(&body[pos..pos + len]);
+            pos += len;
+            continue;
+        }
+
+        // Comment "'", ELSE
+        if !spec.apostrophe.is_empty() && body[pos..].starts_with(spec.apostrophe) {
+            text.push_str("'");
+            text.push_raw(&body[pos + spec.apostrophe.len()..]);
+            break;
+        }
+        if !spec.else_code.is_empty() && body[pos..].starts_with(spec.else_code) {
+            text.push_str("ELSE");
+            pos += spec.else_code.len();
+            continue;
+        }
+
+        // Numeric Constant
+        if let Some((number, len)) = read_number(spec, &body[pos..], addr_map) {
+            text.push_str(&number);
+            pos += len;
+            continue;
+        }
+
+        // Keyword
+        if byte >= 0x80 {
+            let (code, len) = if byte == spec.prefix && pos + 1 < body.len() {
+                (((byte as u16) << 8) | body[pos + 1] as u16, 2)
+            } else {
+                (byte as u16, 1)
+            };
+            match spec.find_code(code) {
+                Some(keyword) => {
+                    text.push_str(keyword);
+                    pos += len;
+                    match keyword {
+                        "REM" => {
+                            text.push_raw(&body[pos..]);
+                            break;
+                        }
+                        "DATA" => {
+                            let len = data_len(&body[pos..]);
+                            text.push_raw(&body[pos..pos + len]);
+                            pos += len;
+                        }
+                        _ => {}
+                    }
+                }
+                None => {
+                    text.push_str(&format!("{{{:0width$x}}}", code, width = len * 2));
+                    pos += len;
+                }
+            }
+            continue;
+        }
+
+        if byte < 0x20 {
+            text.push_str(&format!("{{{:02x}}}", byte));
+        } else {
+            text.push_raw(&[byte]);
+        }
+        pos += 1;
+    }
+    text.finish()
+}
+
+/// End of Line (0x00 out of Numeric Constants and "") (Helper function)
+///
+fn find_line_end(spec: &Spec, data: &[u8], mut pos: usize) -> Option<usize> {
+    loop {
+        match *data.get(pos)? {
+            0x00 => return Some(pos),
+            b'"' => {
+                pos += 1;
+                while !matches!(data.get(pos)?, b'"' | 0x00) {
+                    pos += 1;
+                }
+                if data[pos] == b'"' {
+                    pos += 1;
+                }
+            }
+            code => pos += operand_len(spec, code) + 1,
+        }
+    }
+}
+
+/// Operand Length of Numeric Constant Code (Helper function)
+///
+fn operand_len(spec: &Spec, code: u8) -> usize {
+    match (spec.numbers, code) {
+        (NumberFormat::HuBasic, 0x0b | 0x12) => 2,
+        (NumberFormat::HuBasic, _) => 0,
+        (_, 0x0b..=0x0e | 0x1c) => 2,
+        (_, 0x0f) => 1,
+        (_, 0x1d) => 4,
+        (_, 0x1f) => 8,
+        _ => 0,
+    }
+}
+
+/// Length of DATA (until ':' out of "") (Helper function)
+///
+fn data_len(body: &[u8]) -> usize {
+    let mut in_string = false;
+    for (pos, byte) in body.iter().enumerate() {
+        match byte {
+            b'"' => in_string = !in_string,
+            b':' if !in_string => return pos,
+            _ => {}
+        }
+    }
+    body.len()
+}
+
+/// Read Numeric Constant (Helper function)
+///
+/// # Return
+///
+///   * Some((Text, Length))
+///   * None  Not a Numeric Constant
+///
+fn read_number(spec: &Spec, body: &[u8], addr_map: &HashMap<i64, u16>) -> Option<(String, usize)> {
+    let code = body[0];
+    let u16_at = |pos: usize| read_u16(body, pos);
+
+    if spec.numbers == NumberFormat::HuBasic {
+        return match code {
+            0x01..=0x0a => Some(((code - 1).to_string(), 1)),
+            0x0b | 0x12 => Some((u16_at(1)?.to_string(), 3)),
+            _ => None,
+        };
+    }
+
+    match code {
+        0x0b => Some((format!("&O{:o}", u16_at(1)?), 3)),
+        0x0c => Some((format!("&H{:X}", u16_at(1)?), 3)),
+        0x0d => {
+            let addr = u16_at(1)?;
+            let text = match addr_map.get(&(addr as i64)) {
+                Some(number) => number.to_string(),
+                None => format!("{{0d{:04x}}}", addr),
+            };
+            Some((text, 3))
+        }
+        0x0e => Some((u16_at(1)?.to_string(), 3)),
+        0x0f => Some((body.get(1)?.to_string(), 2)),
+        0x11..=0x1a => Some(((code - 0x11).to_string(), 1)),
+        0x1c => Some(((u16_at(1)? as i16).to_string(), 3)),
+        0x1d => Some((format_float(spec, body.get(1..5)?), 5)),
+        0x1f => Some((format_float(spec, body.get(1..9)?), 9)),
+        _ => None,
+    }
+}
+
+/// Format Single/Double Constant (Helper function)
+///
+/// 整数に見える単精度には "!"、単精度の桁数に収まる倍精度には "#" を付ける。
+///
+fn format_float(spec: &Spec, bytes: &[u8]) -> String {
+    let double = bytes.len() == 8;
+    let (single_digits, (sign, digits, exp), precision, exp_char) = match spec.numbers {
+        NumberFormat::MsxBcd => (6, bcd_to_digits(bytes), if double { 14 } else { 6 }, 'E'),
+        _ => {
+            let value = mbf_to_f64(bytes);
+            let precision = if double { 16 } else { 7 };
+            let (digits, exp) = f64_to_digits(value, precision);
+            (
+                7,
+                (value < 0.0, digits, exp),
+                precision as i32,
+                if double { 'D' } else { 'E' },
+            )
+        }
+    };
+
+    let text = format_decimal(sign, &digits, exp, precision, exp_char);
+    if !double && !text.contains(['.', 'E']) {
+        text + "!"
+    } else if double && digits.len() <= single_digits && !text.contains('D') {
+        text + "#"
+    } else {
+        text
+    }
+}
+
+fn read_u16(data: &[u8], pos: usize) -> Option<u16> {
+    Some(u16::from_le_bytes([*data.get(pos)?, *data.get(pos + 1)?]))
+}
+
+/// Listing Text (Helper struct)
+///
+/// 生のバイト列は文字セットで変換する(Shift-JISの2バイト文字が途切れないようにまとめて変換)。
+///
+struct Text {
+    charset: Charset,
+    text: String,
+    raw: Vec<u8>,
+}
+
+impl Text {
+    fn new(charset: Charset) -> Self {
+        Self {
+            charset,
+            text: String::new(),
+            raw: Vec::new(),
+        }
+    }
+
+    fn push_raw(&mut self, bytes: &[u8]) {
+        self.raw.extend_from_slice(bytes);
+    }
+
+    fn push_str(&mut self, s: &str) {
+        self.flush();
+        self.text.push_str(s);
+    }
+
+    fn flush(&mut self) {
+        let raw = std::mem::take(&mut self.raw);
+        self.text.push_str(&self.charset.decode(&raw));
+    }
+
+    fn finish(mut self) -> String {
+        self.flush();
+        self.text
+    }
+}
+
+// ================================================================================
+//  Tokenize
+// ================================================================================
+
+/// Tokenize
+///
+/// 1行は「行番号 本文」。文字列とコメントは Shift-JIS に変換する。
+/// `linked` な形式のリンクは`base_addr`から計算する(ロード時にBASICが張り直す)。
+///
+/// # Argument
+///
+///   * `dialect` BASIC Dialect
+///   * `text`    Program Text
+///
+/// # Return
+///
+///   * Ok(Vec<u8>) Tokenized Program (File)
+///   * Err(String)
+///
+pub fn tokenize(dialect: Dialect, text: &str) -> Result<Vec<u8>, String> {
+    let spec = dialect.spec();
+    let mut data = Vec::new();
+    if let Some(header) = spec.header {
+        data.push(header);
+    }
+
+    for line in text.lines() {
+        let line = line.trim_end_matches('\r').trim_start();
+        if line.is_empty() {
+            continue;
+        }
+        let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        let number: u16 = line[..digits]
+            .parse()
+            .map_err(|_| format!("No line number \"{}\"", line))?;
+        let rest = &line[digits..];
+        let rest = rest.strip_prefix(' ').unwrap_or(rest);
+
+        let body = tokenize_line(spec, &encode(rest)?)
+            .map_err(|mes| format!("{} at line {}", mes, number))?;
+        let len = 4 + body.len() + 1;
+        let link = if spec.linked {
+            (spec.base_addr as usize + data.len() - spec.header.map_or(0, |_| 1) + len) as u16
+        } else {
+            len as u16
+        };
+        data.extend_from_slice(&link.to_le_bytes());
+        data.extend_from_slice(&number.to_le_bytes());
+        data.extend_from_slice(&body);
+        data.push(0x00);
+    }
+    data.extend_from_slice(&[0x00, 0x00]);
+    Ok(data)
+}
+
+/// Text to Shift-JIS (Helper function)
+///
+fn encode(text: &str) -> Result<Vec<u8>, String> {
+    let text = text.replace('¥', "\\").replace('‾', "~");
+    let (bytes, _, had_errors) = SHIFT_JIS.encode(&text);
+    if had_errors {
+        return Err(format!("Can't encode \"{}\" to Shift-JIS", text));
+    }
+    Ok(bytes.into_owned())
+}
+
+/// Tokenize a Line (Helper function)
+///
+fn tokenize_line(spec: &Spec, line: &[u8]) -> Result<Vec<u8>, String> {
+    let mut keywords: Vec<(u16, &str)> = spec.tokens.to_vec();
+    keywords.sort_by_key(|(_, keyword)| std::cmp::Reverse(keyword.len()));
+
+    let mut body = Vec::new();
+    let mut pos = 0;
+    let mut in_ident = false;
+    let mut line_number_mode = false;
+    while pos < line.len() {
+        let byte = line[pos];
+
+        // String
+        if byte == b'"' {
+            let len = line[pos + 1..]
+                .iter()
+                .position(|c| *c == b'"')
+                .map_or(line.len() - pos, |len| len + 2);
+            body.extend_from_slice(&line[pos..pos + len]);
+            pos += len;
+            in_ident = false;
+            continue;
+        }
+
+        // Comment
+        if byte == b'\'' && !spec.apostrophe.is_empty() {
+            body.extend_from_slice(spec.apostrophe);
+            body.extend_from_slice(&line[pos + 1..]);
+            break;
+        }
+
+        // Escaped Code "{xx}"
+        if byte == b'{' {
+            if let Some(len) = line[pos..].iter().position(|c| *c == b'}') {
+                let hex = std::str::from_utf8(&line[pos + 1..pos + len]).unwrap_or("");
+                if !hex.is_empty() && hex.len().is_multiple_of(2) {
+                    let bytes: Option<Vec<u8>> = (0..hex.len())
+                        .step_by(2)
+                        .map(|idx| u8::from_str_radix(&hex[idx..idx + 2], 16).ok())
+                        .collect();
+                    if let Some(bytes) = bytes {
+                        body.extend(bytes);
+                        pos += len + 1;
+                        continue;
+                    }
+                }
+            }
+        }
+
+        // Numeric Constant
+        if !in_ident && (byte.is_ascii_digit() || byte == b'&' || byte == b'.') {
+            if let Some((bytes, len)) = tokenize_number(spec, &line[pos..], line_number_mode)? {
+                body.extend(bytes);
+                pos += len;
+                continue;
+            }
+        }
+
+        // Keyword
+        let upper = line[pos..].to_ascii_uppercase();
+        let keyword = keywords
+            .iter()
+            .find(|(_, keyword)| upper.starts_with(keyword.as_bytes()))
+            .copied()
+            .or_else(|| {
+                (byte == b'?')
+                    .then(|| spec.find_keyword("PRINT").map(|code| (code, "?")))
+                    .flatten()
+            });
+        if let Some((code, keyword)) = keyword {
+            if keyword == "ELSE" && !spec.else_code.is_empty() {
+                body.extend_from_slice(spec.else_code);
+            } else if keyword == "'" {
+                body.extend_from_slice(spec.apostrophe);
+                body.extend_from_slice(&line[pos + 1..]);
+                break;
+            } else if code > 0xff {
+                body.extend_from_slice(&code.to_be_bytes());
+            } else {
+                body.push(code as u8);
+            }
+            pos += keyword.len();
+            in_ident = false;
+            line_number_mode = LINE_KEYWORDS.contains(&keyword);
+
+            match keyword {
+                "REM" => {
+                    body.extend_from_slice(&line[pos..]);
+                    break;
+                }
+                "DATA" => {
+                    let len = data_len(&line[pos..]);
+                    body.extend_from_slice(&line[pos..pos + len]);
+                    pos += len;
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        if !(byte == b' ' || byte == b',' || byte == b'-') {
+            line_number_mode = false;
+        }
+        in_ident = byte.is_ascii_alphabetic() || (in_ident && byte.is_ascii_digit());
+        body.push(byte);
+        pos += 1;
+    }
+    Ok(body)
+}
+
+/// Tokenize Numeric Constant (Helper function)
+///
+/// # Return
+///
+///   * Ok(Some((Bytes, Length)))
+///   * Ok(None)     Not a Number ("." only etc.)
+///   * Err(String)  Overflow
+///
+fn tokenize_number(
+    spec: &Spec,
+    text: &[u8],
+    line_number_mode: bool,
+) -> Result<Option<(Vec<u8>, usize)>, String> {
+    let digits_len = |from: usize, radix: u32| {
+        text[from..]
+            .iter()
+            .take_while(|c| (**c as char).is_digit(radix))
+            .count()
+    };
+    let hu = spec.numbers == NumberFormat::HuBasic;
+
+    // &H, &O, &
+    if text[0] == b'&' {
+        if hu {
+            return Ok(None);
+        }
+        let (code, radix, from) = match text.get(1).map(|c| c.to_ascii_uppercase()) {
+            Some(b'H') => (0x0c, 16, 2),
+            Some(b'O') => (0x0b, 8, 2),
+            _ => (0x0b, 8, 1),
+        };
+        let len = digits_len(from, radix);
+        if len == 0 {
+            return Ok(None);
+        }
+        let digits = std::str::from_utf8(&text[from..from + len]).unwrap_or("");
+        let value = u16::from_str_radix(digits, radix).map_err(|_| "Overflow")?;
+        let mut bytes = vec![code];
+        bytes.extend_from_slice(&value.to_le_bytes());
+        return Ok(Some((bytes, from + len)));
+    }
+
+    // Integer Part, Fraction, Exponent, Suffix
+    let int_len = digits_len(0, 10);
+    let mut len = int_len;
+    let mut is_float = false;
+    if !line_number_mode && text.get(len) == Some(&b'.') && !hu {
+        len += 1 + digits_len(len + 1, 10);
+        is_float = true;
+    }
+    if len == 0 || (len == 1 && is_float) {
+        return Ok(None);
+    }
+    let mut exp_char = None;
+    if !line_number_mode && !hu {
+        if let Some(c @ (b'E' | b'D' | b'e' | b'd')) = text.get(len) {
+            let sign = matches!(text.get(len + 1), Some(b'+' | b'-')) as usize;
+            let exp_len = digits_len(len + 1 + sign, 10);
+            if exp_len > 0 {
+                exp_char = Some(c.to_ascii_uppercase());
+                len += 1 + sign + exp_len;
+                is_float = true;
+            }
+        }
+    }
+    let suffix = if hu {
+        None
+    } else {
+        text.get(len).copied().filter(|c| b"%!#".contains(c))
+    };
+    let number = std::str::from_utf8(&text[..len]).unwrap_or("");
+    let total = len + suffix.is_some() as usize;
+
+    // Line Number, Integer
+    if line_number_mode && !is_float {
+        let value: u16 = number.parse().map_err(|_| "Line number overflow")?;
+        let code = if hu { 0x0b } else { 0x0e };
+        let mut bytes = vec![code];
+        bytes.extend_from_slice(&value.to_le_bytes());
+        return Ok(Some((bytes, len)));
+    }
+    if hu {
+        let value: u16 = match number.parse() {
+            Ok(value) => value,
+            Err(_) => return Ok(None),
+        };
+        let bytes = if value <= 9 {
+            vec![value as u8 + 1]
+        } else {
+            let mut bytes = vec![0x12];
+            bytes.extend_from_slice(&value.to_le_bytes());
+            bytes
+        };
+        return Ok(Some((bytes, len)));
+    }
+    if !is_float && suffix != Some(b'!') && suffix != Some(b'#') {
+        if let Ok(value) = number.parse::<u32>() {
+            if value <= 32767 {
+                let bytes = match value {
+                    0..=9 => vec![0x11 + value as u8],
+                    10..=255 => vec![0x0f, value as u8],
+                    _ => {
+                        let mut bytes = vec![0x1c];
+                        bytes.extend_from_slice(&(value as u16).to_le_bytes());
+                        bytes
+                    }
+                };
+                return Ok(Some((bytes, total)));
+            }
+        }
+        if suffix == Some(b'%') {
+            return Err(format!("Overflow \"{}\"", number));
+        }
+    }
+
+    // Single, Double
+    let (digits, exp) = split_decimal(number).ok_or(format!("Bad number \"{}\"", number))?;
+    let single_digits = if spec.numbers == NumberFormat::MsxBcd {
+        6
+    } else {
+        7
+    };
+    let double = suffix == Some(b'#')
+        || exp_char == Some(b'D')
+        || (suffix != Some(b'!') && digits.len() > single_digits);
+    let size = if double { 8 } else { 4 };
+    let encoded = match spec.numbers {
+        NumberFormat::MsxBcd => digits_to_bcd(false, &digits, exp, size),
+        _ => {
+            let value: f64 = number
+                .to_ascii_uppercase()
+                .replace('D', "E")
+                .parse()
+                .unwrap_or(0.0);
+            f64_to_mbf(value, size)
+        }
+    }
+    .ok_or(format!("Overflow \"{}\"", number))?;
+
+    let mut bytes = vec![if double { 0x1f } else { 0x1d }];
+    bytes.extend(encoded);
+    Ok(Some((bytes, total)))
+}
+
+// ================================================================================
+//
+//  Test Code
+//
+// ================================================================================
+#[cfg(test)]
+mod test {
+    use crate::basic::{detokenize, tokenize, Dialect};
+    use crate::charset::Charset;
+    use crate::fileio::D88FileIO;
+    use crate::filesystem::detect;
+
+    const HUBASIC: &str = "../../sample/HuBASIC_Format_2D.d88";
+
+    #[test]
+    fn test_hubasic() {
+        let d88fileio = D88FileIO::open(HUBASIC);
+        let fs = detect(&d88fileio.disk).unwrap();
+        let bas = fs.read(&d88fileio.disk, "StopTheClock.Bas").unwrap();
+        let asc = fs.read(&d88fileio.disk, "StopTheClock.Asc").unwrap();
+        let asc: String = asc
+            .split(|byte| *byte == b'\r')
+            .filter(|line| !line.is_empty())
+            .map(|line| Charset::Sjis.decode(line) + "\n")
+            .collect();
+
+        let lines = detokenize(Dialect::HuBasic, &bas, Charset::Sjis).unwrap();
+        let listing: String = lines
+            .iter()
+            .map(|line| format!("{} {}\n", line.number, line.text))
+            .collect();
+        assert_eq!(listing, asc);
+        assert!(listing.starts_with("100 '****"));
+
+        assert_eq!(tokenize(Dialect::HuBasic, &listing).unwrap(), bas);
+    }
+
+    #[test]
+    fn test_hubasic_codes() {
+        // Length (2 byte), Line Number (2 byte), Body, 0x00 .. 0x0000
+        let data = tokenize(Dialect::HuBasic, "10 IF A=3 THEN GOTO200\n").unwrap();
+        let body = [
+            0x93, b' ', b'A', 0xf4, 0x04, b' ', 0xe2, b' ', 0x80, 0x0b, 0xc8, 0x00,
+        ];
+        assert_eq!(&data[..4], &[(body.len() + 5) as u8, 0x00, 10, 0x00]);
+        assert_eq!(&data[4..4 + body.len()], &body);
+        assert_eq!(&data[4 + body.len()..], &[0x00, 0x00, 0x00]);
+
+        // Integer 0-9 (1 byte), 10- (0x12 + 2 byte), Comment ":'"
+        let data = tokenize(Dialect::HuBasic, "20 A=A+12345 'X\n").unwrap();
+        assert_eq!(
+            &data[4..data.len() - 3],
+            &[b'A', 0xf4, b'A', 0xf7, 0x12, 0x39, 0x30, b' ', b':', b'\'', b'X']
+        );
+
+        // Codes not in the table are "{xx}" and tokenized back as is
+        let text = "30 {81}A{fe01}:PLAY\"C\":END\n";
+        let data = tokenize(Dialect::HuBasic, text).unwrap();
+        assert_eq!(&data[4..8], &[0x81, b'A', 0xfe, 0x01]);
+        assert_eq!(&data[data.len() - 4..], &[0x98, 0x00, 0x00, 0x00]);
+        let lines = detokenize(Dialect::HuBasic, &data, Charset::Sjis).unwrap();
+        assert_eq!(format!("{} {}\n", lines[0].number, lines[0].text), text);
+
+        // No float constant code: the decimal point stays a character
+        let data = tokenize(Dialect::HuBasic, "40 A=1.5\n").unwrap();
+        assert_eq!(&data[4..data.len() - 3], &[b'A', 0xf4, 0x02, b'.', 0x06]);
+        let lines = detokenize(Dialect::HuBasic, &data, Charset::Sjis).unwrap();
+        assert_eq!(lines[0].text, "A=1.5");
+    }
+
+    #[test]
+    fn test_n88_msx() {
+        let text = "10 FOR I=1 TO 300:PRINT I*1.5;&HFF;\"ｱｲｳ\":NEXT\n\
+                    20 IF A>=40000! THEN 10 ELSE GOSUB 30,40 ' コメント\n\
+                    30 X#=3.1415926535898:DATA 1,\"A\",B:Y=1E+20\n";
+        for dialect in [Dialect::N88, Dialect::Msx] {
+            let data = tokenize(dialect, text).unwrap();
+            assert_eq!(data[0], 0xff);
+            let lines = detokenize(dialect, &data, Charset::Sjis).unwrap();
+            let listing: String = lines
+                .iter()
+                .map(|line| format!("{} {}\n", line.number, line.text))
+                .collect();
+            assert_eq!(listing, text);
+            assert_eq!(tokenize(dialect, &listing).unwrap(), data);
+        }
+
+        // 10 PRINT 1.5 (MBF), 2 bytes "PRINT" = 0x91
+        let data = tokenize(Dialect::N88, "10 PRINT 1.5").unwrap();
+        assert_eq!(
+            &data[5..],
+            &[0x91, 0x20, 0x1d, 0x00, 0x00, 0x40, 0x81, 0x00, 0x00, 0x00]
+        );
+    }
+}
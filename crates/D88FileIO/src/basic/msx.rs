@@ -0,0 +1,198 @@
+//! MSX-BASIC
+//!
+//! 行は「次の行のアドレス(2バイト)、行番号(2バイト)、本体、0x00」。
+//! ファイルの先頭は 0xff、プログラムは 0x8001 から置かれる。
+//! 関数は 0xff を前置した2バイトの中間コード。数値の浮動小数点はBCD。
+//!
+use crate::basic::{NumberFormat, Spec};
+
+/// MSX-BASIC Specification
+///
+pub const SPEC: Spec = Spec {
+    name: "MSX-BASIC",
+    tokens: TOKENS,
+    prefix: 0xff,
+    linked: true,
+    header: Some(0xff),
+    base_addr: 0x8001,
+    apostrophe: &[b':', 0x8f, 0xe6],
+    else_code: &[b':', 0xa1],
+    numbers: NumberFormat::MsxBcd,
+};
+
+/// Intermediate Codes
+///
+const TOKENS: &[(u16, &str)] = &[
+    (0x81, "END"),
+    (0x82, "FOR"),
+    (0x83, "NEXT"),
+    (0x84, "DATA"),
+    (0x85, "INPUT"),
+    (0x86, "DIM"),
+    (0x87, "READ"),
+    (0x88, "LET"),
+    (0x89, "GOTO"),
+    (0x8a, "RUN"),
+    (0x8b, "IF"),
+    (0x8c, "RESTORE"),
+    (0x8d, "GOSUB"),
+    (0x8e, "RETURN"),
+    (0x8f, "REM"),
+    (0x90, "STOP"),
+    (0x91, "PRINT"),
+    (0x92, "CLEAR"),
+    (0x93, "LIST"),
+    (0x94, "NEW"),
+    (0x95, "ON"),
+    (0x96, "WAIT"),
+    (0x97, "DEF"),
+    (0x98, "POKE"),
+    (0x99, "CONT"),
+    (0x9a, "CSAVE"),
+    (0x9b, "CLOAD"),
+    (0x9c, "OUT"),
+    (0x9d, "LPRINT"),
+    (0x9e, "LLIST"),
+    (0x9f, "CLS"),
+    (0xa0, "WIDTH"),
+    (0xa1, "ELSE"),
+    (0xa2, "TRON"),
+    (0xa3, "TROFF"),
+    (0xa4, "SWAP"),
+    (0xa5, "ERASE"),
+    (0xa6, "ERROR"),
+    (0xa7, "RESUME"),
+    (0xa8, "DELETE"),
+    (0xa9, "AUTO"),
+    (0xaa, "RENUM"),
+    (0xab, "DEFSTR"),
+    (0xac, "DEFINT"),
+    (0xad, "DEFSNG"),
+    (0xae, "DEFDBL"),
+    (0xaf, "LINE"),
+    (0xb0, "OPEN"),
+    (0xb1, "FIELD"),
+    (0xb2, "GET"),
+    (0xb3, "PUT"),
+    (0xb4, "CLOSE"),
+    (0xb5, "LOAD"),
+    (0xb6, "MERGE"),
+    (0xb7, "FILES"),
+    (0xb8, "LSET"),
+    (0xb9, "RSET"),
+    (0xba, "SAVE"),
+    (0xbb, "LFILES"),
+    (0xbc, "CIRCLE"),
+    (0xbd, "COLOR"),
+    (0xbe, "DRAW"),
+    (0xbf, "PAINT"),
+    (0xc0, "BEEP"),
+    (0xc1, "PLAY"),
+    (0xc2, "PSET"),
+    (0xc3, "PRESET"),
+    (0xc4, "SOUND"),
+    (0xc5, "SCREEN"),
+    (0xc6, "VPOKE"),
+    (0xc7, "SPRITE"),
+    (0xc8, "VDP"),
+    (0xc9, "BASE"),
+    (0xca, "CALL"),
+    (0xcb, "TIME"),
+    (0xcc, "KEY"),
+    (0xcd, "MAX"),
+    (0xce, "MOTOR"),
+    (0xcf, "BLOAD"),
+    (0xd0, "BSAVE"),
+    (0xd1, "DSKO$"),
+    (0xd2, "SET"),
+    (0xd3, "NAME"),
+    (0xd4, "KILL"),
+    (0xd5, "IPL"),
+    (0xd6, "COPY"),
+    (0xd7, "CMD"),
+    (0xd8, "LOCATE"),
+    (0xd9, "TO"),
+    (0xda, "THEN"),
+    (0xdb, "TAB("),
+    (0xdc, "STEP"),
+    (0xdd, "USR"),
+    (0xde, "FN"),
+    (0xdf, "SPC("),
+    (0xe0, "NOT"),
+    (0xe1, "ERL"),
+    (0xe2, "ERR"),
+    (0xe3, "STRING$"),
+    (0xe4, "USING"),
+    (0xe5, "INSTR"),
+    (0xe6, "'"),
+    (0xe7, "VARPTR"),
+    (0xe8, "CSRLIN"),
+    (0xe9, "ATTR$"),
+    (0xea, "DSKI$"),
+    (0xeb, "OFF"),
+    (0xec, "INKEY$"),
+    (0xed, "POINT"),
+    (0xee, ">"),
+    (0xef, "="),
+    (0xf0, "<"),
+    (0xf1, "+"),
+    (0xf2, "-"),
+    (0xf3, "*"),
+    (0xf4, "/"),
+    (0xf5, "^"),
+    (0xf6, "AND"),
+    (0xf7, "OR"),
+    (0xf8, "XOR"),
+    (0xf9, "EQV"),
+    (0xfa, "IMP"),
+    (0xfb, "MOD"),
+    (0xfc, "\\"),
+    (0xff81, "LEFT$"),
+    (0xff82, "RIGHT$"),
+    (0xff83, "MID$"),
+    (0xff84, "SGN"),
+    (0xff85, "INT"),
+    (0xff86, "ABS"),
+    (0xff87, "SQR"),
+    (0xff88, "RND"),
+    (0xff89, "SIN"),
+    (0xff8a, "LOG"),
+    (0xff8b, "EXP"),
+    (0xff8c, "COS"),
+    (0xff8d, "TAN"),
+    (0xff8e, "ATN"),
+    (0xff8f, "FRE"),
+    (0xff90, "INP"),
+    (0xff91, "POS"),
+    (0xff92, "LEN"),
+    (0xff93, "STR$"),
+    (0xff94, "VAL"),
+    (0xff95, "ASC"),
+    (0xff96, "CHR$"),
+    (0xff97, "PEEK"),
+    (0xff98, "VPEEK"),
+    (0xff99, "SPACE$"),
+    (0xff9a, "OCT$"),
+    (0xff9b, "HEX$"),
+    (0xff9c, "LPOS"),
+    (0xff9d, "BIN$"),
+    (0xff9e, "CINT"),
+    (0xff9f, "CSNG"),
+    (0xffa0, "CDBL"),
+    (0xffa1, "FIX"),
+    (0xffa2, "STICK"),
+    (0xffa3, "STRIG"),
+    (0xffa4, "PDL"),
+    (0xffa5, "PAD"),
+    (0xffa6, "DSKF"),
+    (0xffa7, "FPOS"),
+    (0xffa8, "CVI"),
+    (0xffa9, "CVS"),
+    (0xffaa, "CVD"),
+    (0xffab, "EOF"),
+    (0xffac, "LOC"),
+    (0xffad, "LOF"),
+    (0xffae, "MKI$"),
+    (0xffaf, "MKS$"),
+    (0xffb0, "MKD$"),
+];
@@ -0,0 +1,189 @@
+//! N88-BASIC (PC-8801)
+//!
+//! 行は「次の行のアドレス(2バイト)、行番号(2バイト)、本体、0x00」。
+//! ファイルの先頭は 0xff。関数は 0xff を前置した2バイトの中間コード。
+//! 数値の浮動小数点は Microsoft Binary Format。
+//!
+use crate::basic::{NumberFormat, Spec};
+
+/// N88-BASIC Specification
+///
+pub const SPEC: Spec = Spec {
+    name: "N88-BASIC",
+    tokens: TOKENS,
+    prefix: 0xff,
+    linked: true,
+    header: Some(0xff),
+    base_addr: 0x8021,
+    apostrophe: &[b':', 0x8f, 0xe9],
+    else_code: &[b':', 0x9f],
+    numbers: NumberFormat::Microsoft,
+};
+
+/// Intermediate Codes
+///
+const TOKENS: &[(u16, &str)] = &[
+    (0x81, "END"),
+    (0x82, "FOR"),
+    (0x83, "NEXT"),
+    (0x84, "DATA"),
+    (0x85, "INPUT"),
+    (0x86, "DIM"),
+    (0x87, "READ"),
+    (0x88, "LET"),
+    (0x89, "GOTO"),
+    (0x8a, "RUN"),
+    (0x8b, "IF"),
+    (0x8c, "RESTORE"),
+    (0x8d, "GOSUB"),
+    (0x8e, "RETURN"),
+    (0x8f, "REM"),
+    (0x90, "STOP"),
+    (0x91, "PRINT"),
+    (0x92, "CLEAR"),
+    (0x93, "LIST"),
+    (0x94, "NEW"),
+    (0x95, "ON"),
+    (0x96, "WAIT"),
+    (0x97, "DEF"),
+    (0x98, "POKE"),
+    (0x99, "CONT"),
+    (0x9a, "OUT"),
+    (0x9b, "LPRINT"),
+    (0x9c, "LLIST"),
+    (0x9d, "CONSOLE"),
+    (0x9e, "WIDTH"),
+    (0x9f, "ELSE"),
+    (0xa0, "TRON"),
+    (0xa1, "TROFF"),
+    (0xa2, "SWAP"),
+    (0xa3, "ERASE"),
+    (0xa4, "EDIT"),
+    (0xa5, "ERROR"),
+    (0xa6, "RESUME"),
+    (0xa7, "DELETE"),
+    (0xa8, "AUTO"),
+    (0xa9, "RENUM"),
+    (0xaa, "DEFSTR"),
+    (0xab, "DEFINT"),
+    (0xac, "DEFSNG"),
+    (0xad, "DEFDBL"),
+    (0xae, "LINE"),
+    (0xaf, "WHILE"),
+    (0xb0, "WEND"),
+    (0xb1, "CALL"),
+    (0xb5, "WRITE"),
+    (0xb6, "COMMON"),
+    (0xb7, "CHAIN"),
+    (0xb8, "OPTION"),
+    (0xb9, "RANDOMIZE"),
+    (0xba, "DSKO$"),
+    (0xbb, "OPEN"),
+    (0xbc, "FIELD"),
+    (0xbd, "GET"),
+    (0xbe, "PUT"),
+    (0xbf, "SET"),
+    (0xc0, "CLOSE"),
+    (0xc1, "LOAD"),
+    (0xc2, "MERGE"),
+    (0xc3, "FILES"),
+    (0xc4, "NAME"),
+    (0xc5, "KILL"),
+    (0xc6, "LSET"),
+    (0xc7, "RSET"),
+    (0xc8, "SAVE"),
+    (0xc9, "LFILES"),
+    (0xca, "MON"),
+    (0xcb, "COLOR"),
+    (0xcc, "CIRCLE"),
+    (0xcd, "COPY"),
+    (0xce, "CLS"),
+    (0xcf, "PSET"),
+    (0xd0, "PRESET"),
+    (0xd1, "PAINT"),
+    (0xd2, "TERM"),
+    (0xd3, "SCREEN"),
+    (0xd4, "BLOAD"),
+    (0xd5, "BSAVE"),
+    (0xd6, "LOCATE"),
+    (0xd7, "BEEP"),
+    (0xd8, "ROLL"),
+    (0xd9, "HELP"),
+    (0xdb, "KANJI"),
+    (0xdc, "TO"),
+    (0xdd, "THEN"),
+    (0xde, "TAB("),
+    (0xdf, "STEP"),
+    (0xe0, "USR"),
+    (0xe1, "FN"),
+    (0xe2, "SPC("),
+    (0xe3, "NOT"),
+    (0xe4, "ERL"),
+    (0xe5, "ERR"),
+    (0xe6, "STRING$"),
+    (0xe7, "USING"),
+    (0xe8, "INSTR"),
+    (0xe9, "'"),
+    (0xea, "VARPTR"),
+    (0xeb, "ATTR$"),
+    (0xec, "DSKI$"),
+    (0xed, "SRQ"),
+    (0xee, "OFF"),
+    (0xef, "INKEY$"),
+    (0xf0, ">"),
+    (0xf1, "="),
+    (0xf2, "<"),
+    (0xf3, "+"),
+    (0xf4, "-"),
+    (0xf5, "*"),
+    (0xf6, "/"),
+    (0xf7, "^"),
+    (0xf8, "AND"),
+    (0xf9, "OR"),
+    (0xfa, "XOR"),
+    (0xfb, "EQV"),
+    (0xfc, "IMP"),
+    (0xfd, "MOD"),
+    (0xfe, "\\"),
+    (0xff81, "LEFT$"),
+    (0xff82, "RIGHT$"),
+    (0xff83, "MID$"),
+    (0xff84, "SGN"),
+    (0xff85, "INT"),
+    (0xff86, "ABS"),
+    (0xff87, "SQR"),
+    (0xff88, "RND"),
+    (0xff89, "SIN"),
+    (0xff8a, "LOG"),
+    (0xff8b, "EXP"),
+    (0xff8c, "COS"),
+    (0xff8d, "TAN"),
+    (0xff8e, "ATN"),
+    (0xff8f, "FRE"),
+    (0xff90, "INP"),
+    (0xff91, "POS"),
+    (0xff92, "LEN"),
+    (0xff93, "STR$"),
+    (0xff94, "VAL"),
+    (0xff95, "ASC"),
+    (0xff96, "CHR$"),
+    (0xff97, "PEEK"),
+    (0xff98, "SPACE$"),
+    (0xff99, "OCT$"),
+    (0xff9a, "HEX$"),
+    (0xff9b, "LPOS"),
+    (0xff9c, "CINT"),
+    (0xff9d, "CSNG"),
+    (0xff9e, "CDBL"),
+    (0xff9f, "FIX"),
+    (0xffa0, "CVI"),
+    (0xffa1, "CVS"),
+    (0xffa2, "CVD"),
+    (0xffa3, "EOF"),
+    (0xffa4, "LOC"),
+    (0xffa5, "LOF"),
+    (0xffa6, "FPOS"),
+    (0xffa7, "MKI$"),
+    (0xffa8, "MKS$"),
+    (0xffa9, "MKD$"),
+];
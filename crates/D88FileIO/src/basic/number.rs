@@ -0,0 +1,284 @@
+//! Numeric Constants in Tokenized BASIC
+//!
+//!   * Microsoft Binary Format (N88-BASIC)  単精度4バイト、倍精度8バイト
+//!   * BCD (MSX-BASIC)                      単精度4バイト(6桁)、倍精度8バイト(14桁)
+//!
+
+/// Decode Microsoft Binary Format
+///
+/// 下位バイトから仮数部、最後のバイトが指数部(0x80 バイアス、0なら値は0)。
+/// 仮数部の最上位ビットは符号で、ケチ表現の1は省略されている。
+///
+/// # Argument
+///
+///   * `bytes` 4 byte (Single) or 8 byte (Double)
+///
+pub fn mbf_to_f64(bytes: &[u8]) -> f64 {
+    let (mantissa, exp) = bytes.split_at(bytes.len() - 1);
+    if exp[0] == 0 {
+        return 0.0;
+    }
+
+    let sign = mantissa[mantissa.len() - 1] & 0x80 != 0;
+    let mut value = 0.0_f64;
+    for (idx, byte) in mantissa.iter().enumerate() {
+        let byte = if idx == mantissa.len() - 1 {
+            byte | 0x80
+        } else {
+            *byte
+        };
+        value = value / 256.0 + byte as f64;
+    }
+    // value = 0.1xxx (binary) x 256
+    let value = value / 256.0 * 2.0_f64.powi(exp[0] as i32 - 128);
+    if sign {
+        -value
+    } else {
+        value
+    }
+}
+
+/// Encode Microsoft Binary Format
+///
+/// # Argument
+///
+///   * `value` Value
+///   * `len`   4 (Single) or 8 (Double)
+///
+/// # Return
+///
+///   * Some(Vec<u8>)
+///   * None  Overflow
+///
+pub fn f64_to_mbf(value: f64, len: usize) -> Option<Vec<u8>> {
+    let mut bytes = vec![0; len];
+    if value == 0.0 {
+        return Some(bytes);
+    }
+
+    let bits = (len as i32 - 1) * 8;
+    let (mantissa, mut exp) = frexp(value.abs());
+    // 0.5 <= mantissa < 1 (rounding may carry to 1.0)
+    let mut int_mantissa = (mantissa * 2.0_f64.powi(bits)).round() as u64;
+    if int_mantissa >> bits != 0 {
+        int_mantissa >>= 1;
+        exp += 1;
+    }
+    let exp = exp + 128;
+    if !(1..=255).contains(&exp) {
+        return None;
+    }
+
+    for (idx, byte) in bytes.iter_mut().take(len - 1).enumerate() {
+        *byte = (int_mantissa >> (idx * 8)) as u8;
+    }
+    bytes[len - 2] &= 0x7f;
+    if value < 0.0 {
+        bytes[len - 2] |= 0x80;
+    }
+    bytes[len - 1] = exp as u8;
+    Some(bytes)
+}
+
+/// Split into Mantissa (0.5 <= m < 1) and Exponent (Helper function)
+///
+fn frexp(value: f64) -> (f64, i32) {
+    let mut exp = value.log2().floor() as i32 + 1;
+    let mut mantissa = value / 2.0_f64.powi(exp);
+    while mantissa >= 1.0 {
+        mantissa /= 2.0;
+        exp += 1;
+    }
+    while mantissa < 0.5 {
+        mantissa *= 2.0;
+        exp -= 1;
+    }
+    (mantissa, exp)
+}
+
+/// Decode MSX BCD
+///
+/// 先頭バイトは符号(bit7)と指数(0x40 バイアス)、続いてBCDで仮数部 0.dddd...。
+///
+/// # Return
+///
+///   * (Negative, Digits, Exponent)  value = 0.Digits x 10^Exponent
+///
+pub fn bcd_to_digits(bytes: &[u8]) -> (bool, String, i32) {
+    let sign = bytes[0] & 0x80 != 0;
+    let exp = (bytes[0] & 0x7f) as i32 - 0x40;
+    let digits: String = bytes[1..]
+        .iter()
+        .flat_map(|byte| [byte >> 4, byte & 0x0f])
+        .map(|digit| char::from(b'0' + digit.min(9)))
+        .collect();
+    if bytes[0] & 0x7f == 0 {
+        return (false, String::new(), 0);
+    }
+    (sign, digits.trim_end_matches('0').to_string(), exp)
+}
+
+/// Encode MSX BCD
+///
+/// # Argument
+///
+///   * `sign`   Negative
+///   * `digits` Significant Digits (no leading zero)
+///   * `exp`    value = 0.Digits x 10^Exponent
+///   * `len`    4 (Single) or 8 (Double)
+///
+pub fn digits_to_bcd(sign: bool, digits: &str, exp: i32, len: usize) -> Option<Vec<u8>> {
+    let mut bytes = vec![0; len];
+    let digits = digits.trim_end_matches('0');
+    if digits.is_empty() {
+        return Some(bytes);
+    }
+    if !(-0x3f..=0x3f).contains(&exp) {
+        return None;
+    }
+
+    bytes[0] = (exp + 0x40) as u8 | if sign { 0x80 } else { 0x00 };
+    for (idx, digit) in digits.bytes().take((len - 1) * 2).enumerate() {
+        let digit = digit - b'0';
+        bytes[1 + idx / 2] |= if idx.is_multiple_of(2) {
+            digit << 4
+        } else {
+            digit
+        };
+    }
+    Some(bytes)
+}
+
+/// Split Decimal Text into Digits and Exponent
+///
+/// "12.5E3" -> ("125", 5) (0.125 x 10^5)
+///
+/// # Return
+///
+///   * Some((Digits, Exponent))  Digits has no leading/trailing zero ("" for zero)
+///   * None                      Not a number
+///
+pub fn split_decimal(text: &str) -> Option<(String, i32)> {
+    let text = text.to_ascii_uppercase().replace('D', "E");
+    let (mantissa, exp) = match text.split_once('E') {
+        Some((mantissa, exp)) => (mantissa.to_string(), exp.parse::<i32>().ok()?),
+        None => (text.clone(), 0),
+    };
+    let (int_part, frac_part) = mantissa.split_once('.').unwrap_or((&mantissa, ""));
+    if !(int_part.bytes().chain(frac_part.bytes())).all(|c| c.is_ascii_digit())
+        || int_part.len() + frac_part.len() == 0
+    {
+        return None;
+    }
+
+    let all_digits = format!("{}{}", int_part, frac_part);
+    let leading = all_digits.len() - all_digits.trim_start_matches('0').len();
+    let digits = all_digits.trim_matches('0').to_string();
+    if digits.is_empty() {
+        return Some((digits, 0));
+    }
+    Some((digits, int_part.len() as i32 - leading as i32 + exp))
+}
+
+/// Format Decimal for Listing
+///
+/// BASICの LIST と同じく、`precision`桁に収まり 10^-2 以上なら固定小数点、
+/// それ以外は指数表記("1.5E+20")にする。"0.5" は ".5" と表示する。
+///
+/// # Argument
+///
+///   * `sign`      Negative
+///   * `digits`    Significant Digits (value = 0.Digits x 10^Exponent)
+///   * `exp`       Exponent
+///   * `precision` Digits of Fixed Point (Single 7, Double 16)
+///   * `exp_char`  'E' or 'D'
+///
+pub fn format_decimal(
+    sign: bool,
+    digits: &str,
+    exp: i32,
+    precision: i32,
+    exp_char: char,
+) -> String {
+    if digits.is_empty() {
+        return "0".to_string();
+    }
+
+    let sign = if sign { "-" } else { "" };
+    let len = digits.len() as i32;
+    let text = if exp > precision || exp < -1 {
+        let (first, rest) = digits.split_at(1);
+        let point = if rest.is_empty() { "" } else { "." };
+        let exp = exp - 1;
+        format!(
+            "{}{}{}{}{}{:02}",
+            first,
+            point,
+            rest,
+            exp_char,
+            if exp < 0 { '-' } else { '+' },
+            exp.abs()
+        )
+    } else if exp <= 0 {
+        format!(".{}{}", "0".repeat((-exp) as usize), digits)
+    } else if exp >= len {
+        format!("{}{}", digits, "0".repeat((exp - len) as usize))
+    } else {
+        let (int_part, frac_part) = digits.split_at(exp as usize);
+        format!("{}.{}", int_part, frac_part)
+    };
+    format!("{}{}", sign, text)
+}
+
+/// Significant Digits of f64 (rounded to `precision`)
+///
+/// # Return
+///
+///   * (Digits, Exponent)  value = 0.Digits x 10^Exponent
+///
+pub fn f64_to_digits(value: f64, precision: usize) -> (String, i32) {
+    if value == 0.0 {
+        return (String::new(), 0);
+    }
+    // "d.dddde-x"
+    let text = format!("{:.*e}", precision - 1, value.abs());
+    let (mantissa, exp) = text.split_once('e').unwrap_or((&text, "0"));
+    let digits: String = mantissa.chars().filter(|c| c.is_ascii_digit()).collect();
+    let exp: i32 = exp.parse().unwrap_or(0);
+    (digits.trim_end_matches('0').to_string(), exp + 1)
+}
+
+// ================================================================================
+//
+//  Test Code
+//
+// ================================================================================
+#[cfg(test)]
+mod test {
+    use crate::basic::number::*;
+
+    #[test]
+    fn test_mbf() {
+        // 1.0 = 00 00 00 81, -0.5 = 00 00 80 80, 10 = 00 00 20 84
+        assert_eq!(mbf_to_f64(&[0x00, 0x00, 0x00, 0x81]), 1.0);
+        assert_eq!(mbf_to_f64(&[0x00, 0x00, 0x80, 0x80]), -0.5);
+        assert_eq!(f64_to_mbf(10.0, 4), Some(vec![0x00, 0x00, 0x20, 0x84]));
+        let pi = f64_to_mbf(std::f64::consts::PI, 8).unwrap();
+        assert!((mbf_to_f64(&pi) - std::f64::consts::PI).abs() < 1e-15);
+    }
+
+    #[test]
+    fn test_decimal() {
+        assert_eq!(split_decimal("12.5E3"), Some(("125".to_string(), 5)));
+        assert_eq!(split_decimal(".005"), Some(("5".to_string(), -2)));
+        assert_eq!(format_decimal(false, "125", 5, 7, 'E'), "12500");
+        assert_eq!(format_decimal(true, "5", 0, 7, 'E'), "-.5");
+        assert_eq!(format_decimal(false, "15", 21, 7, 'E'), "1.5E+20");
+        assert_eq!(format_decimal(false, "1", -2, 7, 'E'), "1E-03");
+
+        let bcd = digits_to_bcd(false, "314159", 1, 4).unwrap();
+        assert_eq!(bcd, vec![0x41, 0x31, 0x41, 0x59]);
+        assert_eq!(bcd_to_digits(&bcd), (false, "314159".to_string(), 1));
+        assert_eq!(f64_to_digits(0.1, 7), ("1".to_string(), 0));
+    }
+}
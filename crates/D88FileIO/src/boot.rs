@@ -0,0 +1,304 @@
+//! Boot Sector (IPL) Identification
+//!
+//! トラック0 面0 セクタ1 のブートセクタから、起動方式を判定する。
+//!
+//!   * X1 IPL          先頭が Hu-BASIC のディレクトリエントリ(32byte)の形で、属性 0x01、拡張子 "Sys" (または "IPL")
+//!   * MSX             先頭が JP (0xc3)/ JR(0x18)/ 0xeb/ 0xe9 で、BPB が正しい
+//!   * PC-8801         N88-BASIC のディスクで、ブートセクタ(0xc000 にロード)にコードがある
+//!   * CP/M            CP/M のディスク、またはブートセクタに "CP/M" があり、コードがある
+//!   * Unknown         上のどれでもないが、ブートセクタにコードがある
+//!   * Non-Bootable    ブートセクタが無い、または全て同じ値
+//!
+use crate::charset::Charset;
+use crate::classify::is_fill;
+use crate::disk::Disk;
+use crate::filesystem::cpm::Cpm;
+use crate::filesystem::n88::N88Basic;
+use crate::filesystem::FileSystem;
+
+/// BIOS Parameter Block (MSX-DOS)
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bpb {
+    pub oem_name: String,
+    pub bytes_per_sector: u16,
+    pub sectors_per_cluster: u8,
+    pub reserved_sectors: u16,
+    pub fats: u8,
+    pub root_entries: u16,
+    pub total_sectors: u16,
+    pub media: u8,
+    pub sectors_per_fat: u16,
+    pub sectors_per_track: u16,
+    pub heads: u16,
+}
+
+/// Boot Loader
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Boot {
+    /// X1 IPL (File Name, Size, Load Address, Exec Address, Start Sector)
+    X1Ipl {
+        name: String,
+        size: u16,
+        load_addr: u16,
+        exec_addr: u16,
+        start: u16,
+    },
+    /// MSX Boot Sector with BPB
+    Msx(Bpb),
+    /// PC-8801 N88-BASIC Boot (Sector Size)
+    N88 {
+        size: usize,
+    },
+    /// CP/M Cold Boot Loader (Sector Size, Text including "CP/M")
+    Cpm {
+        size: usize,
+        text: Option<String>,
+    },
+    /// Unknown Code (Sector Size)
+    Unknown {
+        size: usize,
+    },
+    NonBootable,
+}
+
+impl Boot {
+    /// Identify Boot Loader
+    ///
+    /// # Argument
+    ///
+    ///   * `disk` Disk
+    ///
+    pub fn identify(disk: &Disk) -> Boot {
+        let sector = disk
+            .get_track(0, 0)
+            .and_then(|track| track.sector_tbl.iter().find(|s| s.header.sector == 1));
+        let data = match sector {
+            Some(sector) if !is_fill(&sector.data) => &sector.data,
+            _ => return Boot::NonBootable,
+        };
+
+        if let Some(boot) = Boot::x1_ipl(data) {
+            return boot;
+        }
+        if let Some(bpb) = Boot::bpb(data) {
+            return Boot::Msx(bpb);
+        }
+        if N88Basic.detect(disk) {
+            return Boot::N88 { size: data.len() };
+        }
+        let text = find_text(data, b"CP/M");
+        if text.is_some() || Cpm::x1turbo().detect(disk) {
+            return Boot::Cpm {
+                size: data.len(),
+                text,
+            };
+        }
+        Boot::Unknown { size: data.len() }
+    }
+
+    /// Boot Loader Name
+    ///
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Boot::X1Ipl { .. } => "X1 IPL",
+            Boot::Msx(_) => "MSX",
+            Boot::N88 { .. } => "PC-8801 N88-BASIC",
+            Boot::Cpm { .. } => "CP/M",
+            Boot::Unknown { .. } => "Unknown",
+            Boot::NonBootable => "Non-Bootable",
+        }
+    }
+
+    /// Parsed Fields
+    ///
+    /// # Return
+    ///
+    ///   * Vec<(Field Name, Value)>
+    ///
+    pub fn get_fields(&self) -> Vec<(&'static str, String)> {
+        match self {
+            Boot::X1Ipl {
+                name,
+                size,
+                load_addr,
+                exec_addr,
+                start,
+            } => vec![
+                ("Name", name.clone()),
+                ("Size", format!("{} byte", size)),
+                ("Load", format!("{:04x}", load_addr)),
+                ("Exec", format!("{:04x}", exec_addr)),
+                ("Start", format!("LBA {}", start)),
+            ],
+            Boot::Msx(bpb) => vec![
+                ("OEM", bpb.oem_name.clone()),
+                ("BytesPerSector", bpb.bytes_per_sector.to_string()),
+                ("SectorsPerCluster", bpb.sectors_per_cluster.to_string()),
+                ("ReservedSectors", bpb.reserved_sectors.to_string()),
+                ("FATs", bpb.fats.to_string()),
+                ("RootEntries", bpb.root_entries.to_string()),
+                ("TotalSectors", bpb.total_sectors.to_string()),
+                ("Media", format!("{:02x}", bpb.media)),
+                ("SectorsPerFAT", bpb.sectors_per_fat.to_string()),
+                ("SectorsPerTrack", bpb.sectors_per_track.to_string()),
+                ("Heads", bpb.heads.to_string()),
+            ],
+            Boot::N88 { size } => vec![
+                ("Load", "c000".to_string()),
+                ("Exec", "c000".to_string()),
+                ("Size", format!("{} byte", size)),
+            ],
+            Boot::Cpm { size, text } => {
+                let mut fields = vec![("Size", format!("{} byte", size))];
+                if let Some(text) = text {
+                    fields.push(("Text", text.clone()));
+                }
+                fields
+            }
+            Boot::Unknown { size } => vec![("Size", format!("{} byte", size))],
+            Boot::NonBootable => vec![],
+        }
+    }
+
+    /// X1 IPL Record (Helper function)
+    ///
+    fn x1_ipl(data: &[u8]) -> Option<Boot> {
+        let ext = data.get(14..17)?;
+        if data[0] != 0x01 || !(ext.eq_ignore_ascii_case(b"Sys") || ext == b"IPL") {
+            return None;
+        }
+        let word = |pos: usize| u16::from_le_bytes([data[pos], data[pos + 1]]);
+        Some(Boot::X1Ipl {
+            name: Charset::Sjis.decode(&data[1..14]).trim_end().to_string(),
+            size: word(18),
+            load_addr: word(20),
+            exec_addr: word(22),
+            start: word(30),
+        })
+    }
+
+    /// MSX-DOS BPB (Helper function)
+    ///
+    fn bpb(data: &[u8]) -> Option<Bpb> {
+        if data.len() < 0x1e || !matches!(data[0], 0xeb | 0xe9 | 0xc3 | 0x18) {
+            return None;
+        }
+        let word = |pos: usize| u16::from_le_bytes([data[pos], data[pos + 1]]);
+        let bpb = Bpb {
+            oem_name: Charset::Ascii.decode(&data[3..11]).trim_end().to_string(),
+            bytes_per_sector: word(0x0b),
+            sectors_per_cluster: data[0x0d],
+            reserved_sectors: word(0x0e),
+            fats: data[0x10],
+            root_entries: word(0x11),
+            total_sectors: word(0x13),
+            media: data[0x15],
+            sectors_per_fat: word(0x16),
+            sectors_per_track: word(0x18),
+            heads: word(0x1a),
+        };
+
+        let is_valid = [128, 256, 512, 1024].contains(&bpb.bytes_per_sector)
+            && bpb.sectors_per_cluster.is_power_of_two()
+            && (1..=2).contains(&bpb.fats)
+            && bpb.root_entries > 0
+            && bpb.total_sectors > 0
+            && bpb.media >= 0xf0
+            && bpb.sectors_per_fat > 0
+            && (1..=2).contains(&bpb.heads);
+        if is_valid {
+            Some(bpb)
+        } else {
+            None
+        }
+    }
+}
+
+/// Printable Text around `pattern` (Helper function)
+///
+fn find_text(data: &[u8], pattern: &[u8]) -> Option<String> {
+    let pos = data
+        .windows(pattern.len())
+        .position(|window| window == pattern)?;
+    let is_print = |byte: &u8| (0x20..0x7f).contains(byte);
+    let start = data[..pos]
+        .iter()
+        .rposition(|byte| !is_print(byte))
+        .map_or(0, |pos| pos + 1);
+    let end = data[pos..]
+        .iter()
+        .position(|byte| !is_print(byte))
+        .map_or(data.len(), |len| pos + len);
+    Some(
+        String::from_utf8_lossy(&data[start..end])
+            .trim()
+            .to_string(),
+    )
+}
+
+// ================================================================================
+//
+//  Test Code
+//
+// ================================================================================
+#[cfg(test)]
+mod test {
+    use crate::boot::Boot;
+    use crate::disk::Disk;
+    use crate::fileio::D88FileIO;
+    use crate::raw::{blank, Geometry};
+
+    const HUBASIC: &str = "../../sample/HuBASIC_Format_2D.d88";
+
+    fn with_boot_sector(data: &[u8]) -> Disk {
+        let mut disk = blank(&Geometry::from_name("2D").unwrap(), 0xe5);
+        disk.track_tbl[0].sector_tbl[0].data[..data.len()].copy_from_slice(data);
+        disk
+    }
+
+    #[test]
+    fn test_identify() {
+        let d88fileio = D88FileIO::open(HUBASIC);
+        assert_eq!(Boot::identify(&d88fileio.disk), Boot::NonBootable);
+
+        let mut ipl = vec![0x01];
+        ipl.extend(b"GAME         Sys");
+        ipl.extend([0x00, 0x00, 0x40, 0x00, 0x00, 0x00, 0x10]);
+        ipl.extend([0u8; 6]);
+        ipl.extend([0x20, 0x00]);
+        let boot = Boot::identify(&with_boot_sector(&ipl));
+        assert_eq!(
+            boot,
+            Boot::X1Ipl {
+                name: "GAME".to_string(),
+                size: 0x4000,
+                load_addr: 0x0000,
+                exec_addr: 0x1000,
+                start: 0x0020,
+            }
+        );
+        assert_eq!(boot.get_fields()[2], ("Load", "0000".to_string()));
+
+        let mut msx = vec![0xeb, 0xfe, 0x90];
+        msx.extend(b"MSX_04  ");
+        msx.extend([0x00, 0x02, 0x02, 0x01, 0x00, 0x02, 0x70, 0x00]);
+        msx.extend([0xa0, 0x05, 0xf9, 0x03, 0x00, 0x09, 0x00, 0x02, 0x00]);
+        match Boot::identify(&with_boot_sector(&msx)) {
+            Boot::Msx(bpb) => {
+                assert_eq!(bpb.oem_name, "MSX_04");
+                assert_eq!(bpb.total_sectors, 1440);
+                assert_eq!(bpb.media, 0xf9);
+            }
+            boot => panic!("{:?}", boot),
+        }
+
+        let boot = Boot::identify(&with_boot_sector(b"\xf3\x31\x00\x01 CP/M boot"));
+        assert_eq!(boot.get_name(), "CP/M");
+        assert_eq!(boot.get_fields()[1], ("Text", "CP/M boot".to_string()));
+
+        let boot = Boot::identify(&with_boot_sector(&[0xf3, 0xc9]));
+        assert_eq!(boot, Boot::Unknown { size: 256 });
+    }
+}
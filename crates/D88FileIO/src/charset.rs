@@ -0,0 +1,158 @@
+//! Character Sets of Retro PCs
+//!
+//! 16進ダンプの文字欄とディスク名の表示に使う。
+//!
+//!   * `ascii`     0x20-0x7e
+//!   * `jisx0201`  ASCII (0x5c は ¥、0x7e は ‾) + 半角カナ 0xa1-0xdf
+//!   * `sjis`      Shift-JIS (2バイト文字 + 半角カナ)
+//!   * `pc8801`    JIS X 0201 + グラフィック文字 (罫線、ブロック、トランプ記号)
+//!   * `x1`        `pc8801` と同じ表
+//!
+use encoding_rs::SHIFT_JIS;
+
+/// Character Set
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Charset {
+    #[default]
+    Ascii,
+    JisX0201,
+    Sjis,
+    Pc8801,
+    X1,
+}
+
+/// Graphic Characters 0x80-0x9f
+const GRAPHIC_80: [char; 32] = [
+    '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█', '▏', '▎', '▍', '▌', '▋', '▊', '▉', '┼', //
+    '┴', '┬', '┤', '├', '▔', '─', '│', '▕', '┌', '┐', '└', '┘', '╭', '╮', '╰', '╯',
+];
+
+/// Graphic Characters 0xe0-0xf0 (0xf1- are Kanji, not shown)
+const GRAPHIC_E0: [char; 17] = [
+    '═', '╞', '╪', '╡', '◢', '◣', '◥', '◤', '♠', '♥', '♦', '♣', '•', '○', '╱', '╲', //
+    '╳',
+];
+
+impl Charset {
+    /// Character Set of Name ("ascii", "jisx0201", "sjis", "pc8801", "x1")
+    ///
+    pub fn from_name(name: &str) -> Option<Charset> {
+        match name.to_ascii_lowercase().as_str() {
+            "ascii" => Some(Charset::Ascii),
+            "jisx0201" | "jis" => Some(Charset::JisX0201),
+            "sjis" | "shift-jis" | "shift_jis" => Some(Charset::Sjis),
+            "pc8801" | "pc88" => Some(Charset::Pc8801),
+            "x1" => Some(Charset::X1),
+            _ => None,
+        }
+    }
+
+    /// Character Set Name
+    ///
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Charset::Ascii => "ascii",
+            Charset::JisX0201 => "jisx0201",
+            Charset::Sjis => "sjis",
+            Charset::Pc8801 => "pc8801",
+            Charset::X1 => "x1",
+        }
+    }
+
+    /// Render Bytes (one cell per byte)
+    ///
+    /// 表示できないバイトは '.' になる。
+    /// Shift-JIS の2バイト文字は1バイト目のセルに全角1文字、2バイト目のセルは空文字列になる。
+    ///
+    /// # Argument
+    ///
+    ///   * `data` Bytes
+    ///
+    /// # Return
+    ///
+    ///   * Vec<String> (`data.len()` cells)
+    ///
+    pub fn render(&self, data: &[u8]) -> Vec<String> {
+        let mut cells = Vec::with_capacity(data.len());
+        let mut idx = 0;
+        while idx < data.len() {
+            if *self == Charset::Sjis {
+                if let Some(c) = data.get(idx..idx + 2).and_then(decode_sjis_pair) {
+                    cells.push(c.to_string());
+                    cells.push(String::new());
+                    idx += 2;
+                    continue;
+                }
+            }
+            cells.push(self.get_char(data[idx]).to_string());
+            idx += 1;
+        }
+        cells
+    }
+
+    /// Decode Bytes to String
+    ///
+    pub fn decode(&self, data: &[u8]) -> String {
+        self.render(data).concat()
+    }
+
+    /// Character of a Byte ('.' if not printable)
+    ///
+    pub fn get_char(&self, byte: u8) -> char {
+        let is_jis = *self != Charset::Ascii && *self != Charset::Sjis;
+        let is_graphic = matches!(self, Charset::Pc8801 | Charset::X1);
+        match byte {
+            0x5c if is_jis => '¥',
+            0x7e if is_jis => '‾',
+            0x20..=0x7e => byte as char,
+            0xa1..=0xdf if *self != Charset::Ascii => {
+                char::from_u32(0xff61 + (byte - 0xa1) as u32).unwrap_or('.')
+            }
+            0x80..=0x9f if is_graphic => GRAPHIC_80[(byte - 0x80) as usize],
+            0xe0..=0xf0 if is_graphic => GRAPHIC_E0[(byte - 0xe0) as usize],
+            _ => '.',
+        }
+    }
+}
+
+/// Shift-JIS Double-byte Character (Helper function)
+///
+fn decode_sjis_pair(pair: &[u8]) -> Option<char> {
+    let (lead, trail) = (pair[0], pair[1]);
+    if !matches!(lead, 0x81..=0x9f | 0xe0..=0xfc) || !matches!(trail, 0x40..=0x7e | 0x80..=0xfc) {
+        return None;
+    }
+    let text = SHIFT_JIS.decode_without_bom_handling_and_without_replacement(pair)?;
+    let mut chars = text.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if !c.is_control() => Some(c),
+        _ => None,
+    }
+}
+
+// ================================================================================
+//
+//  Test Code
+//
+// ================================================================================
+#[cfg(test)]
+mod test {
+    use crate::charset::Charset;
+
+    #[test]
+    fn test_render() {
+        let data = [b'A', 0x5c, 0xb1, 0x87, 0x00];
+        assert_eq!(Charset::Ascii.decode(&data), "A\\...");
+        assert_eq!(Charset::JisX0201.decode(&data), "A¥ｱ..");
+        assert_eq!(Charset::Pc8801.decode(&data), "A¥ｱ█.");
+        assert_eq!(Charset::from_name("X1"), Some(Charset::X1));
+
+        // "日本" + "ｱ", odd lead byte at the end
+        let sjis = [0x93, 0xfa, 0x96, 0x7b, 0xb1, 0x93];
+        assert_eq!(
+            Charset::Sjis.render(&sjis),
+            vec!["日", "", "本", "", "ｱ", "."]
+        );
+    }
+}
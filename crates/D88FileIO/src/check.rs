@@ -0,0 +1,222 @@
+use std::collections::HashMap;
+use std::mem;
+
+use crate::disk::Disk;
+use crate::format::{D88_Header, D88_SectorHdr};
+use crate::format::{DISK_TYPE_1D, DISK_TYPE_1DD, DISK_TYPE_2D, DISK_TYPE_2DD, DISK_TYPE_2HD};
+
+/// Severity of a Problem
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Unusual, but can be intended (e.g. Copy Protection)
+    Warning,
+    /// Broken Image
+    Error,
+}
+
+impl Severity {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Severity::Warning => "Warning",
+            Severity::Error => "Error",
+        }
+    }
+}
+
+/// Problem found by Integrity Check
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    pub severity: Severity,
+    /// Index to `track_tbl` (None: Disk Header)
+    pub track_idx: Option<usize>,
+    pub message: String,
+}
+
+/// Integrity Check
+///
+/// ヘッダ、トラックオフセットテーブル、セクタヘッダの整合性を調べる。
+/// コピープロテクトの解析は `protect` で行う。
+///
+/// # Argument
+///
+///   * `disk`      Disk
+///   * `file_size` Size of the D88 File (None: not checked)
+///
+/// # Return
+///
+///   * Vec<Problem>  (Empty: OK)
+///
+pub fn check(disk: &Disk, file_size: Option<u64>) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    let mut disk_problem = |severity, message: String| {
+        problems.push(Problem {
+            severity,
+            track_idx: None,
+            message,
+        })
+    };
+    let header = &disk.header;
+
+    // Disk Header
+    if !matches!(header.write_protect, 0x00 | 0x10) {
+        disk_problem(
+            Severity::Warning,
+            format!("Unknown write protect flag ({:02x}h)", header.write_protect),
+        );
+    }
+    if ![
+        DISK_TYPE_2D,
+        DISK_TYPE_2DD,
+        DISK_TYPE_2HD,
+        DISK_TYPE_1D,
+        DISK_TYPE_1DD,
+    ]
+    .contains(&header.disk_type)
+    {
+        disk_problem(
+            Severity::Error,
+            format!("Unknown disk type ({:02x}h)", header.disk_type),
+        );
+    }
+    if let Some(file_size) = file_size {
+        if header.disk_size as u64 != file_size {
+            disk_problem(
+                Severity::Error,
+                format!(
+                    "Disk size ({} byte) differs from file size ({} byte)",
+                    header.disk_size, file_size
+                ),
+            );
+        }
+    }
+
+    // Track Offset Table
+    let header_size = mem::size_of::<D88_Header>() as u32;
+    let mut track_problem = |severity, idx: usize, message: String| {
+        problems.push(Problem {
+            severity,
+            track_idx: Some(idx),
+            message,
+        })
+    };
+    for (idx, offset) in header.track_offset_tbl.iter().enumerate() {
+        if *offset == 0 {
+            continue;
+        }
+        if *offset < header_size || *offset >= header.disk_size {
+            track_problem(
+                Severity::Error,
+                idx,
+                format!("Track offset ({:x}h) is out of disk", offset),
+            );
+        } else if idx >= disk.track_tbl.len() {
+            track_problem(Severity::Error, idx, "Track can't be read".to_string());
+        }
+    }
+
+    // Sector Headers
+    for (idx, track) in disk.track_tbl.iter().enumerate() {
+        if let Some(last) = track.sector_tbl.last() {
+            let end = last.offset + last.data.len() as u64;
+            if end > header.disk_size as u64 {
+                track_problem(
+                    Severity::Error,
+                    idx,
+                    format!("Track data ends at {:x}h, beyond disk size", end),
+                );
+            }
+        }
+
+        let mut numbers: Vec<u16> = track
+            .sector_tbl
+            .iter()
+            .map(|sector| sector.header.number_of_sec)
+            .collect();
+        numbers.dedup();
+        if numbers.len() > 1 {
+            track_problem(
+                Severity::Warning,
+                idx,
+                format!("Sectors disagree on the number of sectors {:?}", numbers),
+            );
+        }
+
+        let mut ids: HashMap<(u8, u8, u8, u8), usize> = HashMap::new();
+        for sector in track.sector_tbl.iter() {
+            let hdr: &D88_SectorHdr = &sector.header;
+            *ids.entry((hdr.track, hdr.side, hdr.sector, hdr.sector_size))
+                .or_default() += 1;
+
+            if sector.get_sector_size_byte() != Some(hdr.size_of_data as usize) {
+                track_problem(
+                    Severity::Warning,
+                    idx,
+                    format!(
+                        "Sector({}) N={}, data size {} byte",
+                        hdr.sector, hdr.sector_size, hdr.size_of_data
+                    ),
+                );
+            }
+            if !sector.is_status_ok() {
+                track_problem(
+                    Severity::Warning,
+                    idx,
+                    format!("Sector({}) {}", hdr.sector, sector.get_status()),
+                );
+            }
+        }
+
+        let mut duplicates: Vec<_> = ids.into_iter().filter(|(_, count)| *count > 1).collect();
+        duplicates.sort();
+        for ((_, _, sector, _), count) in duplicates {
+            track_problem(
+                Severity::Warning,
+                idx,
+                format!("Sector({}) ID appears {} times", sector, count),
+            );
+        }
+    }
+
+    problems.sort_by_key(|problem| problem.track_idx.map_or(0, |idx| idx + 1));
+    problems
+}
+
+// ================================================================================
+//
+//  Test Code
+//
+// ================================================================================
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use crate::check::{check, Severity};
+    use crate::fileio::D88FileIO;
+
+    const HUBASIC: &str = "../../sample/HuBASIC_Format_2D.d88";
+
+    #[test]
+    fn test_check_ok() {
+        let d88fileio = D88FileIO::open(HUBASIC);
+        let file_size = fs::metadata(HUBASIC).unwrap().len();
+        assert!(check(&d88fileio.disk, Some(file_size)).is_empty());
+    }
+
+    #[test]
+    fn test_check_problems() {
+        let mut disk = D88FileIO::open(HUBASIC).disk;
+        disk.header.disk_type = 0x55;
+        disk.track_tbl[3].sector_tbl[2].header.status = 0xb0;
+        let sector = disk.track_tbl[3].sector_tbl[1].header.sector;
+        disk.track_tbl[3].sector_tbl[0].header.sector = sector;
+
+        let problems = check(&disk, Some(1000));
+        assert_eq!(problems.len(), 4);
+        assert_eq!(problems[0].severity, Severity::Error);
+        assert_eq!(problems[0].track_idx, None);
+        assert_eq!(problems[3].track_idx, Some(3));
+        assert!(problems[3].message.contains("appears 2 times"));
+    }
+}
@@ -0,0 +1,310 @@
+//! Sector Content Classification
+//!
+//! セクタデータの中身を推定する。判定は上から順に行う。
+//!
+//!   * Empty       未使用(全て E5, 00, FF のどれか)
+//!   * Pattern     16バイト以下の繰り返し(上以外の値で埋めたものを含む)
+//!   * Text        ASCII, JIS X 0201, Shift-JIS のテキスト(末尾の埋め草を除いて90%以上)
+//!   * Compressed  エントロピーが高い(圧縮、暗号化)。256バイトなら 7.0 bit/byte 以上、64バイト未満は判定しない
+//!   * Code        Z80 のコードらしい
+//!   * Data        上のどれでもない
+//!
+use crate::sector::Sector;
+use crate::z80::disassemble;
+
+/// Sector Content
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Content {
+    Empty,
+    Pattern,
+    Text,
+    Compressed,
+    Code,
+    Data,
+}
+
+impl Content {
+    /// All Contents (in Classification Order)
+    ///
+    pub const ALL: [Content; 6] = [
+        Content::Empty,
+        Content::Pattern,
+        Content::Text,
+        Content::Compressed,
+        Content::Code,
+        Content::Data,
+    ];
+
+    /// Content Name
+    ///
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Content::Empty => "Empty",
+            Content::Pattern => "Pattern",
+            Content::Text => "Text",
+            Content::Compressed => "Compressed",
+            Content::Code => "Code",
+            Content::Data => "Data",
+        }
+    }
+}
+
+/// Classification of Sector Data
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Classification {
+    pub content: Content,
+    /// Shannon Entropy (bit/byte, 0.0 - 8.0)
+    pub entropy: f64,
+}
+
+/// Classify Sector Data
+///
+/// # Argument
+///
+///   * `data` Sector Data
+///
+pub fn classify(data: &[u8]) -> Classification {
+    let entropy = get_entropy(data);
+    let content = if data.is_empty() || (is_fill(data) && [0xe5, 0x00, 0xff].contains(&data[0])) {
+        Content::Empty
+    } else if get_period(data).is_some() {
+        Content::Pattern
+    } else if is_text(data) {
+        Content::Text
+    } else if data.len() >= 64 && entropy >= 0.875 * (data.len().min(256) as f64).log2() {
+        Content::Compressed
+    } else if is_code(data) {
+        Content::Code
+    } else {
+        Content::Data
+    };
+
+    Classification { content, entropy }
+}
+
+/// All Bytes are the Same
+///
+/// 空のデータも `true`。
+///
+pub(crate) fn is_fill(data: &[u8]) -> bool {
+    data.windows(2).all(|pair| pair[0] == pair[1])
+}
+
+/// Shannon Entropy (bit/byte)
+///
+pub fn get_entropy(data: &[u8]) -> f64 {
+    if data.is_empty() {
+        return 0.0;
+    }
+
+    let mut counts = [0usize; 256];
+    for byte in data.iter() {
+        counts[*byte as usize] += 1;
+    }
+    let len = data.len() as f64;
+    counts
+        .iter()
+        .filter(|count| **count > 0)
+        .map(|count| {
+            let p = *count as f64 / len;
+            p * (1.0 / p).log2()
+        })
+        .sum()
+}
+
+/// Period of Repeating Pattern (1 - 16 byte) (Helper function)
+///
+fn get_period(data: &[u8]) -> Option<usize> {
+    (1..=16.min(data.len() / 2)).find(|period| {
+        data.iter()
+            .enumerate()
+            .all(|(pos, byte)| *byte == data[pos % period])
+    })
+}
+
+/// Text (Helper function)
+///
+/// 末尾の 00, 1A, E5 の埋め草を除き、90%以上が文字(制御文字は TAB, LF, CR だけ)。
+///
+fn is_text(data: &[u8]) -> bool {
+    let len = data.len()
+        - data
+            .iter()
+            .rev()
+            .take_while(|byte| matches!(byte, 0x00 | 0x1a | 0xe5))
+            .count();
+    if len < 16 {
+        return false;
+    }
+
+    let mut text_bytes = 0;
+    let mut pos = 0;
+    while pos < len {
+        let byte = data[pos];
+        let is_sjis_lead = matches!(byte, 0x81..=0x9f | 0xe0..=0xef);
+        match data.get(pos + 1) {
+            Some(trail) if is_sjis_lead && matches!(trail, 0x40..=0x7e | 0x80..=0xfc) => {
+                text_bytes += 2;
+                pos += 2;
+                continue;
+            }
+            _ => {}
+        }
+        if matches!(byte, 0x20..=0x7e | 0xa1..=0xdf | 0x09 | 0x0a | 0x0d) {
+            text_bytes += 1;
+        }
+        pos += 1;
+    }
+    text_bytes * 10 >= len * 9
+}
+
+/// Z80 Code (Helper function)
+///
+/// 逆アセンブルして、よく使う命令が6割以上、未定義命令(DB)が5%未満、
+/// かつ分岐(JP, JR, CALL, RET, DJNZ)があればコードとみなす。
+///
+fn is_code(data: &[u8]) -> bool {
+    const COMMON: &[u8] = &[
+        0x01, 0x11, 0x21, 0x31, 0x06, 0x0e, 0x16, 0x1e, 0x26, 0x2e, 0x36, 0x3e, 0x10, 0x18, 0x20,
+        0x28, 0x30, 0x38, 0x03, 0x0b, 0x13, 0x1b, 0x23, 0x2b, 0x04, 0x05, 0x0c, 0x0d, 0x3c, 0x3d,
+        0x09, 0x19, 0x12, 0x1a, 0x22, 0x2a, 0x32, 0x3a, 0x47, 0x4f, 0x57, 0x5f, 0x67, 0x6f, 0x77,
+        0x78, 0x79, 0x7a, 0x7b, 0x7c, 0x7d, 0x7e, 0xa7, 0xaf, 0xb7, 0xc1, 0xd1, 0xe1, 0xf1, 0xc5,
+        0xd5, 0xe5, 0xf5, 0xc2, 0xc3, 0xca, 0xd2, 0xda, 0xc4, 0xcc, 0xcd, 0xd4, 0xdc, 0xc0, 0xc8,
+        0xc9, 0xd0, 0xd8, 0xcb, 0xdd, 0xed, 0xfd, 0xd3, 0xdb, 0xe6, 0xf6, 0xfe, 0xeb, 0xf3, 0xfb,
+    ];
+
+    let instructions = disassemble(data, 0);
+    let len = instructions.len();
+    let common = instructions
+        .iter()
+        .filter(|inst| COMMON.contains(&inst.bytes[0]))
+        .count();
+    let invalid = instructions
+        .iter()
+        .filter(|inst| inst.text.starts_with("DB"))
+        .count();
+    let branch = instructions.iter().any(|inst| {
+        inst.target.is_some() || inst.text.starts_with("RET") || inst.text.starts_with("JP (")
+    });
+
+    branch && common * 10 >= len * 6 && invalid * 20 < len
+}
+
+/// Disk Usage
+///
+/// 未使用(`Content::Empty`)と使用済みのセクタ数と容量、種類ごとのセクタ数。
+///
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Usage {
+    pub used_sectors: usize,
+    pub used_bytes: usize,
+    pub empty_sectors: usize,
+    pub empty_bytes: usize,
+    /// Sectors of each Content (`Content::ALL` order)
+    pub counts: [usize; 6],
+}
+
+impl Usage {
+    /// Usage of Sectors
+    ///
+    /// # Argument
+    ///
+    ///   * `sectors` Sectors
+    ///
+    pub fn new<'a, I: IntoIterator<Item = &'a Sector>>(sectors: I) -> Self {
+        let mut usage = Usage::default();
+        for sector in sectors {
+            let content = classify(&sector.data).content;
+            if content == Content::Empty {
+                usage.empty_sectors += 1;
+                usage.empty_bytes += sector.data.len();
+            } else {
+                usage.used_sectors += 1;
+                usage.used_bytes += sector.data.len();
+            }
+            let idx = Content::ALL.iter().position(|c| *c == content).unwrap_or(0);
+            usage.counts[idx] += 1;
+        }
+        usage
+    }
+}
+
+// ================================================================================
+//
+//  Test Code
+//
+// ================================================================================
+#[cfg(test)]
+mod test {
+    use crate::classify::{classify, get_entropy, is_fill, Content, Usage};
+    use crate::fileio::D88FileIO;
+
+    const HUBASIC: &str = "../../sample/HuBASIC_Format_2D.d88";
+
+    #[test]
+    fn test_classify() {
+        assert!(is_fill(&[0xe5; 256]));
+        assert!(is_fill(&[]));
+        assert!(!is_fill(&[0xe5, 0x00]));
+
+        assert_eq!(classify(&[0xe5; 256]).content, Content::Empty);
+        assert_eq!(classify(&[0xe5; 256]).entropy, 0.0);
+        assert_eq!(get_entropy(&[]), 0.0);
+        assert!(get_entropy(&[]).is_sign_positive()); // not -0.0
+        assert_eq!(classify(&[0x55; 256]).content, Content::Pattern);
+        assert_eq!(
+            classify(&[0x12, 0x34].repeat(128)).content,
+            Content::Pattern
+        );
+
+        let mut text = b"10 PRINT \"HELLO\"\r\n20 GOTO 10\r\n".repeat(5);
+        text.resize(256, 0x1a);
+        assert_eq!(classify(&text).content, Content::Text);
+
+        // xorshift
+        let mut x: u32 = 2463534242;
+        let random: Vec<u8> = (0..256)
+            .map(|_| {
+                x ^= x << 13;
+                x ^= x >> 17;
+                x ^= x << 5;
+                x as u8
+            })
+            .collect();
+        assert_eq!(classify(&random).content, Content::Compressed);
+        assert!(get_entropy(&random) > 7.0);
+
+        // DI / LD SP,C000 / LD HL,C100 / LD DE,8000 / LD BC,0100 / LDIR / LD A,(HL) / OR A /
+        // JR Z,+5 / CALL C200 / INC HL / JR -9 / RET ...
+        let mut code = vec![
+            0xf3, 0x31, 0x00, 0xc0, 0x21, 0x00, 0xc1, 0x11, 0x00, 0x80, 0x01, 0x00, 0x01, 0xed,
+            0xb0, 0x7e, 0xb7, 0x28, 0x06, 0xcd, 0x00, 0xc2, 0x23, 0x18, 0xf6, 0xc9, 0x3e, 0x01,
+            0xd3, 0x1c, 0xc9,
+        ]
+        .repeat(8);
+        code.resize(256, 0x00);
+        assert_eq!(classify(&code).content, Content::Code);
+
+        assert_eq!(
+            classify(&[0x00, 0x01, 0x02, 0x03, 0x80, 0x40]).content,
+            Content::Data
+        );
+    }
+
+    #[test]
+    fn test_usage() {
+        let d88fileio = D88FileIO::open(HUBASIC);
+        let sectors = d88fileio
+            .disk
+            .track_tbl
+            .iter()
+            .flat_map(|track| track.sector_tbl.iter());
+        let usage = Usage::new(sectors);
+        assert_eq!(usage.used_sectors + usage.empty_sectors, 1280);
+        assert_eq!(usage.empty_bytes, usage.empty_sectors * 256);
+        assert!(usage.used_sectors > 0);
+        assert_eq!(usage.counts.iter().sum::<usize>(), 1280);
+    }
+}
@@ -0,0 +1,421 @@
+use std::fs;
+use std::path::Path;
+
+use crate::hash::Digest;
+
+/// ROM Entry at DAT File
+///
+/// `<rom name=".." size=".." crc=".." md5=".." sha1=".." status=".."/>`
+/// ハッシュ値は小文字の16進文字列で保持する。
+///
+#[derive(Debug, Clone, Default)]
+pub struct DatRom {
+    pub name: String,
+    pub size: Option<u64>,
+    pub crc32: Option<String>,
+    pub md5: Option<String>,
+    pub sha1: Option<String>,
+    pub status: Option<String>,
+}
+
+/// Game Entry at DAT File
+///
+/// `<game name=".." cloneof=".."><description>..</description><rom/>..</game>`
+///
+#[derive(Debug, Clone, Default)]
+pub struct DatGame {
+    pub name: String,
+    pub description: String,
+    pub cloneof: Option<String>,
+    pub roms: Vec<DatRom>,
+}
+
+/// DAT File (Logiqx XML)
+///
+/// TOSEC / Redump 等のDATカタログ
+///
+#[derive(Debug, Clone, Default)]
+pub struct DatFile {
+    pub name: String,
+    pub description: String,
+    pub games: Vec<DatGame>,
+}
+
+/// Which Hash Matched
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchBy {
+    /// Whole File Hash
+    File,
+    /// Sector Data Hash (`Disk::content_digest()`)
+    Content,
+}
+
+/// Matched Entry
+///
+#[derive(Debug, Clone, Copy)]
+pub struct DatMatch<'a> {
+    pub game: &'a DatGame,
+    pub rom: &'a DatRom,
+    pub by: MatchBy,
+}
+
+impl DatRom {
+    /// Compare with Digest
+    ///
+    /// SHA-1 > MD5 > CRC32 の順で、DATに記載されている最も強いハッシュで比較する。
+    ///
+    pub fn is_match(&self, digest: &Digest) -> bool {
+        if let Some(ref sha1) = self.sha1 {
+            *sha1 == digest.get_sha1()
+        } else if let Some(ref md5) = self.md5 {
+            *md5 == digest.get_md5()
+        } else if let Some(ref crc32) = self.crc32 {
+            *crc32 == digest.get_crc32()
+        } else {
+            false
+        }
+    }
+
+    /// Known Bad Dump
+    ///
+    pub fn is_bad_dump(&self) -> bool {
+        matches!(self.status.as_deref(), Some("baddump") | Some("nodump"))
+    }
+}
+
+impl DatGame {
+    /// Title without Dump Flags
+    ///
+    /// TOSECの `[a]`, `[b]`, `[h]` 等のフラグを取り除いた名前。
+    /// 同じタイトルの別ダンプを探すのに使う。
+    ///
+    pub fn get_title(&self) -> String {
+        let mut title = String::new();
+        let mut depth = 0;
+
+        for c in self.name.chars() {
+            match c {
+                '[' => depth += 1,
+                ']' if depth > 0 => depth -= 1,
+                _ if depth == 0 => title.push(c),
+                _ => {}
+            }
+        }
+
+        title.trim().to_string()
+    }
+
+    /// TOSEC Dump Flags
+    ///
+    /// # Return
+    ///
+    ///   * Vec<String>  e.g. ["a2", "h Translated"]
+    ///
+    pub fn get_dump_flags(&self) -> Vec<String> {
+        let mut flags = Vec::new();
+        let mut rest = self.name.as_str();
+
+        while let Some(start) = rest.find('[') {
+            if let Some(len) = rest[start..].find(']') {
+                flags.push(rest[start + 1..start + len].to_string());
+                rest = &rest[start + len + 1..];
+            } else {
+                break;
+            }
+        }
+
+        flags
+    }
+
+    /// Dump Status (Helper function)
+    ///
+    /// TOSECのフラグとROMの`status`属性から、ダンプの状態を返す。
+    ///
+    pub fn get_dump_status(&self, rom: &DatRom) -> String {
+        let mut status: Vec<&str> = Vec::new();
+
+        if rom.is_bad_dump() {
+            status.push("Bad Dump");
+        }
+        for flag in self.get_dump_flags() {
+            let code: String = flag
+                .chars()
+                .take_while(|c| c.is_ascii_alphabetic() || (*c == '!'))
+                .collect();
+
+            let name = match code.as_str() {
+                "!" => "Verified",
+                "b" => "Bad Dump",
+                "a" => "Alternate",
+                "h" => "Hacked",
+                "m" => "Modified",
+                "t" => "Trained",
+                "f" => "Fixed",
+                "cr" => "Cracked",
+                "o" => "Over Dump",
+                "u" => "Under Dump",
+                "p" => "Pirate",
+                "v" => "Virus",
+                _ => continue,
+            };
+            if !status.contains(&name) {
+                status.push(name);
+            }
+        }
+
+        if status.is_empty() {
+            "Good".to_string()
+        } else {
+            status.join(", ")
+        }
+    }
+
+    /// Known Bad or Modified Dump
+    ///
+    pub fn is_bad_or_modified(&self, rom: &DatRom) -> bool {
+        let status = self.get_dump_status(rom);
+        [
+            "Bad Dump",
+            "Hacked",
+            "Modified",
+            "Trained",
+            "Cracked",
+            "Over Dump",
+            "Under Dump",
+            "Virus",
+        ]
+        .iter()
+        .any(|s| status.contains(s))
+    }
+}
+
+impl DatFile {
+    /// Read DAT File
+    ///
+    /// # Argument
+    ///
+    ///   * `path` DAT File Path
+    ///
+    /// # Return
+    ///
+    ///   * Ok(DatFile)
+    ///   * Err(String)  Error Message
+    ///
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let text = fs::read_to_string(path.as_ref())
+            .map_err(|e| format!("{}: {}", path.as_ref().display(), e))?;
+
+        DatFile::parse(&text)
+    }
+
+    /// Parse Logiqx DAT XML
+    ///
+    /// `<game>` の他に `<machine>` 要素も受け付ける。
+    ///
+    /// # Argument
+    ///
+    ///   * `text` XML Text
+    ///
+    /// # Return
+    ///
+    ///   * Ok(DatFile)
+    ///   * Err(String)  Error Message
+    ///
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let opt = roxmltree::ParsingOptions {
+            allow_dtd: true,
+            ..roxmltree::ParsingOptions::default()
+        };
+        let doc = roxmltree::Document::parse_with_options(text, opt).map_err(|e| e.to_string())?;
+
+        let root = doc.root_element();
+        if !root.has_tag_name("datafile") {
+            return Err(format!(
+                "<datafile> expected, found <{}>",
+                root.tag_name().name()
+            ));
+        }
+
+        let mut dat = DatFile::default();
+        for node in root.children().filter(|n| n.is_element()) {
+            match node.tag_name().name() {
+                "header" => {
+                    dat.name = child_text(&node, "name");
+                    dat.description = child_text(&node, "description");
+                }
+                "game" | "machine" => {
+                    let mut game = DatGame {
+                        name: node.attribute("name").unwrap_or_default().to_string(),
+                        description: child_text(&node, "description"),
+                        cloneof: node.attribute("cloneof").map(|s| s.to_string()),
+                        roms: Vec::new(),
+                    };
+
+                    for rom in node.children().filter(|n| n.has_tag_name("rom")) {
+                        let hex = |attr: &str| rom.attribute(attr).map(|s| s.to_ascii_lowercase());
+                        game.roms.push(DatRom {
+                            name: rom.attribute("name").unwrap_or_default().to_string(),
+                            size: rom.attribute("size").and_then(|s| s.parse().ok()),
+                            crc32: hex("crc"),
+                            md5: hex("md5"),
+                            sha1: hex("sha1"),
+                            status: rom.attribute("status").map(|s| s.to_string()),
+                        });
+                    }
+                    dat.games.push(game);
+                }
+                _ => {}
+            }
+        }
+
+        Ok(dat)
+    }
+
+    /// Find ROM Entries
+    ///
+    /// # Argument
+    ///
+    ///   * `digest` Hash Value
+    ///   * `size`   File Size (`MatchBy::File` only)
+    ///   * `by`     Kind of Hash
+    ///
+    /// # Return
+    ///
+    ///   * Vec<DatMatch>
+    ///
+    pub fn find(&self, digest: &Digest, size: Option<u64>, by: MatchBy) -> Vec<DatMatch<'_>> {
+        let mut matches = Vec::new();
+
+        for game in self.games.iter() {
+            for rom in game.roms.iter() {
+                let size_ok = match (by, rom.size, size) {
+                    (MatchBy::File, Some(rom_size), Some(size)) => rom_size == size,
+                    _ => true,
+                };
+
+                if size_ok && rom.is_match(digest) {
+                    matches.push(DatMatch { game, rom, by });
+                }
+            }
+        }
+
+        matches
+    }
+
+    /// Other Dumps of the same Title
+    ///
+    /// フラグを除いたタイトルが同じもの、または `cloneof` で関連付けられたもの。
+    ///
+    pub fn find_alternates(&self, game: &DatGame) -> Vec<&DatGame> {
+        let title = game.get_title();
+        let parent = game.cloneof.as_deref().unwrap_or(&game.name);
+
+        self.games
+            .iter()
+            .filter(|other| other.name != game.name)
+            .filter(|other| {
+                (other.get_title() == title)
+                    || (other.name == parent)
+                    || (other.cloneof.as_deref() == Some(parent))
+            })
+            .collect()
+    }
+}
+
+fn child_text(node: &roxmltree::Node, name: &str) -> String {
+    node.children()
+        .find(|n| n.has_tag_name(name))
+        .and_then(|n| n.text())
+        .unwrap_or_default()
+        .trim()
+        .to_string()
+}
+
+// ================================================================================
+//
+//  Test Code
+//
+// ================================================================================
+#[cfg(test)]
+mod test {
+    use crate::dat::{DatFile, MatchBy};
+    use crate::fileio::D88FileIO;
+    use crate::hash::Digest;
+
+    const HUBASIC: &str = "../../sample/HuBASIC_Format_2D.d88";
+
+    const DAT: &str = r#"<?xml version="1.0"?>
+<!DOCTYPE datafile PUBLIC "-//Logiqx//DTD ROM Management Datafile//EN" "http://www.logiqx.com/Dats/datafile.dtd">
+<datafile>
+  <header>
+    <name>Sharp X1 - Sample</name>
+    <description>Sharp X1 - Sample (TOSEC-style)</description>
+  </header>
+  <game name="Hu-BASIC Disk (1984)(Sharp)">
+    <description>Hu-BASIC Disk (1984)(Sharp)</description>
+    <rom name="Hu-BASIC Disk (1984)(Sharp).d88" size="348848" crc="C692E84D" md5="8486cf60a94b956b9fba22c5cc8bf904" sha1="65b61bdf8a2948b9dd7fee18eda2957cc907bfaf"/>
+  </game>
+  <game name="Hu-BASIC Disk (1984)(Sharp)[a]">
+    <description>Hu-BASIC Disk (1984)(Sharp)[a]</description>
+    <rom name="Hu-BASIC Disk (1984)(Sharp)[a].d88" size="348848" sha1="287f344b00b981c182869189577f56fb5f5d3abc"/>
+  </game>
+  <game name="Hu-BASIC Disk (1984)(Sharp)[h Translated]">
+    <description>Hu-BASIC Disk (1984)(Sharp)[h]</description>
+    <rom name="Hu-BASIC Disk (1984)(Sharp)[h].d88" size="348848" crc="00000000" status="baddump"/>
+  </game>
+</datafile>
+"#;
+
+    #[test]
+    fn test_parse() {
+        let dat = DatFile::parse(DAT).unwrap();
+        assert_eq!(dat.name, "Sharp X1 - Sample");
+        assert_eq!(dat.games.len(), 3);
+        assert_eq!(dat.games[0].roms[0].size, Some(348848));
+        assert_eq!(dat.games[0].roms[0].crc32.as_deref(), Some("c692e84d"));
+
+        assert!(DatFile::parse("<html></html>").is_err());
+        assert!(DatFile::parse("<datafile>").is_err());
+    }
+
+    #[test]
+    fn test_find() {
+        let dat = DatFile::parse(DAT).unwrap();
+        let d88fileio = D88FileIO::open(HUBASIC);
+
+        let file_digest = Digest::from_file(HUBASIC).unwrap();
+        let found = dat.find(&file_digest, Some(348848), MatchBy::File);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].game.name, "Hu-BASIC Disk (1984)(Sharp)");
+        assert_eq!(found[0].game.get_dump_status(found[0].rom), "Good");
+
+        assert!(dat.find(&file_digest, Some(1), MatchBy::File).is_empty());
+
+        let found = dat.find(&d88fileio.disk.content_digest(), None, MatchBy::Content);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].game.get_dump_status(found[0].rom), "Alternate");
+        assert!(!found[0].game.is_bad_or_modified(found[0].rom));
+    }
+
+    #[test]
+    fn test_alternates_and_flags() {
+        let dat = DatFile::parse(DAT).unwrap();
+
+        let hacked = &dat.games[2];
+        assert_eq!(hacked.get_title(), "Hu-BASIC Disk (1984)(Sharp)");
+        assert_eq!(hacked.get_dump_flags(), vec!["h Translated".to_string()]);
+        assert_eq!(hacked.get_dump_status(&hacked.roms[0]), "Bad Dump, Hacked");
+        assert!(hacked.is_bad_or_modified(&hacked.roms[0]));
+
+        let mut flagged = hacked.clone();
+        flagged.name = "Hu-BASIC Disk (1984)(Sharp)[b][h][b2][a]".to_string();
+        assert_eq!(
+            flagged.get_dump_status(&flagged.roms[0]),
+            "Bad Dump, Hacked, Alternate"
+        );
+
+        let alternates = dat.find_alternates(&dat.games[0]);
+        assert_eq!(alternates.len(), 2);
+    }
+}
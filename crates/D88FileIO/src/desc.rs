@@ -0,0 +1,640 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::classify::is_fill;
+use crate::disk::Disk;
+use crate::format::{D88_Header, D88_SectorHdr, ImageFormat, MAX_SECTOR};
+use crate::format::{DISK_TYPE_1D, DISK_TYPE_1DD, DISK_TYPE_2D, DISK_TYPE_2DD, DISK_TYPE_2HD};
+use crate::sector::Sector;
+use crate::track::Track;
+
+/// Bytes per line of `hex` at Sector Description
+const HEX_LINE: usize = 32;
+
+/// Disk Description
+///
+/// テキスト(TOML)で書いたディスクの定義。`build()` でD88に、`DiskDesc::from_disk()` で逆変換。
+///
+/// ```toml
+/// name = "TEST DISK"
+/// type = "2D"
+///
+/// [[track]]
+/// track = 0
+/// side = 0
+///
+/// [[track.sector]]
+/// c = 0
+/// h = 0
+/// r = 1
+/// n = 1
+/// fill = 0xe5
+///
+/// [[track.sector]]
+/// c = 0
+/// h = 0
+/// r = 2
+/// n = 1
+/// status = 0xb0
+/// file = "sector2.bin"
+/// ```
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiskDesc {
+    #[serde(default)]
+    pub name: String,
+    /// "2D", "2DD", "2HD", "1D", "1DD" or a number ("0x30")
+    #[serde(rename = "type")]
+    pub disk_type: String,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub write_protect: bool,
+    /// Reserved bytes of D88 File Header (hex)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reserved: Option<String>,
+    #[serde(default, rename = "track")]
+    pub tracks: Vec<TrackDesc>,
+}
+
+/// Track Description
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrackDesc {
+    pub track: usize,
+    #[serde(default)]
+    pub side: usize,
+    /// Index to Track Offset Table (default: `track * 2 + side`, or `track` on single-sided disk)
+    ///
+    /// 偶数トラックだけに書かれた1Dイメージ等、既定の位置と違う時だけ指定する。
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub index: Option<usize>,
+    #[serde(default, rename = "sector")]
+    pub sectors: Vec<SectorDesc>,
+}
+
+/// Sector Description
+///
+/// データは `fill`, `hex`, `file` のどれか1つ。省略時は0で埋める。
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SectorDesc {
+    pub c: u8,
+    pub h: u8,
+    pub r: u8,
+    pub n: u8,
+    /// Number of Sector in the Track (default: actual count)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub number_of_sec: Option<u16>,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub density: u8,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub deleted: bool,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub status: u8,
+    /// Reserved bytes of Sector Header (hex)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reserved: Option<String>,
+    /// Data Size (default: 128 << N, or the size of `hex`/`file`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<u16>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fill: Option<u8>,
+    /// Hex String (whitespace is ignored)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hex: Option<String>,
+    /// Raw Data File (relative to the description file)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+}
+
+impl DiskDesc {
+    /// Parse Description (TOML)
+    ///
+    /// # Argument
+    ///
+    ///   * `text` TOML Text
+    ///
+    /// # Return
+    ///
+    ///   * Ok(DiskDesc)
+    ///   * Err(String)   Error Message
+    ///
+    pub fn parse(text: &str) -> Result<Self, String> {
+        toml::from_str(text).map_err(|e| format!("Description Error: {}", e))
+    }
+
+    /// Description as TOML Text
+    ///
+    pub fn to_toml(&self) -> Result<String, String> {
+        toml::to_string(self).map_err(|e| format!("Description Error: {}", e))
+    }
+
+    /// Description of a Disk
+    ///
+    /// 全セクタが同じ値なら `fill`、それ以外は `hex` で出力する。
+    /// 空のトラックは出力しない。
+    ///
+    /// # Argument
+    ///
+    ///   * `disk` Disk
+    ///
+    /// # Return
+    ///
+    ///   * DiskDesc
+    ///
+    pub fn from_disk(disk: &Disk) -> Self {
+        let header = &disk.header;
+        let name_len = header
+            .disk_name
+            .iter()
+            .position(|b| *b == 0)
+            .unwrap_or(header.disk_name.len());
+
+        let tracks = disk
+            .track_tbl
+            .iter()
+            .enumerate()
+            .filter(|(_, track)| !track.sector_tbl.is_empty())
+            .map(|(idx, track)| {
+                let (track_no, side_no) = disk.track_position(idx);
+                let default_idx = get_default_index(disk, track_no, side_no);
+                TrackDesc {
+                    track: track_no,
+                    side: side_no,
+                    index: (default_idx != Some(idx)).then_some(idx),
+                    sectors: track
+                        .sector_tbl
+                        .iter()
+                        .map(|sector| SectorDesc::from_sector(sector, track.sector_tbl.len()))
+                        .collect(),
+                }
+            })
+            .collect();
+
+        Self {
+            name: String::from_utf8_lossy(&header.disk_name[..name_len]).to_string(),
+            disk_type: match header.disk_type {
+                DISK_TYPE_2D => "2D".to_string(),
+                DISK_TYPE_2DD => "2DD".to_string(),
+                DISK_TYPE_2HD => "2HD".to_string(),
+                DISK_TYPE_1D => "1D".to_string(),
+                DISK_TYPE_1DD => "1DD".to_string(),
+                n => format!("0x{:02x}", n),
+            },
+            write_protect: header.write_protect == 0x10,
+            reserved: encode_reserved(&header.reserved),
+            tracks,
+        }
+    }
+
+    /// Build Disk
+    ///
+    /// # Argument
+    ///
+    ///   * `base_dir` Base Directory of `file` at Sector Description
+    ///
+    /// # Return
+    ///
+    ///   * Ok(Disk)
+    ///   * Err(String)  Error Message
+    ///
+    pub fn to_disk(&self, base_dir: &Path) -> Result<Disk, String> {
+        let mut disk = Disk {
+            header: D88_Header::default(),
+            track_tbl: Vec::new(),
+            format: ImageFormat::D88,
+        };
+
+        if self.name.len() > disk.header.disk_name.len() {
+            return Err(format!("Disk name \"{}\" is too long", self.name));
+        }
+        disk.header.disk_name[..self.name.len()].copy_from_slice(self.name.as_bytes());
+        disk.header.disk_type = parse_disk_type(&self.disk_type)?;
+        disk.header.write_protect = if self.write_protect { 0x10 } else { 0x00 };
+        if let Some(ref reserved) = self.reserved {
+            decode_reserved(reserved, &mut disk.header.reserved)?;
+        }
+        if disk.is_single_sided_media() {
+            disk.format = ImageFormat::D77;
+        }
+
+        for track_desc in self.tracks.iter() {
+            if disk.is_single_sided_media() && (track_desc.side != 0) {
+                return Err(format!(
+                    "Track({}) Side({}) on single-sided disk",
+                    track_desc.track, track_desc.side
+                ));
+            }
+            let idx = track_desc
+                .index
+                .or_else(|| get_default_index(&disk, track_desc.track, track_desc.side))
+                .unwrap_or(MAX_SECTOR as usize);
+            if (track_desc.side >= 2) || (idx >= MAX_SECTOR as usize) {
+                return Err(format!(
+                    "Track({}) Side({}) is out of range",
+                    track_desc.track, track_desc.side
+                ));
+            }
+
+            if disk.track_tbl.len() <= idx {
+                disk.track_tbl.resize_with(idx + 1, Track::default);
+            }
+            if !disk.track_tbl[idx].sector_tbl.is_empty() {
+                return Err(format!(
+                    "Track({}) Side({}) is defined twice",
+                    track_desc.track, track_desc.side
+                ));
+            }
+
+            let track = &mut disk.track_tbl[idx];
+            let count = track_desc.sectors.len();
+            for sector_desc in track_desc.sectors.iter() {
+                let sector = sector_desc.to_sector(count, base_dir).map_err(|mes| {
+                    format!(
+                        "Track({}) Side({}) Sector({}): {}",
+                        track_desc.track, track_desc.side, sector_desc.r, mes
+                    )
+                })?;
+                track.sector_tbl.push(sector);
+            }
+            track.number_of_sector = count as u16;
+        }
+
+        disk.update_offset();
+        Ok(disk)
+    }
+}
+
+impl SectorDesc {
+    /// Description of a Sector (Helper function)
+    ///
+    fn from_sector(sector: &Sector, count: usize) -> Self {
+        let hdr = &sector.header;
+
+        let (fill, hex) = match sector.data.first() {
+            Some(first) if is_fill(&sector.data) => (Some(*first), None),
+            Some(_) => (None, Some(encode_hex(&sector.data))),
+            None => (None, None),
+        };
+        let size = if sector.get_sector_size_byte() == Some(sector.data.len()) {
+            None
+        } else {
+            Some(hdr.size_of_data)
+        };
+
+        Self {
+            c: hdr.track,
+            h: hdr.side,
+            r: hdr.sector,
+            n: hdr.sector_size,
+            number_of_sec: if hdr.number_of_sec as usize == count {
+                None
+            } else {
+                Some(hdr.number_of_sec)
+            },
+            density: hdr.density,
+            deleted: hdr.deleted_mark == 0x10,
+            status: hdr.status,
+            reserved: encode_reserved(&hdr.reserved),
+            size,
+            fill,
+            hex,
+            file: None,
+        }
+    }
+
+    /// Build Sector (Helper function)
+    ///
+    fn to_sector(&self, count: usize, base_dir: &Path) -> Result<Sector, String> {
+        let default_size = if self.n <= 7 {
+            Some(128usize << self.n)
+        } else {
+            None
+        };
+
+        let data: Vec<u8> = match (self.fill, &self.hex, &self.file) {
+            (Some(_), Some(_), _) | (Some(_), _, Some(_)) | (_, Some(_), Some(_)) => {
+                return Err("Specify one of fill, hex or file".to_string());
+            }
+            (_, Some(hex), _) => decode_hex(hex)?,
+            (_, _, Some(file)) => {
+                let path = base_dir.join(file);
+                fs::read(&path).map_err(|_| format!("File Not Found \"{}\"", path.display()))?
+            }
+            (fill, None, None) => {
+                let size = self
+                    .size
+                    .map(|size| size as usize)
+                    .or(default_size)
+                    .ok_or_else(|| format!("N={} needs size", self.n))?;
+                vec![fill.unwrap_or(0); size]
+            }
+        };
+
+        if let Some(size) = self.size {
+            if data.len() != size as usize {
+                return Err(format!(
+                    "Data is {} bytes, but size is {}",
+                    data.len(),
+                    size
+                ));
+            }
+        }
+        if data.len() > u16::MAX as usize {
+            return Err(format!("Data is too large ({} bytes)", data.len()));
+        }
+
+        let mut header = D88_SectorHdr {
+            track: self.c,
+            side: self.h,
+            sector: self.r,
+            sector_size: self.n,
+            number_of_sec: self.number_of_sec.unwrap_or(count as u16),
+            density: self.density,
+            deleted_mark: if self.deleted { 0x10 } else { 0x00 },
+            status: self.status,
+            size_of_data: data.len() as u16,
+            ..D88_SectorHdr::default()
+        };
+        if let Some(ref reserved) = self.reserved {
+            decode_reserved(reserved, &mut header.reserved)?;
+        }
+
+        Ok(Sector {
+            offset: 0,
+            header,
+            data,
+        })
+    }
+}
+
+/// Build D88 from Description File
+///
+/// `file` は記述ファイルのディレクトリからの相対パス。
+///
+/// # Argument
+///
+///   * `path` Description File (TOML)
+///
+/// # Return
+///
+///   * Ok(Disk)
+///   * Err(String)  Error Message
+///
+pub fn build<P: AsRef<Path>>(path: P) -> Result<Disk, String> {
+    let path = path.as_ref();
+    let text =
+        fs::read_to_string(path).map_err(|_| format!("File Not Found \"{}\"", path.display()))?;
+
+    let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+    DiskDesc::parse(&text)?.to_disk(base_dir)
+}
+
+/// Disk Type from Name or Number (Helper function)
+///
+fn parse_disk_type(s: &str) -> Result<u8, String> {
+    match s.to_ascii_uppercase().as_str() {
+        "2D" => Ok(DISK_TYPE_2D),
+        "2DD" => Ok(DISK_TYPE_2DD),
+        "2HD" => Ok(DISK_TYPE_2HD),
+        "1D" => Ok(DISK_TYPE_1D),
+        "1DD" => Ok(DISK_TYPE_1DD),
+        t => {
+            let n = if let Some(hex) = t.strip_prefix("0X") {
+                u8::from_str_radix(hex, 16)
+            } else {
+                t.parse::<u8>()
+            };
+            n.map_err(|_| format!("Unknown disk type \"{}\"", s))
+        }
+    }
+}
+
+/// Hex String, 32 bytes per line (Helper function)
+///
+fn encode_hex(data: &[u8]) -> String {
+    data.chunks(HEX_LINE)
+        .map(|line| {
+            line.iter()
+                .map(|b| format!("{:02x}", b))
+                .collect::<String>()
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Bytes from Hex String (Helper function)
+///
+fn decode_hex(hex: &str) -> Result<Vec<u8>, String> {
+    let digits: Vec<u8> = hex.bytes().filter(|c| !c.is_ascii_whitespace()).collect();
+    if !digits.len().is_multiple_of(2) {
+        return Err("Odd number of hex digits".to_string());
+    }
+
+    digits
+        .chunks(2)
+        .map(|pair| {
+            std::str::from_utf8(pair)
+                .ok()
+                .and_then(|s| u8::from_str_radix(s, 16).ok())
+                .ok_or_else(|| format!("Not hex \"{}\"", String::from_utf8_lossy(pair)))
+        })
+        .collect()
+}
+
+/// Reserved Bytes, None if all zero (Helper function)
+///
+fn encode_reserved(reserved: &[u8]) -> Option<String> {
+    if reserved.iter().all(|b| *b == 0) {
+        None
+    } else {
+        Some(encode_hex(reserved))
+    }
+}
+
+fn decode_reserved(hex: &str, reserved: &mut [u8]) -> Result<(), String> {
+    let bytes = decode_hex(hex)?;
+    if bytes.len() != reserved.len() {
+        return Err(format!("reserved must be {} bytes", reserved.len()));
+    }
+    reserved.copy_from_slice(&bytes);
+    Ok(())
+}
+
+/// Default Index to Track Offset Table (Helper function)
+///
+/// トラックオフセットテーブルが空のディスクとしての位置(偶数トラックだけの配置は考えない)。
+///
+fn get_default_index(disk: &Disk, track: usize, side: usize) -> Option<usize> {
+    if disk.is_single_sided_media() {
+        (side == 0).then_some(track)
+    } else {
+        (side < 2).then_some((track * 2) + side)
+    }
+}
+
+fn is_false(b: &bool) -> bool {
+    !*b
+}
+
+fn is_zero(n: &u8) -> bool {
+    *n == 0
+}
+
+// ================================================================================
+//
+//  Test Code
+//
+// ================================================================================
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use crate::desc::DiskDesc;
+    use crate::fileio::D88FileIO;
+    use crate::format::{DISK_TYPE_1D, DISK_TYPE_2D};
+    use crate::raw::{self, Geometry};
+
+    const HUBASIC: &str = "../../sample/HuBASIC_Format_2D.d88";
+    const CPM: &str = "../../sample/CPM_Format_2D_turboCPM_X1turbo.d88";
+
+    #[test]
+    fn test_desc_round_trip() {
+        for path in [HUBASIC, CPM] {
+            let d88fileio = D88FileIO::open(path);
+
+            let text = DiskDesc::from_disk(&d88fileio.disk).to_toml().unwrap();
+            let desc = DiskDesc::parse(&text).unwrap();
+            assert_eq!(desc, DiskDesc::from_disk(&d88fileio.disk));
+
+            let disk = desc.to_disk(Path::new(".")).unwrap();
+            let mut img: Vec<u8> = Vec::new();
+            assert!(disk.write(&mut img).is_ok());
+            assert_eq!(img, std::fs::read(path).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_desc_round_trip_even_track_only() {
+        // 1D, Track 0..39 at even index
+        let mut disk = raw::blank(&Geometry::from_name("2D").unwrap(), 0xe5);
+        disk.header.disk_type = DISK_TYPE_1D;
+        for (idx, track) in disk.track_tbl.iter_mut().enumerate() {
+            if (idx % 2) == 1 {
+                track.sector_tbl.clear();
+                track.number_of_sector = 0;
+            }
+        }
+        disk.update_offset();
+        assert!(disk.is_even_track_only() && disk.is_single_sided_media());
+
+        let desc = DiskDesc::from_disk(&disk);
+        assert_eq!(desc.tracks.len(), 40);
+        assert_eq!((desc.tracks[1].track, desc.tracks[1].index), (1, Some(2)));
+        let text = desc.to_toml().unwrap();
+        assert!(text.contains("index = 78"));
+
+        let built = DiskDesc::parse(&text)
+            .unwrap()
+            .to_disk(Path::new("."))
+            .unwrap();
+        assert_eq!(built.header.track_offset_tbl, disk.header.track_offset_tbl);
+        assert_eq!(built.get_track(39, 0).unwrap().sector_tbl.len(), 16);
+
+        let mut img: Vec<u8> = Vec::new();
+        let mut expected: Vec<u8> = Vec::new();
+        assert!(built.write(&mut img).is_ok());
+        assert!(disk.write(&mut expected).is_ok());
+        assert_eq!(img, expected);
+
+        // 2D even-track-only needs no index
+        disk.header.disk_type = DISK_TYPE_2D;
+        let desc = DiskDesc::from_disk(&disk);
+        assert!(desc.tracks.iter().all(|track| track.index.is_none()));
+        assert_eq!(
+            desc.to_disk(Path::new("."))
+                .unwrap()
+                .header
+                .track_offset_tbl,
+            disk.header.track_offset_tbl
+        );
+    }
+
+    #[test]
+    fn test_desc_build_protect_layout() {
+        let text = r#"
+name = "PROTECT"
+type = "2D"
+
+[[track]]
+track = 1
+side = 1
+
+[[track.sector]]
+c = 1
+h = 1
+r = 1
+n = 1
+fill = 0xe5
+
+[[track.sector]]
+c = 40
+h = 1
+r = 1
+n = 6
+size = 256
+status = 0xb0
+deleted = true
+hex = """
+00 01 02 03
+"""
+"#;
+        let desc = DiskDesc::parse(text);
+        assert!(desc.is_ok());
+        // hex is 4 bytes, size is 256
+        assert!(desc.unwrap().to_disk(Path::new(".")).is_err());
+
+        let desc = DiskDesc::parse(&text.replace("size = 256", "size = 4")).unwrap();
+        let disk = desc.to_disk(Path::new(".")).unwrap();
+
+        assert_eq!(disk.track_tbl.len(), 4);
+        assert!(disk.track_tbl[0].sector_tbl.is_empty());
+        let track = disk.get_track(1, 1).unwrap();
+        assert_eq!(track.sector_tbl.len(), 2);
+        assert_eq!(track.sector_tbl[0].data, vec![0xe5; 256]);
+
+        let hdr = &track.sector_tbl[1].header;
+        assert_eq!((hdr.track, hdr.sector_size, hdr.number_of_sec), (40, 6, 2));
+        assert_eq!(
+            (hdr.status, hdr.deleted_mark, hdr.size_of_data),
+            (0xb0, 0x10, 4)
+        );
+        assert_eq!(track.sector_tbl[1].data, vec![0, 1, 2, 3]);
+
+        // Decompile keeps the layout
+        assert_eq!(
+            DiskDesc::from_disk(&disk)
+                .to_disk(Path::new("."))
+                .unwrap()
+                .header
+                .disk_size,
+            disk.header.disk_size
+        );
+    }
+
+    #[test]
+    fn test_desc_errors() {
+        assert!(DiskDesc::parse("type = 2").is_err());
+        assert!(DiskDesc::parse("name = \"A\"").is_err()); // no type
+
+        let desc = DiskDesc::parse("type = \"3D\"").unwrap();
+        assert!(desc.to_disk(Path::new(".")).is_err());
+
+        let desc = DiskDesc::parse(
+            "type = \"2D\"\n[[track]]\ntrack = 0\n[[track.sector]]\nc = 0\nh = 0\nr = 1\nn = 1\nfill = 0\nhex = \"00\"\n",
+        )
+        .unwrap();
+        assert!(desc.to_disk(Path::new(".")).is_err());
+    }
+}
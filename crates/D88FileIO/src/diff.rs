@@ -0,0 +1,353 @@
+use crate::disk::Disk;
+use crate::sector::Sector;
+
+/// Position of a Sector
+///
+/// 物理位置(`track_tbl` 上のトラック、サイド)とセクタID(R)。
+/// 同じトラックに同じIDのセクタが複数ある場合は `nth` で区別する。
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SectorKey {
+    pub track: usize,
+    pub side: usize,
+    pub sector: u8,
+    pub nth: usize,
+}
+
+/// Differing Field
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldDiff {
+    pub name: &'static str,
+    pub a: String,
+    pub b: String,
+}
+
+/// Differing Byte Range in Sector Data
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DataDiff {
+    pub start: usize,
+    pub len: usize,
+}
+
+/// Difference of a Sector
+///
+#[derive(Debug)]
+pub enum SectorDiff<'a> {
+    /// Only in Disk A
+    OnlyA { key: SectorKey, a: &'a Sector },
+    /// Only in Disk B
+    OnlyB { key: SectorKey, b: &'a Sector },
+    /// Header or Data differ
+    Changed {
+        key: SectorKey,
+        a: &'a Sector,
+        b: &'a Sector,
+        fields: Vec<FieldDiff>,
+        data: Vec<DataDiff>,
+    },
+}
+
+/// Difference of two Disks
+///
+#[derive(Debug, Default)]
+pub struct DiskDiff<'a> {
+    pub header: Vec<FieldDiff>,
+    pub sectors: Vec<SectorDiff<'a>>,
+
+    pub same_count: usize,
+    pub changed_count: usize,
+    pub only_a_count: usize,
+    pub only_b_count: usize,
+}
+
+impl<'a> DiskDiff<'a> {
+    /// No Difference
+    ///
+    pub fn is_same(&self) -> bool {
+        self.header.is_empty() && self.sectors.is_empty()
+    }
+}
+
+impl<'a> SectorDiff<'a> {
+    pub fn key(&self) -> &SectorKey {
+        match self {
+            SectorDiff::OnlyA { key, .. } => key,
+            SectorDiff::OnlyB { key, .. } => key,
+            SectorDiff::Changed { key, .. } => key,
+        }
+    }
+}
+
+/// Compare two Disks
+///
+/// ファイル上の配置やヘッダの違いに影響されないよう、
+/// (トラック, サイド, セクタID) でセクタを対応付けて比較する。
+///
+/// # Argument
+///
+///   * `a` Disk A
+///   * `b` Disk B
+///
+/// # Return
+///
+///   * DiskDiff
+///
+pub fn diff<'a>(a: &'a Disk, b: &'a Disk) -> DiskDiff<'a> {
+    let mut result = DiskDiff {
+        header: diff_header(a, b),
+        ..DiskDiff::default()
+    };
+
+    let mut sectors_a = keyed_sectors(a);
+    let mut sectors_b = keyed_sectors(b);
+    sectors_a.sort_by_key(|(key, _)| *key);
+    sectors_b.sort_by_key(|(key, _)| *key);
+
+    // Merge two sorted lists
+    let mut it_a = sectors_a.into_iter().peekable();
+    let mut it_b = sectors_b.into_iter().peekable();
+    loop {
+        let order = match (it_a.peek(), it_b.peek()) {
+            (Some((key_a, _)), Some((key_b, _))) => key_a.cmp(key_b),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => break,
+        };
+
+        match order {
+            std::cmp::Ordering::Less => {
+                let (key, a) = it_a.next().unwrap();
+                result.only_a_count += 1;
+                result.sectors.push(SectorDiff::OnlyA { key, a });
+            }
+            std::cmp::Ordering::Greater => {
+                let (key, b) = it_b.next().unwrap();
+                result.only_b_count += 1;
+                result.sectors.push(SectorDiff::OnlyB { key, b });
+            }
+            std::cmp::Ordering::Equal => {
+                let (key, a) = it_a.next().unwrap();
+                let (_, b) = it_b.next().unwrap();
+
+                let fields = diff_sector_header(a, b);
+                let data = diff_data(&a.data, &b.data);
+                if fields.is_empty() && data.is_empty() {
+                    result.same_count += 1;
+                } else {
+                    result.changed_count += 1;
+                    result.sectors.push(SectorDiff::Changed {
+                        key,
+                        a,
+                        b,
+                        fields,
+                        data,
+                    });
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// Compare Disk Header (Helper function)
+///
+/// トラックオフセットテーブルは配置に依存するので比較しない。
+///
+fn diff_header(a: &Disk, b: &Disk) -> Vec<FieldDiff> {
+    let mut fields = Vec::new();
+
+    push_field(&mut fields, "Name", a.get_disk_name(), b.get_disk_name());
+    push_field(
+        &mut fields,
+        "Protect",
+        a.get_disk_write_protect(),
+        b.get_disk_write_protect(),
+    );
+    push_field(&mut fields, "Type", a.get_disk_type(), b.get_disk_type());
+    push_field(
+        &mut fields,
+        "DiskSize",
+        a.get_disk_size(),
+        b.get_disk_size(),
+    );
+
+    fields
+}
+
+/// Compare Sector Header (Helper function)
+///
+fn diff_sector_header(a: &Sector, b: &Sector) -> Vec<FieldDiff> {
+    let mut fields = Vec::new();
+
+    push_field(&mut fields, "Track", a.get_track(), b.get_track());
+    push_field(&mut fields, "Side", a.get_side(), b.get_side());
+    push_field(
+        &mut fields,
+        "Size",
+        a.get_sector_size(),
+        b.get_sector_size(),
+    );
+    push_field(
+        &mut fields,
+        "NumOfSector",
+        a.get_num_of_sector(),
+        b.get_num_of_sector(),
+    );
+    push_field(&mut fields, "Status", a.get_status(), b.get_status());
+    push_field(&mut fields, "Density", a.get_density(), b.get_density());
+    push_field(&mut fields, "Mark", a.get_mark(), b.get_mark());
+    push_field(
+        &mut fields,
+        "DataSize",
+        format!("DataSize({} byte)", a.header.size_of_data),
+        format!("DataSize({} byte)", b.header.size_of_data),
+    );
+
+    fields
+}
+
+/// Compare Sector Data (Helper function)
+///
+/// 長さが異なる場合、短い方を超えた部分も差分とする。
+///
+/// # Return
+///
+///   * Vec<DataDiff>  Differing Byte Ranges
+///
+pub fn diff_data(a: &[u8], b: &[u8]) -> Vec<DataDiff> {
+    let mut ranges: Vec<DataDiff> = Vec::new();
+
+    for pos in 0..a.len().max(b.len()) {
+        if a.get(pos) == b.get(pos) {
+            continue;
+        }
+
+        match ranges.last_mut() {
+            Some(last) if (last.start + last.len) == pos => last.len += 1,
+            _ => ranges.push(DataDiff { start: pos, len: 1 }),
+        }
+    }
+
+    ranges
+}
+
+fn push_field(fields: &mut Vec<FieldDiff>, name: &'static str, a: String, b: String) {
+    if a != b {
+        fields.push(FieldDiff { name, a, b });
+    }
+}
+
+fn keyed_sectors(disk: &Disk) -> Vec<(SectorKey, &Sector)> {
+    let mut sectors = Vec::new();
+
+    for (idx, track) in disk.track_tbl.iter().enumerate() {
+        let (track_no, side_no) = disk.track_position(idx);
+
+        let mut sector_tbl: Vec<&Sector> = track.sector_tbl.iter().collect();
+        sector_tbl.sort_by_key(|sector| sector.offset);
+
+        let mut seen: Vec<u8> = Vec::new();
+        for sector in sector_tbl {
+            let nth = seen.iter().filter(|r| **r == sector.header.sector).count();
+            seen.push(sector.header.sector);
+
+            let key = SectorKey {
+                track: track_no,
+                side: side_no,
+                sector: sector.header.sector,
+                nth,
+            };
+            sectors.push((key, sector));
+        }
+    }
+
+    sectors
+}
+
+// ================================================================================
+//
+//  Test Code
+//
+// ================================================================================
+#[cfg(test)]
+mod test {
+    use crate::diff::{diff, diff_data, DataDiff, SectorDiff};
+    use crate::fileio::D88FileIO;
+
+    const HUBASIC: &str = "../../sample/HuBASIC_Format_2D.d88";
+    const CPM: &str = "../../sample/CPM_Format_2D_turboCPM_X1turbo.d88";
+
+    #[test]
+    fn test_diff_same() {
+        let a = D88FileIO::open(HUBASIC);
+        let mut b = D88FileIO::open(HUBASIC);
+        b.file_offset_sort();
+
+        let result = diff(&a.disk, &b.disk);
+        assert!(result.is_same());
+        assert_eq!(result.same_count, 80 * 16);
+    }
+
+    #[test]
+    fn test_diff_changed() {
+        let a = D88FileIO::open(HUBASIC);
+        let mut b = D88FileIO::open(HUBASIC);
+
+        b.disk.header.disk_name[0] = b'X';
+        b.disk.track_tbl[1].sector_tbl[0].data[3] ^= 0xff;
+        b.disk.track_tbl[1].sector_tbl[0].data[4] ^= 0xff;
+        b.disk.track_tbl[1].sector_tbl[0].header.status = 0xb0;
+        let removed = b.disk.track_tbl[2].sector_tbl.pop().unwrap();
+
+        let result = diff(&a.disk, &b.disk);
+        assert_eq!(result.header.len(), 1);
+        assert_eq!(result.header[0].name, "Name");
+        assert_eq!(result.changed_count, 1);
+        assert_eq!(result.only_a_count, 1);
+        assert_eq!(result.only_b_count, 0);
+
+        if let SectorDiff::Changed {
+            key, fields, data, ..
+        } = &result.sectors[0]
+        {
+            assert_eq!((key.track, key.side, key.sector), (0, 1, 1));
+            assert_eq!(fields.len(), 1);
+            assert_eq!(fields[0].b, "Status(Data CRC Err)");
+            assert_eq!(data, &vec![DataDiff { start: 3, len: 2 }]);
+        } else {
+            panic!("SectorDiff::Changed expected");
+        }
+
+        if let SectorDiff::OnlyA { key, a } = &result.sectors[1] {
+            assert_eq!((key.track, key.side, key.sector), (1, 0, 16));
+            assert_eq!(a.offset, removed.offset);
+        } else {
+            panic!("SectorDiff::OnlyA expected");
+        }
+    }
+
+    #[test]
+    fn test_diff_other_disk() {
+        let a = D88FileIO::open(HUBASIC);
+        let b = D88FileIO::open(CPM);
+
+        let result = diff(&a.disk, &b.disk);
+        assert_eq!(
+            result.same_count + result.changed_count,
+            a.disk.logical_sectors().len()
+        );
+        assert!(result.changed_count > 0);
+    }
+
+    #[test]
+    fn test_diff_data() {
+        assert!(diff_data(&[1, 2, 3], &[1, 2, 3]).is_empty());
+        assert_eq!(
+            diff_data(&[1, 2, 3, 4], &[0, 2, 0, 0, 5]),
+            vec![DataDiff { start: 0, len: 1 }, DataDiff { start: 2, len: 3 }]
+        );
+    }
+}
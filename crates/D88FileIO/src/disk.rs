@@ -1,15 +1,26 @@
-use std::io::{BufReader, Read};
+use std::fs;
+use std::io::{BufWriter, Read, Write};
 use std::io::{Seek, SeekFrom};
 use std::mem;
+use std::path::Path;
 
-use crate::format::D88_Header;
+use crate::charset::Charset;
+use crate::format::{D88_Header, D88_SectorHdr, ImageFormat, MAX_SECTOR};
+use crate::format::{DISK_TYPE_1D, DISK_TYPE_1DD, DISK_TYPE_2D, DISK_TYPE_2DD, DISK_TYPE_2HD};
+use crate::sector::Sector;
 use crate::track::Track;
 
+/// Disk Image
 ///
-#[derive(Default, Debug)]
+/// `track_tbl` はトラックオフセットテーブルと同じ並び。
+/// オフセットが0のエントリは空の`Track`になる。
+///
+#[derive(Default, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Disk {
     pub header: D88_Header,
     pub track_tbl: Vec<Track>,
+    pub format: ImageFormat,
 }
 
 impl Disk {
@@ -17,7 +28,7 @@ impl Disk {
     ///
     /// # Argument
     ///
-    ///   * `reader` &mut BufReader<std::fs::File>
+    ///   * `reader` Reader (Read + Seek)
     ///
     /// # Return
     ///
@@ -25,7 +36,7 @@ impl Disk {
     ///   * Err(())    
     ///
     #[allow(clippy::result_unit_err)]
-    pub fn preset(&mut self, reader: &mut BufReader<std::fs::File>) -> Result<usize, ()> {
+    pub fn preset<R: Read + Seek>(&mut self, reader: &mut R) -> Result<usize, ()> {
         if reader.seek(SeekFrom::Start(0)).is_err() {
             return Err(());
         }
@@ -43,6 +54,10 @@ impl Disk {
             }
             self.header = header;
 
+            if self.is_single_sided_media() {
+                self.format = ImageFormat::D77;
+            }
+
             self.preset_track(reader) // return Ok(disk_size :usize)
         } else {
             Err(())
@@ -50,17 +65,25 @@ impl Disk {
         //
     }
 
+    /// Disk Name (Helper function)
     ///
+    /// ディスク名はShift-JISとして読む。
     ///
     pub fn get_disk_name(&self) -> String {
-        let disk_name;
-        unsafe {
-            disk_name = String::from_utf8_unchecked(self.header.disk_name.to_vec());
-        }
-        format!("Name({})", disk_name)
+        format!("Name({})", self.get_disk_name_with(Charset::Sjis))
     }
 
+    /// Disk Name in Character Set
     ///
+    /// 最初のNULまでを`charset`で変換する(表示できないバイトは'.')。
+    ///
+    pub fn get_disk_name_with(&self, charset: Charset) -> String {
+        let name = &self.header.disk_name;
+        let name_len = name.iter().position(|&c| c == 0).unwrap_or(name.len());
+        charset.decode(&name[..name_len])
+    }
+
+    /// Write Protect (Helper function)
     ///
     pub fn get_disk_write_protect(&self) -> String {
         format!(
@@ -73,31 +96,282 @@ impl Disk {
         )
     }
 
-    ///
+    /// Media Type (Helper function)
     ///
     pub fn get_disk_type(&self) -> String {
+        format!("Type({} Disk)", self.get_disk_type_name())
+    }
+
+    /// Media Type Name ("2D", "2DD", "2HD", "1D", "1DD", "??")
+    ///
+    pub fn get_disk_type_name(&self) -> &'static str {
+        match self.header.disk_type {
+            DISK_TYPE_2D => "2D",
+            DISK_TYPE_2DD => "2DD",
+            DISK_TYPE_2HD => "2HD",
+            DISK_TYPE_1D => "1D",
+            DISK_TYPE_1DD => "1DD",
+            _ => "??",
+        }
+    }
+
+    /// Disk Size (Helper function)
+    ///
+    pub fn get_disk_size(&self) -> String {
+        format!("DiskSize({} byte)", self.header.disk_size)
+    }
+
+    /// Image Format (Helper function)
+    ///
+    pub fn get_image_format(&self) -> String {
         format!(
-            "Type({} Disk)",
-            match self.header.disk_type {
-                0x00 => "2D",
-                0x10 => "2DD",
-                0x20 => "2HD",
-                _ => "??",
+            "Format({})",
+            match self.format {
+                ImageFormat::D88 => "D88",
+                ImageFormat::D77 => "D77",
             }
         )
     }
 
+    /// Single-sided Media (1D/1DD)
     ///
+    /// 片面メディアかどうか
     ///
-    pub fn get_disk_size(&self) -> String {
-        format!("DiskSize({} byte)", self.header.disk_size)
+    pub fn is_single_sided_media(&self) -> bool {
+        matches!(self.header.disk_type, DISK_TYPE_1D | DISK_TYPE_1DD)
+    }
+
+    /// Only even entries of Track Offset Table are used
+    ///
+    /// 片面イメージで、トラックテーブルの偶数エントリ(表面)だけが使われているか
+    ///
+    pub fn is_even_track_only(&self) -> bool {
+        let tbl = &self.header.track_offset_tbl;
+
+        let even = tbl.iter().step_by(2).filter(|ofst| **ofst != 0).count();
+        let odd = tbl
+            .iter()
+            .skip(1)
+            .step_by(2)
+            .filter(|ofst| **ofst != 0)
+            .count();
+
+        (even > 1) && (odd == 0)
+    }
+
+    /// Number of Sides
+    ///
+    /// # Return
+    ///
+    ///   * 1 or 2
+    ///
+    pub fn number_of_side(&self) -> usize {
+        if self.is_single_sided_media() || self.is_even_track_only() {
+            1
+        } else {
+            2
+        }
+    }
+
+    /// Index to `track_tbl`
+    ///
+    /// 両面ディスクでは `(track * 2) + side`。  
+    /// 片面ディスクでは、偶数エントリのみのテーブルなら `track * 2`、
+    /// 詰めて並んだテーブルなら `track`。
+    ///
+    /// # Argument
+    ///
+    ///   * `track` Track Number (0 Start)
+    ///   * `side`  Side  Number (0 Start)
+    ///
+    /// # Return
+    ///
+    ///   * Some(usize)  Index to `track_tbl`
+    ///   * None         No such track
+    ///
+    pub fn track_index(&self, track: usize, side: usize) -> Option<usize> {
+        self.layout_index(track, side)
+            .filter(|idx| *idx < self.track_tbl.len())
+    }
+
+    /// Index to `track_tbl` in this Layout
+    ///
+    /// `track_index()` から `track_tbl` の長さのチェックを除いたもの。
+    /// 別のディスクのトラックを同じ並びに置く時に使う。
+    ///
+    /// # Return
+    ///
+    ///   * Some(usize)  Index to `track_tbl`
+    ///   * None         No such side in this layout
+    ///
+    pub fn layout_index(&self, track: usize, side: usize) -> Option<usize> {
+        if self.is_even_track_only() {
+            (side == 0).then_some(track * 2)
+        } else if self.is_single_sided_media() {
+            (side == 0).then_some(track)
+        } else {
+            (side < 2).then_some((track * 2) + side)
+        }
+    }
+
+    /// Get Track
+    ///
+    /// # Argument
+    ///
+    ///   * `track` Track Number (0 Start)
+    ///   * `side`  Side  Number (0 Start)
+    ///
+    /// # Return
+    ///
+    ///   * Some(&Track)
+    ///   * None
+    ///
+    pub fn get_track(&self, track: usize, side: usize) -> Option<&Track> {
+        self.track_index(track, side)
+            .map(|idx| &self.track_tbl[idx])
+    }
+
+    /// Track and Side of `track_tbl` Index
+    ///
+    /// `track_index()` の逆変換
+    ///
+    /// # Argument
+    ///
+    ///   * `idx` Index to `track_tbl`
+    ///
+    /// # Return
+    ///
+    ///   * (Track Number, Side Number)
+    ///
+    pub fn track_position(&self, idx: usize) -> (usize, usize) {
+        if self.is_even_track_only() {
+            (idx / 2, 0)
+        } else if self.is_single_sided_media() {
+            (idx, 0)
+        } else {
+            (idx / 2, idx % 2)
+        }
+    }
+
+    /// `track_tbl` Index is a real (Track, Side)
+    ///
+    /// 偶数トラックだけのイメージの奇数番目など、`track_position()` が
+    /// 別のインデックスと同じ (Track, Side) を返す位置は `false`。
+    ///
+    /// # Argument
+    ///
+    ///   * `idx` Index to `track_tbl`
+    ///
+    pub fn is_track_slot(&self, idx: usize) -> bool {
+        let (track, side) = self.track_position(idx);
+        self.layout_index(track, side) == Some(idx)
+    }
+
+    /// Sectors in Logical Order
+    ///
+    /// トラックテーブル順、トラック内はセクタ番号(R)順に並べたセクタ一覧。
+    /// `track_tbl` の並び(`sector_sort`/`file_offset_sort`)には影響されない。
+    ///
+    /// # Return
+    ///
+    ///   * Vec<(Index to `track_tbl`, &Sector)>
+    ///
+    pub fn logical_sectors(&self) -> Vec<(usize, &Sector)> {
+        let mut sectors = Vec::new();
+
+        for (idx, track) in self.track_tbl.iter().enumerate() {
+            let mut sector_tbl: Vec<&Sector> = track.sector_tbl.iter().collect();
+            sector_tbl.sort_by_key(|sector| (sector.header.sector, sector.offset));
+
+            sectors.extend(sector_tbl.into_iter().map(|sector| (idx, sector)));
+        }
+
+        sectors
+    }
+
+    /// Update Track Offset Table, Disk Size and Sector Offset
+    ///
+    /// `track_tbl` と `sector_tbl` の並び順でファイル上に配置し直す。
+    /// セクタを追加・削除した後、書き込む前に呼ぶ。
+    ///
+    pub fn update_offset(&mut self) {
+        let mut offset = mem::size_of::<D88_Header>();
+
+        self.header.track_offset_tbl = [0u32; MAX_SECTOR as usize];
+        for (idx, track) in self.track_tbl.iter_mut().enumerate() {
+            if track.sector_tbl.is_empty() || (idx >= MAX_SECTOR as usize) {
+                continue;
+            }
+            self.header.track_offset_tbl[idx] = offset as u32;
+
+            for sector in track.sector_tbl.iter_mut() {
+                offset += mem::size_of::<D88_SectorHdr>();
+                sector.offset = offset as u64;
+                offset += sector.data.len();
+            }
+        }
+
+        self.header.disk_size = offset as u32;
+    }
+
+    /// Write Disk
+    ///
+    /// D88ファイルのバイトイメージを書き込む。
+    /// `update_offset()` で配置を更新してから呼ぶこと。
+    ///
+    /// # Argument
+    ///
+    ///   * `writer` Writer
+    ///
+    /// # Return
+    ///
+    ///   * Ok(usize)  Written Size
+    ///   * Err(())
+    ///
+    #[allow(clippy::result_unit_err)]
+    pub fn write<W: Write>(&self, writer: &mut W) -> Result<usize, ()> {
+        let byte_img;
+        unsafe {
+            byte_img =
+                mem::transmute::<D88_Header, [u8; mem::size_of::<D88_Header>()]>(self.header);
+        }
+        writer.write_all(&byte_img).map_err(|_| ())?;
+
+        let mut disk_size = byte_img.len();
+        for track in self.track_tbl.iter() {
+            disk_size += track.write(writer)?;
+        }
+
+        Ok(disk_size)
+    }
+
+    /// Save as D88 File
+    ///
+    /// # Argument
+    ///
+    ///   * `path` D88 File Path
+    ///
+    /// # Return
+    ///
+    ///   * Ok(usize)  Written Size
+    ///   * Err(())
+    ///
+    #[allow(clippy::result_unit_err)]
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<usize, ()> {
+        let fh = fs::File::create(path).map_err(|_| ())?;
+        let mut writer = BufWriter::new(fh);
+
+        let disk_size = self.write(&mut writer)?;
+        writer.flush().map_err(|_| ())?;
+
+        Ok(disk_size)
     }
 
     /// Read Track and Sector
     ///
     /// # Argument
     ///
-    ///   * `reader` &mut BufReader<std::fs::File>
+    ///   * `reader` Reader (Read + Seek)
     ///
     /// # Return
     ///
@@ -105,16 +379,27 @@ impl Disk {
     ///   * Err(())    
     ///
     #[allow(clippy::result_unit_err)]
-    pub fn preset_track(&mut self, reader: &mut BufReader<std::fs::File>) -> Result<usize, ()> {
+    pub fn preset_track<R: Read + Seek>(&mut self, reader: &mut R) -> Result<usize, ()> {
         let mut disk_size: usize = 0;
 
-        for track_offset in self.header.track_offset_tbl {
+        // Last Used Entry at Track Offset Table
+        let num_of_entry = self
+            .header
+            .track_offset_tbl
+            .iter()
+            .rposition(|ofst| *ofst != 0)
+            .map_or(0, |pos| pos + 1);
+
+        for track_offset in self.header.track_offset_tbl[..num_of_entry].iter() {
             let mut track = Track::default();
 
-            if let Ok(track_size) = track.preset(reader, track_offset as u64) {
-                disk_size += track_size as usize;
-            } else {
-                break;
+            // Unused Entry (e.g. back side of single-sided Disk)
+            if *track_offset != 0 {
+                if let Ok(track_size) = track.preset(reader, *track_offset as u64) {
+                    disk_size += track_size;
+                } else {
+                    break;
+                }
             }
             self.track_tbl.push(track);
         }
@@ -0,0 +1,342 @@
+use std::io::{Seek, SeekFrom, Write};
+use std::mem;
+
+use crate::disk::Disk;
+use crate::format::D88_SectorHdr;
+use crate::sector::Sector;
+
+/// Byte Position in a Sector
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BytePos {
+    /// Sector Header (0-15)
+    Header(usize),
+    /// Sector Data (0 Start)
+    Data(usize),
+}
+
+/// An Edit (1 byte)
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Edit {
+    pub track_idx: usize,
+    pub sector_idx: usize,
+    pub pos: BytePos,
+    pub old: u8,
+    pub new: u8,
+}
+
+/// Changed Sector
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SectorChange {
+    pub track_idx: usize,
+    pub sector_idx: usize,
+    /// Changed Bytes in Sector Header
+    pub header_bytes: usize,
+    /// Changed Bytes in Sector Data
+    pub data_bytes: usize,
+}
+
+/// Sector Editor
+///
+/// セクタヘッダとデータを1バイト単位で書き換え、元に戻す/やり直す。
+/// セクタの並びとサイズは変えないので、保存は変更したバイトだけを書き込む。
+///
+#[derive(Debug, Clone)]
+pub struct Editor {
+    pub disk: Disk,
+    original: Disk,
+    undo_list: Vec<Edit>,
+    redo_list: Vec<Edit>,
+}
+
+/// Offset of `size_of_data` in Sector Header
+const SIZE_OF_DATA_POS: usize = 14;
+
+impl Editor {
+    /// Constructor
+    ///
+    pub fn new(disk: Disk) -> Self {
+        Self {
+            original: disk.clone(),
+            disk,
+            undo_list: Vec::new(),
+            redo_list: Vec::new(),
+        }
+    }
+
+    /// Get a Byte
+    ///
+    /// # Return
+    ///
+    ///   * Some(u8)
+    ///   * None      Out of Range
+    ///
+    pub fn get_byte(&self, track_idx: usize, sector_idx: usize, pos: BytePos) -> Option<u8> {
+        let sector = get_sector(&self.disk, track_idx, sector_idx)?;
+        read_byte(sector, pos)
+    }
+
+    /// Set a Byte
+    ///
+    /// 値が変わる場合だけ履歴に積み、やり直しの履歴は消す。
+    /// セクタヘッダの `size_of_data` はファイル上の配置が変わるので書き換えられない。
+    ///
+    /// # Argument
+    ///
+    ///   * `track_idx`  Index to `track_tbl`
+    ///   * `sector_idx` Index to `sector_tbl`
+    ///   * `pos`        Byte Position
+    ///   * `value`      New Value
+    ///
+    /// # Return
+    ///
+    ///   * Ok(())
+    ///   * Err(String)  Error Message
+    ///
+    pub fn set_byte(
+        &mut self,
+        track_idx: usize,
+        sector_idx: usize,
+        pos: BytePos,
+        value: u8,
+    ) -> Result<(), String> {
+        if let BytePos::Header(idx) = pos {
+            if (SIZE_OF_DATA_POS..SIZE_OF_DATA_POS + 2).contains(&idx) {
+                return Err("Data size of sector header can't be edited".to_string());
+            }
+        }
+
+        let old = self
+            .get_byte(track_idx, sector_idx, pos)
+            .ok_or_else(|| "Out of sector".to_string())?;
+        if old == value {
+            return Ok(());
+        }
+
+        let edit = Edit {
+            track_idx,
+            sector_idx,
+            pos,
+            old,
+            new: value,
+        };
+        self.apply(&edit, value);
+        self.undo_list.push(edit);
+        self.redo_list.clear();
+        Ok(())
+    }
+
+    /// Undo
+    ///
+    /// # Return
+    ///
+    ///   * Some(Edit)  Undone Edit
+    ///   * None        No History
+    ///
+    pub fn undo(&mut self) -> Option<Edit> {
+        let edit = self.undo_list.pop()?;
+        self.apply(&edit, edit.old);
+        self.redo_list.push(edit);
+        Some(edit)
+    }
+
+    /// Redo
+    ///
+    /// # Return
+    ///
+    ///   * Some(Edit)  Redone Edit
+    ///   * None        No History
+    ///
+    pub fn redo(&mut self) -> Option<Edit> {
+        let edit = self.redo_list.pop()?;
+        self.apply(&edit, edit.new);
+        self.undo_list.push(edit);
+        Some(edit)
+    }
+
+    /// Byte differs from the Original
+    ///
+    pub fn is_modified(&self, track_idx: usize, sector_idx: usize, pos: BytePos) -> bool {
+        let original = get_sector(&self.original, track_idx, sector_idx)
+            .and_then(|sector| read_byte(sector, pos));
+        original != self.get_byte(track_idx, sector_idx, pos)
+    }
+
+    /// Changed Sectors (compared with the Original)
+    ///
+    /// 書き換えて元の値に戻したバイトは含まない。
+    ///
+    pub fn changes(&self) -> Vec<SectorChange> {
+        let mut changes = Vec::new();
+        for (track_idx, track) in self.disk.track_tbl.iter().enumerate() {
+            for (sector_idx, sector) in track.sector_tbl.iter().enumerate() {
+                let original = &self.original.track_tbl[track_idx].sector_tbl[sector_idx];
+
+                let count =
+                    |a: &[u8], b: &[u8]| a.iter().zip(b.iter()).filter(|(a, b)| a != b).count();
+                let header_bytes = count(&header_bytes(sector), &header_bytes(original));
+                let data_bytes = count(&sector.data, &original.data);
+                if header_bytes + data_bytes > 0 {
+                    changes.push(SectorChange {
+                        track_idx,
+                        sector_idx,
+                        header_bytes,
+                        data_bytes,
+                    });
+                }
+            }
+        }
+        changes
+    }
+
+    /// Write Changed Sectors
+    ///
+    /// 変更したセクタのヘッダとデータを、元のファイルオフセットに書き込む。
+    /// `writer` は元のD88ファイル(またはそのコピー)であること。
+    /// 書き込んだ後は、今の内容を元の内容とする。
+    ///
+    /// # Argument
+    ///
+    ///   * `writer` Writer (D88 File)
+    ///
+    /// # Return
+    ///
+    ///   * Ok(usize)  Number of Written Sectors
+    ///   * Err(())
+    ///
+    #[allow(clippy::result_unit_err)]
+    pub fn write_changes<W: Write + Seek>(&mut self, writer: &mut W) -> Result<usize, ()> {
+        let changes = self.changes();
+        for change in changes.iter() {
+            let sector = &self.disk.track_tbl[change.track_idx].sector_tbl[change.sector_idx];
+            let header_offset = sector.offset - mem::size_of::<D88_SectorHdr>() as u64;
+
+            writer
+                .seek(SeekFrom::Start(header_offset))
+                .map_err(|_| ())?;
+            writer.write_all(&header_bytes(sector)).map_err(|_| ())?;
+            writer.write_all(&sector.data).map_err(|_| ())?;
+        }
+        writer.flush().map_err(|_| ())?;
+
+        self.original = self.disk.clone();
+        Ok(changes.len())
+    }
+
+    /// Apply a Value (Helper function)
+    ///
+    fn apply(&mut self, edit: &Edit, value: u8) {
+        let sector = &mut self.disk.track_tbl[edit.track_idx].sector_tbl[edit.sector_idx];
+        match edit.pos {
+            BytePos::Header(idx) => {
+                let mut byte_img = header_bytes(sector);
+                byte_img[idx] = value;
+                unsafe {
+                    sector.header = mem::transmute::<
+                        [u8; mem::size_of::<D88_SectorHdr>()],
+                        D88_SectorHdr,
+                    >(byte_img);
+                }
+            }
+            BytePos::Data(idx) => sector.data[idx] = value,
+        }
+    }
+}
+
+/// Get Sector (Helper function)
+///
+fn get_sector(disk: &Disk, track_idx: usize, sector_idx: usize) -> Option<&Sector> {
+    disk.track_tbl.get(track_idx)?.sector_tbl.get(sector_idx)
+}
+
+/// Read a Byte (Helper function)
+///
+fn read_byte(sector: &Sector, pos: BytePos) -> Option<u8> {
+    match pos {
+        BytePos::Header(idx) => header_bytes(sector).get(idx).copied(),
+        BytePos::Data(idx) => sector.data.get(idx).copied(),
+    }
+}
+
+/// Sector Header Byte Image (Helper function)
+///
+fn header_bytes(sector: &Sector) -> [u8; mem::size_of::<D88_SectorHdr>()] {
+    unsafe { mem::transmute::<D88_SectorHdr, [u8; mem::size_of::<D88_SectorHdr>()]>(sector.header) }
+}
+
+// ================================================================================
+//
+//  Test Code
+//
+// ================================================================================
+#[cfg(test)]
+mod test {
+    use std::fs;
+    use std::io::Cursor;
+
+    use crate::edit::{BytePos, Editor};
+    use crate::fileio::D88FileIO;
+
+    const HUBASIC: &str = "../../sample/HuBASIC_Format_2D.d88";
+
+    #[test]
+    fn test_undo_redo() {
+        let d88fileio = D88FileIO::open(HUBASIC);
+        let mut editor = Editor::new(d88fileio.disk);
+
+        let old = editor.get_byte(0, 0, BytePos::Data(0)).unwrap();
+        assert!(editor.set_byte(0, 0, BytePos::Data(0), !old).is_ok());
+        assert!(editor.set_byte(0, 0, BytePos::Data(1), 0x55).is_ok());
+        assert!(editor.is_modified(0, 0, BytePos::Data(0)));
+        assert_eq!(editor.changes().len(), 1);
+
+        let edit = editor.undo().unwrap();
+        assert_eq!(edit.pos, BytePos::Data(1));
+        editor.undo();
+        assert!(editor.undo().is_none());
+        assert_eq!(editor.get_byte(0, 0, BytePos::Data(0)), Some(old));
+        assert!(editor.changes().is_empty());
+
+        editor.redo();
+        assert_eq!(editor.get_byte(0, 0, BytePos::Data(0)), Some(!old));
+
+        // New edit clears redo
+        assert!(editor.set_byte(1, 0, BytePos::Header(2), 9).is_ok());
+        assert!(editor.redo().is_none());
+        assert_eq!(editor.disk.track_tbl[1].sector_tbl[0].header.sector, 9);
+
+        assert!(editor.set_byte(1, 0, BytePos::Header(14), 0).is_err());
+        assert!(editor.set_byte(0, 0, BytePos::Data(256), 0).is_err());
+    }
+
+    #[test]
+    fn test_write_changes() {
+        let img = fs::read(HUBASIC).unwrap();
+        let d88fileio = D88FileIO::open(HUBASIC);
+        let mut editor = Editor::new(d88fileio.disk);
+
+        assert!(editor.set_byte(0, 1, BytePos::Data(0x10), 0xaa).is_ok());
+        assert!(editor.set_byte(2, 3, BytePos::Header(7), 0x10).is_ok()); // Deleted Mark
+        assert_eq!(
+            editor.disk.track_tbl[2].sector_tbl[3].header.deleted_mark,
+            0x10
+        );
+        let changes = editor.changes();
+        assert_eq!(changes.len(), 2);
+        assert_eq!((changes[0].header_bytes, changes[0].data_bytes), (0, 1));
+        assert_eq!((changes[1].header_bytes, changes[1].data_bytes), (1, 0));
+
+        let mut file = Cursor::new(img.clone());
+        assert_eq!(editor.write_changes(&mut file), Ok(2));
+        assert!(editor.changes().is_empty());
+
+        let patched = file.into_inner();
+        assert_eq!(patched.len(), img.len());
+        let data_offset = editor.disk.track_tbl[0].sector_tbl[1].offset as usize;
+        let header_offset = editor.disk.track_tbl[2].sector_tbl[3].offset as usize - 16;
+        let diff: Vec<usize> = (0..img.len()).filter(|i| img[*i] != patched[*i]).collect();
+        assert_eq!(diff, vec![data_offset + 0x10, header_offset + 7]);
+    }
+}
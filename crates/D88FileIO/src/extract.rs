@@ -0,0 +1,216 @@
+use std::io::Write;
+
+use crate::disk::Disk;
+use crate::format::D88_SectorHdr;
+use crate::sector::Sector;
+use crate::select::Selection;
+
+/// Status of padded Sector (No Address Mark)
+pub const STATUS_PADDED: u8 = 0xe0;
+
+/// Extract Option
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExtractOption {
+    /// Write D88 Sector Header (16 byte) before each Sector Data
+    pub with_header: bool,
+    /// Fill missing Sectors with this byte
+    pub pad: Option<u8>,
+}
+
+/// Sectors to Extract
+///
+/// 選択したセクタを論理順(トラック順、トラック内はセクタID順)に並べる。
+///
+/// `pad` 指定時は、各トラックの 1..`number_of_sec` で欠けているIDを
+/// その値で埋めたセクタ(Status `STATUS_PADDED`)で補う。
+/// この場合 LBA は補ったセクタも含めて数える。
+/// セクタが1つも無いトラックは補わない。
+///
+/// # Argument
+///
+///   * `disk`      Disk
+///   * `selection` Selection
+///   * `pad`       Fill byte for missing Sectors
+///
+/// # Return
+///
+///   * Vec<Sector>
+///
+pub fn extract_sectors(disk: &Disk, selection: &Selection, pad: Option<u8>) -> Vec<Sector> {
+    let fill = if let Some(fill) = pad {
+        fill
+    } else {
+        return selection
+            .select(disk)
+            .into_iter()
+            .map(|(_, sector)| sector.clone())
+            .collect();
+    };
+
+    let mut result: Vec<(usize, usize, Sector)> = Vec::new(); // (Track, Side, Sector)
+    for (idx, track) in disk.track_tbl.iter().enumerate() {
+        let template = if let Some(sector) = track.sector_tbl.first() {
+            sector
+        } else {
+            continue;
+        };
+        let (track_no, side_no) = disk.track_position(idx);
+
+        let mut sector_tbl: Vec<Sector> = track.sector_tbl.to_vec();
+        sector_tbl.sort_by_key(|sector| (sector.header.sector, sector.offset));
+
+        let number_of_sec = track
+            .sector_tbl
+            .iter()
+            .map(|sector| sector.header.number_of_sec)
+            .max()
+            .unwrap_or(0);
+        for id in 1..=number_of_sec.min(u8::MAX as u16) as u8 {
+            if track
+                .sector_tbl
+                .iter()
+                .any(|sector| sector.header.sector == id)
+            {
+                continue;
+            }
+
+            let header = D88_SectorHdr {
+                track: track_no as u8,
+                side: side_no as u8,
+                sector: id,
+                sector_size: template.header.sector_size,
+                number_of_sec: template.header.number_of_sec,
+                density: template.header.density,
+                status: STATUS_PADDED,
+                size_of_data: template.data.len() as u16,
+                ..D88_SectorHdr::default()
+            };
+            sector_tbl.push(Sector {
+                offset: 0,
+                header,
+                data: vec![fill; template.data.len()],
+            });
+        }
+        sector_tbl.sort_by_key(|sector| sector.header.sector); // stable
+
+        result.extend(
+            sector_tbl
+                .into_iter()
+                .map(|sector| (track_no, side_no, sector)),
+        );
+    }
+
+    result
+        .into_iter()
+        .enumerate()
+        .filter(|(lba, (track_no, side_no, sector))| {
+            selection.matches(*track_no, *side_no, sector.header.sector as usize, *lba)
+        })
+        .map(|(_, (_, _, sector))| sector)
+        .collect()
+}
+
+/// Extract Sector Data
+///
+/// # Argument
+///
+///   * `disk`      Disk
+///   * `selection` Selection
+///   * `option`    Extract Option
+///   * `writer`    Writer
+///
+/// # Return
+///
+///   * Ok(usize)  Written Size
+///   * Err(())
+///
+#[allow(clippy::result_unit_err)]
+pub fn extract<W: Write>(
+    disk: &Disk,
+    selection: &Selection,
+    option: &ExtractOption,
+    writer: &mut W,
+) -> Result<usize, ()> {
+    let mut size = 0;
+
+    for sector in extract_sectors(disk, selection, option.pad).iter() {
+        if option.with_header {
+            size += sector.write(writer)?;
+        } else {
+            writer.write_all(&sector.data).map_err(|_| ())?;
+            size += sector.data.len();
+        }
+    }
+
+    Ok(size)
+}
+
+// ================================================================================
+//
+//  Test Code
+//
+// ================================================================================
+#[cfg(test)]
+mod test {
+    use crate::extract::{extract, extract_sectors, ExtractOption, STATUS_PADDED};
+    use crate::fileio::D88FileIO;
+    use crate::select::Selection;
+
+    const HUBASIC: &str = "../../sample/HuBASIC_Format_2D.d88";
+
+    #[test]
+    fn test_extract_whole_disk() {
+        let d88fileio = D88FileIO::open(HUBASIC);
+
+        let mut img: Vec<u8> = Vec::new();
+        let size = extract(
+            &d88fileio.disk,
+            &Selection::default(),
+            &ExtractOption::default(),
+            &mut img,
+        );
+        assert_eq!(size, Ok(80 * 16 * 256));
+        assert_eq!(
+            &img[..256],
+            &d88fileio.disk.track_tbl[0].sector_tbl[0].data[..]
+        );
+
+        let mut img: Vec<u8> = Vec::new();
+        let option = ExtractOption {
+            with_header: true,
+            pad: None,
+        };
+        let mut selection = Selection::default();
+        assert!(selection.parse_position("0,0,1").is_ok());
+        assert_eq!(
+            extract(&d88fileio.disk, &selection, &option, &mut img),
+            Ok(16 + 256)
+        );
+        assert_eq!(&img[..4], &[0, 0, 1, 1]);
+    }
+
+    #[test]
+    fn test_extract_pad() {
+        let mut d88fileio = D88FileIO::open(HUBASIC);
+        d88fileio.disk.track_tbl[1].sector_tbl.remove(2); // Track 0 Side 1 Sector 3
+
+        let mut selection = Selection::default();
+        assert!(selection.parse_position("0,1,*").is_ok());
+
+        assert_eq!(extract_sectors(&d88fileio.disk, &selection, None).len(), 15);
+
+        let sectors = extract_sectors(&d88fileio.disk, &selection, Some(0xe5));
+        assert_eq!(sectors.len(), 16);
+        assert_eq!(sectors[2].header.sector, 3);
+        assert_eq!(sectors[2].header.status, STATUS_PADDED);
+        assert_eq!(sectors[2].data, vec![0xe5; 256]);
+
+        // LBA counts padded sectors
+        let mut selection = Selection::default();
+        assert!(selection.set_lba("18").is_ok());
+        let sectors = extract_sectors(&d88fileio.disk, &selection, Some(0));
+        assert_eq!(sectors[0].header.sector, 3);
+        assert_eq!(sectors[0].header.status, STATUS_PADDED);
+    }
+}
@@ -1,22 +1,28 @@
 use std::fs;
-use std::io::{BufReader, Read};
+use std::io::{BufReader, Cursor, Read};
 use std::io::{Seek, SeekFrom};
 use std::mem;
 /// Report D88 File
 use std::path::Path;
 
-use crate::format::D88_Header;
+use crate::format::{D88_Header, ImageFormat};
 
+use crate::archive::{is_archive_source, read_source};
 use crate::disk::Disk;
 use crate::sector::Sector;
 
+/// Reader of Disk Image (File, Memory)
 ///
-///
+pub trait ReadSeek: Read + Seek + Send {}
+
+impl<T: Read + Seek + Send> ReadSeek for T {}
+
+/// D88 File Access
 ///
 #[derive(Default)]
 #[allow(non_snake_case)]
 pub struct D88FileIO {
-    pub reader: Option<BufReader<std::fs::File>>,
+    pub reader: Option<Box<dyn ReadSeek>>,
     pub disk: Disk,
 }
 
@@ -44,13 +50,13 @@ impl D88FileIO {
     ///
     /// # Argument
     ///
-    ///   * `reader` &mut BufReader<std::fs::File>
+    ///   * `reader` Reader (Read + Seek)
     ///
     /// # Return
     ///
     ///   * (none)
     ///
-    fn _read_disk_parameter(reader: &mut BufReader<std::fs::File>) -> Result<Disk, ()> {
+    fn _read_disk_parameter<R: Read + Seek>(reader: &mut R) -> Result<Disk, ()> {
         let mut disk = Disk::default();
         if disk.preset(reader).is_ok() {
             Ok(disk)
@@ -89,18 +95,88 @@ impl D88FileIO {
     ///  }
     ///
     pub fn open<P: AsRef<Path>>(path: P) -> Self {
-        if let Ok(mut reader) = D88FileIO::_open(path) {
-            if let Ok(disk_) = D88FileIO::_read_disk_parameter(&mut reader) {
-                return Self {
-                    reader: Some(reader),
-                    disk: disk_,
-                };
+        // "-", "archive.zip", "archive.zip:disk2.d88"
+        let source = path.as_ref().to_string_lossy().to_string();
+        if is_archive_source(&source) {
+            return match read_source(&source) {
+                Ok(mut entries) => {
+                    let entry = entries.remove(0);
+                    let format = D88FileIO::image_format_from_path(&entry.name);
+                    D88FileIO::from_reader(Cursor::new(entry.data), format)
+                }
+                Err(_) => Self::default(),
+            };
+        }
+
+        let format = D88FileIO::image_format_from_path(&path);
+        if let Ok(reader) = D88FileIO::_open(path) {
+            return D88FileIO::from_reader(reader, format);
+        }
+
+        Self::default()
+    }
+
+    /// Constructor with Disk Image in Memory
+    ///
+    /// # Argument
+    ///
+    ///   * `data`   Disk Image
+    ///   * `format` Image Format by File Name (`image_format_from_path()`)
+    ///
+    /// # Return
+    ///
+    ///   * D88FileIO
+    ///
+    pub fn from_bytes(data: Vec<u8>, format: Option<ImageFormat>) -> Self {
+        D88FileIO::from_reader(Cursor::new(data), format)
+    }
+
+    /// Constructor with Reader
+    ///
+    /// # Argument
+    ///
+    ///   * `reader` Reader (Read + Seek)
+    ///   * `format` Image Format by File Name (`image_format_from_path()`)
+    ///
+    /// # Return
+    ///
+    ///   * D88FileIO
+    ///
+    pub fn from_reader<R: ReadSeek + 'static>(mut reader: R, format: Option<ImageFormat>) -> Self {
+        if let Ok(mut disk_) = D88FileIO::_read_disk_parameter(&mut reader) {
+            // "*.d77" (content may look like plain D88)
+            if let Some(ImageFormat::D77) = format {
+                disk_.format = ImageFormat::D77;
             }
+            return Self {
+                reader: Some(Box::new(reader)),
+                disk: disk_,
+            };
         }
 
-        Self {
-            reader: None,
-            disk: Disk::default(),
+        Self::default()
+    }
+
+    /// Image Format from File Extension (Helper function)
+    ///
+    /// 拡張子からイメージ形式を判定する。
+    ///
+    /// # Argument
+    ///
+    ///   * `path` Disk Image File Path
+    ///
+    /// # Return
+    ///
+    ///   * Some(ImageFormat)  "*.d77" or "*.d88"
+    ///   * None               Unknown extension
+    ///
+    pub fn image_format_from_path<P: AsRef<Path>>(path: P) -> Option<ImageFormat> {
+        let ext = path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+
+        match ext.as_str() {
+            "d77" => Some(ImageFormat::D77),
+            "d88" | "88d" => Some(ImageFormat::D88),
+            _ => None,
         }
     }
 
@@ -208,14 +284,13 @@ impl D88FileIO {
     ///
     #[allow(clippy::result_unit_err)]
     pub fn get_sector(&self, track: usize, side: usize, sector: usize) -> Result<&Sector, ()> {
-        if (track >= self.disk.track_tbl.len())
-            || (side >= 2)
-            || (sector >= self.disk.track_tbl[(track * 2) + side].sector_tbl.len())
-        {
-            return Err(());
+        if let Some(trk) = self.disk.get_track(track, side) {
+            if let Some(sec) = trk.sector_tbl.get(sector) {
+                return Ok(sec);
+            }
         }
 
-        Ok(&self.disk.track_tbl[(track * 2) + side].sector_tbl[sector])
+        Err(())
     }
 } //
 
@@ -227,8 +302,34 @@ impl D88FileIO {
 #[cfg(test)]
 mod test {
     use crate::fileio::D88FileIO;
+    use crate::format::{ImageFormat, DISK_TYPE_1D};
     //use crate::format::{D88_Header, D88_SectorHdr};
 
+    /// Single-sided image, only even entries of track offset table are used.
+    fn write_single_sided_image(path: &std::path::Path, disk_type: u8) {
+        let mut img = vec![0u8; 0x2b0];
+        img[0x1b] = disk_type;
+
+        for track in 0..3u8 {
+            let offset = img.len() as u32;
+            let entry = 0x20 + (track as usize * 2) * 4;
+            img[entry..entry + 4].copy_from_slice(&offset.to_le_bytes());
+
+            let mut hdr = [0u8; 16];
+            hdr[0] = track; // C
+            hdr[2] = 1; // R
+            hdr[3] = 1; // N (256 byte)
+            hdr[4] = 1; // Number of sector
+            hdr[14..16].copy_from_slice(&256u16.to_le_bytes());
+            img.extend_from_slice(&hdr);
+            img.extend_from_slice(&[track; 256]);
+        }
+        let size = img.len() as u32;
+        img[0x1c..0x20].copy_from_slice(&size.to_le_bytes());
+
+        std::fs::write(path, img).unwrap();
+    }
+
     #[test]
     fn test_read_d88_header_disk_name() {
         let mut d88fileio = D88FileIO::open("../../sample/HuBASIC_Format_2D.d88");
@@ -251,7 +352,7 @@ mod test {
         if let Ok(hdr) = d88fileio.read_d88_header() {
             assert_eq!(hdr.write_protect, 0x00); // "Protected"
         } else {
-            assert!(false);
+            panic!("read_d88_header() failed");
         }
     }
 
@@ -262,7 +363,7 @@ mod test {
         if let Ok(hdr) = d88fileio.read_d88_header() {
             assert_eq!(hdr.disk_type, 0x00); // "2D"
         } else {
-            assert!(false);
+            panic!("read_d88_header() failed");
         }
     }
 
@@ -273,7 +374,115 @@ mod test {
         if let Ok(hdr) = d88fileio.read_d88_header() {
             assert_eq!(hdr.disk_size, 348848); // 348848
         } else {
-            assert!(false);
+            panic!("read_d88_header() failed");
+        }
+    }
+
+    #[test]
+    fn test_get_sector_double_sided() {
+        let d88fileio = D88FileIO::open("../../sample/HuBASIC_Format_2D.d88");
+
+        assert_eq!(d88fileio.disk.track_tbl.len(), 80);
+        assert_eq!(d88fileio.disk.number_of_side(), 2);
+        assert_eq!(d88fileio.disk.format, ImageFormat::D88);
+
+        let sector = d88fileio.get_sector(0, 1, 0).unwrap();
+        assert_eq!((sector.header.track, sector.header.side), (0, 1));
+
+        assert!(d88fileio.get_sector(40, 0, 0).is_err());
+        assert!(d88fileio.get_sector(0, 2, 0).is_err());
+        assert!(d88fileio.get_sector(0, 0, 16).is_err());
+    }
+
+    #[test]
+    fn test_get_sector_even_track_only() {
+        let path = std::env::temp_dir().join("d88fileio_test_even_track_only.D77");
+        write_single_sided_image(&path, DISK_TYPE_1D);
+
+        let d88fileio = D88FileIO::open(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(d88fileio.is_open());
+        assert_eq!(d88fileio.disk.format, ImageFormat::D77);
+        assert_eq!(d88fileio.disk.get_disk_type(), "Type(1D Disk)");
+        assert_eq!(d88fileio.disk.number_of_side(), 1);
+        assert_eq!(d88fileio.disk.track_tbl.len(), 5);
+
+        for track in 0..3 {
+            let sector = d88fileio.get_sector(track, 0, 0).unwrap();
+            assert_eq!(sector.header.track as usize, track);
+            assert_eq!(sector.data[0] as usize, track);
+        }
+        assert!(d88fileio.get_sector(0, 1, 0).is_err());
+        assert!(d88fileio.get_sector(3, 0, 0).is_err());
+    }
+
+    #[test]
+    fn test_open_d77_2d() {
+        // 2D media is D88 by content, D77 only by the extension
+        let path = std::env::temp_dir().join("d88fileio_test_2d.d77");
+        std::fs::copy("../../sample/HuBASIC_Format_2D.d88", &path).unwrap();
+
+        let d88fileio = D88FileIO::open(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(d88fileio.is_open());
+        assert_eq!(d88fileio.disk.format, ImageFormat::D77);
+        assert_eq!(d88fileio.disk.get_disk_type(), "Type(2D Disk)");
+        assert_eq!(d88fileio.disk.number_of_side(), 2);
+        assert_eq!(d88fileio.disk.track_tbl.len(), 80);
+
+        let sector = d88fileio.get_sector(0, 1, 0).unwrap();
+        assert_eq!((sector.header.track, sector.header.side), (0, 1));
+    }
+
+    #[test]
+    fn test_open_archive() {
+        let d88fileio = D88FileIO::open("../../sample/HuBASIC_Format_2D.lzh:hubasic.d88");
+        assert!(d88fileio.is_open());
+        assert_eq!(d88fileio.disk.track_tbl.len(), 80);
+
+        let d88fileio = D88FileIO::open("../../sample/HuBASIC_Format_2D.lzh");
+        assert!(d88fileio.is_open());
+        assert!(!D88FileIO::open("../../sample/HuBASIC_Format_2D.lzh:NONE.D88").is_open());
+
+        let data = std::fs::read("../../sample/HuBASIC_Format_2D.d88").unwrap();
+        let d88fileio = D88FileIO::from_bytes(data, None);
+        assert_eq!(d88fileio.disk.header.disk_size, 348848);
+    }
+
+    #[test]
+    fn test_image_format_from_path() {
+        assert_eq!(
+            D88FileIO::image_format_from_path("GAME.D77"),
+            Some(ImageFormat::D77)
+        );
+        assert_eq!(
+            D88FileIO::image_format_from_path("game.d88"),
+            Some(ImageFormat::D88)
+        );
+        assert_eq!(D88FileIO::image_format_from_path("game.zip"), None);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serde_json_round_trip() {
+        use crate::disk::Disk;
+
+        for path in [
+            "../../sample/HuBASIC_Format_2D.d88",
+            "../../sample/CPM_Format_2D_turboCPM_X1turbo.d88",
+        ] {
+            let d88fileio = D88FileIO::open(path);
+            assert!(d88fileio.is_open());
+
+            let json = serde_json::to_string(&d88fileio.disk).unwrap();
+            let disk: Disk = serde_json::from_str(&json).unwrap();
+            assert_eq!(serde_json::to_string(&disk).unwrap(), json);
+
+            let mut img: Vec<u8> = Vec::new();
+            assert!(disk.write(&mut img).is_ok());
+            assert_eq!(img, std::fs::read(path).unwrap());
         }
     }
 }
@@ -0,0 +1,413 @@
+use crate::disk::Disk;
+use crate::filesystem::{join_name, name_field, split_name};
+use crate::filesystem::{DirEntry, FileSystem, FileType, LBA_2D};
+
+/// CP/M 2.2
+///
+/// ディスクパラメータは DPB 相当の値で持つ。
+///
+///   * Extent 32 byte / entry (user, name8, ext3, EX, S1, S2, RC, 16 block pointers)
+///   * Record 128 byte
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cpm {
+    /// Reserved Logical Sectors (System Tracks)
+    pub reserved: usize,
+    /// Block Size (byte)
+    pub block_size: usize,
+    /// Number of Blocks (DSM + 1)
+    pub blocks: usize,
+    /// Directory Entries (DRM + 1)
+    pub dir_entries: usize,
+}
+
+const ENTRY_SIZE: usize = 32;
+const RECORD_SIZE: usize = 128;
+const DELETED: u8 = 0xe5;
+/// Records per 16KB Logical Extent
+const EXTENT_RECORDS: usize = 128;
+
+/// Raw Directory Entry (Helper struct)
+///
+#[derive(Debug, Clone)]
+struct Extent {
+    user: u8,
+    name: String,
+    /// Logical Extent Number (EX + S2 * 32)
+    number: usize,
+    rc: usize,
+    blocks: Vec<usize>,
+}
+
+impl Cpm {
+    /// X1turbo CP/M 2D
+    ///
+    ///   * System 4 track-sides, Block 2KB, DSM 151, 64 directory entries
+    ///
+    pub fn x1turbo() -> Self {
+        Self {
+            reserved: 4 * 16,
+            block_size: 2048,
+            blocks: 152,
+            dir_entries: 64,
+        }
+    }
+
+    fn block_sectors(&self) -> usize {
+        self.block_size / LBA_2D.sector_size
+    }
+
+    fn block_lba(&self, block: usize) -> usize {
+        self.reserved + block * self.block_sectors()
+    }
+
+    /// Blocks used by the Directory
+    ///
+    fn dir_blocks(&self) -> usize {
+        (self.dir_entries * ENTRY_SIZE).div_ceil(self.block_size)
+    }
+
+    /// Extents per Directory Entry - 1 (EXM)
+    ///
+    fn extent_mask(&self) -> usize {
+        // 8-bit block pointers (DSM < 256)
+        (self.block_size * 16) / (EXTENT_RECORDS * RECORD_SIZE) - 1
+    }
+
+    fn read_dir(&self, disk: &Disk) -> Result<Vec<u8>, String> {
+        let sectors = (self.dir_entries * ENTRY_SIZE) / LBA_2D.sector_size;
+        LBA_2D.read_sectors(disk, self.block_lba(0), sectors)
+    }
+
+    /// Directory Entry is valid (Helper function)
+    ///
+    fn is_valid(&self, b: &[u8]) -> bool {
+        b[0] == DELETED
+            || (b[0] <= 15
+                && b[1..12].iter().all(|c| (0x20..0x7f).contains(&(c & 0x7f)))
+                && b[15] as usize <= EXTENT_RECORDS
+                && b[16..32]
+                    .iter()
+                    .all(|block| (*block as usize) < self.blocks))
+    }
+
+    /// Used Extents (by slot)
+    ///
+    /// ブロック番号が範囲外(DSM より大きい)のエントリがあればエラー。
+    ///
+    fn extents(&self, disk: &Disk) -> Result<Vec<(usize, Extent)>, String> {
+        let dir = self.read_dir(disk)?;
+        dir.chunks(ENTRY_SIZE)
+            .enumerate()
+            .filter(|(_, b)| b[0] <= 15)
+            .map(|(slot, b)| {
+                if let Some(block) = b[16..32]
+                    .iter()
+                    .find(|block| (**block as usize) >= self.blocks)
+                {
+                    return Err(format!(
+                        "Broken directory (entry {}, block {})",
+                        slot, block
+                    ));
+                }
+
+                let name: Vec<u8> = b[1..12].iter().map(|c| c & 0x7f).collect();
+                let extent = Extent {
+                    user: b[0],
+                    name: join_name(&name[0..8], &name[8..11]),
+                    number: b[12] as usize + (b[14] as usize) * 32,
+                    rc: b[15] as usize,
+                    blocks: b[16..32]
+                        .iter()
+                        .filter(|block| **block != 0)
+                        .map(|block| *block as usize)
+                        .collect(),
+                };
+                Ok((slot, extent))
+            })
+            .collect()
+    }
+
+    /// Extents of a File, in Extent Order (Helper function)
+    ///
+    fn file_extents(&self, disk: &Disk, user: u8, name: &str) -> Result<Vec<Extent>, String> {
+        let mut extents: Vec<Extent> = self
+            .extents(disk)?
+            .into_iter()
+            .map(|(_, extent)| extent)
+            .filter(|extent| extent.user == user && extent.name == name)
+            .collect();
+        extents.sort_by_key(|extent| extent.number);
+        Ok(extents)
+    }
+
+    /// File Size of the last Extent (Helper function)
+    ///
+    fn file_size(last: &Extent) -> usize {
+        (last.number * EXTENT_RECORDS + last.rc) * RECORD_SIZE
+    }
+}
+
+impl FileSystem for Cpm {
+    fn get_name(&self) -> &'static str {
+        "CP/M"
+    }
+
+    /// Every Directory Entry is valid, and at least one file exists
+    ///
+    fn detect(&self, disk: &Disk) -> bool {
+        if !LBA_2D.fits(disk) {
+            return false;
+        }
+        match self.read_dir(disk) {
+            Ok(dir) => {
+                dir.chunks(ENTRY_SIZE).all(|b| self.is_valid(b))
+                    && dir.chunks(ENTRY_SIZE).any(|b| b[0] <= 15)
+            }
+            Err(_) => false,
+        }
+    }
+
+    fn list(&self, disk: &Disk) -> Result<Vec<DirEntry>, String> {
+        let mut entries: Vec<DirEntry> = Vec::new();
+        let mut extents: Vec<Extent> = self
+            .extents(disk)?
+            .into_iter()
+            .map(|(_, extent)| extent)
+            .collect();
+        extents.sort_by_key(|extent| extent.number);
+
+        for extent in extents.iter() {
+            let size = Cpm::file_size(extent);
+            let attr = extent.user;
+            match entries
+                .iter_mut()
+                .find(|entry| entry.attr == attr && entry.name == extent.name)
+            {
+                Some(entry) => entry.size = entry.size.max(size),
+                None => entries.push(DirEntry {
+                    name: extent.name.clone(),
+                    file_type: FileType::Other,
+                    attr,
+                    size,
+                    start: extent.blocks.first().copied().unwrap_or(0),
+                    load_addr: None,
+                    exec_addr: None,
+                }),
+            }
+        }
+
+        for entry in entries.iter_mut() {
+            let upper = entry.name.to_ascii_uppercase();
+            if upper.ends_with(".COM") {
+                entry.file_type = FileType::Binary;
+                entry.load_addr = Some(0x0100);
+                entry.exec_addr = Some(0x0100);
+            } else if upper.ends_with(".TXT") || upper.ends_with(".DOC") {
+                entry.file_type = FileType::Ascii;
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Read a File (user 0 first, record unit)
+    ///
+    fn read(&self, disk: &Disk, name: &str) -> Result<Vec<u8>, String> {
+        let entry = self
+            .list(disk)?
+            .into_iter()
+            .filter(|entry| entry.name.eq_ignore_ascii_case(name))
+            .min_by_key(|entry| entry.attr)
+            .ok_or_else(|| format!("\"{}\" Not Found", name))?;
+
+        let mut data = Vec::new();
+        for extent in self.file_extents(disk, entry.attr, &entry.name)? {
+            for block in extent.blocks.iter() {
+                data.extend(LBA_2D.read_sectors(
+                    disk,
+                    self.block_lba(*block),
+                    self.block_sectors(),
+                )?);
+            }
+        }
+        data.truncate(entry.size);
+        Ok(data)
+    }
+
+    /// Write a File (user 0)
+    ///
+    /// 最後のレコードの余りは 0x1a で埋める。
+    ///
+    fn write(
+        &self,
+        disk: &mut Disk,
+        name: &str,
+        data: &[u8],
+        _file_type: FileType,
+    ) -> Result<(), String> {
+        let name = name.to_ascii_uppercase();
+        let (base, ext) = split_name(&name);
+        let mut name_bytes = name_field(&base, 8, 0x20)?;
+        name_bytes.extend(name_field(&ext, 3, 0x20)?);
+        if self
+            .list(disk)?
+            .iter()
+            .any(|entry| entry.attr == 0 && entry.name == name)
+        {
+            return Err(format!("\"{}\" already exists", name));
+        }
+
+        let records = data.len().div_ceil(RECORD_SIZE);
+        let mut body = data.to_vec();
+        body.resize(records * RECORD_SIZE, 0x1a);
+
+        // Free Blocks and Slots
+        let used_extents = self.extents(disk)?;
+        let mut used = vec![false; self.blocks];
+        (0..self.dir_blocks()).for_each(|block| used[block] = true);
+        for (_, extent) in used_extents.iter() {
+            extent.blocks.iter().for_each(|block| used[*block] = true);
+        }
+        let blocks: Vec<usize> = (0..self.blocks)
+            .filter(|block| !used[*block])
+            .take(body.len().div_ceil(self.block_size))
+            .collect();
+        if blocks.len() < body.len().div_ceil(self.block_size) {
+            return Err("Disk full".to_string());
+        }
+
+        let block_chunks: Vec<&[usize]> = if blocks.is_empty() {
+            vec![&[]]
+        } else {
+            blocks.chunks(16).collect()
+        };
+        let mut slots =
+            (0..self.dir_entries).filter(|slot| used_extents.iter().all(|(used, _)| used != slot));
+        let mut dir = self.read_dir(disk)?;
+
+        // Data
+        for (block, chunk) in blocks.iter().zip(body.chunks(self.block_size)) {
+            LBA_2D.write_sectors(disk, self.block_lba(*block), chunk)?;
+            // write_sectors() pads with 0x00, CP/M pads with 0x1a
+            let last = chunk.len().div_ceil(LBA_2D.sector_size) - 1;
+            let lba = self.block_lba(*block) + last;
+            let mut sector = LBA_2D.read(disk, lba)?.to_vec();
+            let used = chunk.len() - last * LBA_2D.sector_size;
+            sector[used..].iter_mut().for_each(|c| *c = 0x1a);
+            LBA_2D.write(disk, lba, &sector)?;
+        }
+
+        // Directory Entries
+        let records_per_entry = (self.extent_mask() + 1) * EXTENT_RECORDS;
+        for (n, chunk) in block_chunks.iter().enumerate() {
+            let slot = slots.next().ok_or_else(|| "Directory full".to_string())?;
+            let entry_records =
+                (records - (n * records_per_entry).min(records)).min(records_per_entry);
+            // Last logical extent in this entry
+            let number =
+                n * (self.extent_mask() + 1) + entry_records.saturating_sub(1) / EXTENT_RECORDS;
+            let rc =
+                entry_records - (entry_records.saturating_sub(1) / EXTENT_RECORDS) * EXTENT_RECORDS;
+
+            let entry = &mut dir[slot * ENTRY_SIZE..(slot + 1) * ENTRY_SIZE];
+            entry.iter_mut().for_each(|c| *c = 0);
+            entry[1..12].copy_from_slice(&name_bytes);
+            entry[12] = (number % 32) as u8;
+            entry[14] = (number / 32) as u8;
+            entry[15] = rc as u8;
+            for (pos, block) in chunk.iter().enumerate() {
+                entry[16 + pos] = *block as u8;
+            }
+        }
+        LBA_2D.write_sectors(disk, self.block_lba(0), &dir)
+    }
+
+    fn format(&self, disk: &mut Disk) -> Result<(), String> {
+        let sectors = (self.dir_entries * ENTRY_SIZE) / LBA_2D.sector_size;
+        LBA_2D.write_sectors(
+            disk,
+            self.block_lba(0),
+            &vec![DELETED; sectors * LBA_2D.sector_size],
+        )
+    }
+}
+
+// ================================================================================
+//
+//  Test Code
+//
+// ================================================================================
+#[cfg(test)]
+mod test {
+    use crate::fileio::D88FileIO;
+    use crate::filesystem::cpm::{Cpm, ENTRY_SIZE};
+    use crate::filesystem::{FileSystem, FileType, LBA_2D};
+
+    const CPM: &str = "../../sample/CPM_Format_2D_turboCPM_X1turbo.d88";
+
+    #[test]
+    fn test_list_read() {
+        let d88fileio = D88FileIO::open(CPM);
+        let cpm = Cpm::x1turbo();
+        let entries = cpm.list(&d88fileio.disk).unwrap();
+        let names: Vec<&str> = entries.iter().map(|entry| entry.name.as_str()).collect();
+        assert!(names.contains(&"HOGE.TXT") && names.contains(&"WM.COM"));
+
+        let hoge = cpm.read(&d88fileio.disk, "hoge.txt").unwrap();
+        assert_eq!(hoge.len(), 128);
+        assert_eq!(&hoge[..17], b"0123456789ABCDEF\x1a");
+        assert_eq!(cpm.read(&d88fileio.disk, "WM.COM").unwrap().len(), 15360);
+    }
+
+    #[test]
+    fn test_write() {
+        let mut disk = D88FileIO::open(CPM).disk;
+        let cpm = Cpm::x1turbo();
+
+        // 40KB: 2 directory entries
+        let data: Vec<u8> = (0..40000).map(|n| (n % 251) as u8).collect();
+        assert!(cpm
+            .write(&mut disk, "big.dat", &data, FileType::Other)
+            .is_ok());
+        assert!(cpm
+            .write(&mut disk, "BIG.DAT", &data, FileType::Other)
+            .is_err());
+
+        let entry = cpm
+            .list(&disk)
+            .unwrap()
+            .into_iter()
+            .find(|entry| entry.name == "BIG.DAT")
+            .unwrap();
+        assert_eq!(entry.size, 40064);
+        let read = cpm.read(&disk, "BIG.DAT").unwrap();
+        assert_eq!(read[..40000], data[..]);
+        assert!(read[40000..].iter().all(|c| *c == 0x1a));
+        assert!(cpm.detect(&disk));
+    }
+
+    #[test]
+    fn test_write_broken_directory() {
+        let mut disk = D88FileIO::open(CPM).disk;
+        let cpm = Cpm::x1turbo();
+
+        // Block 255 (> DSM) at "HOGE.TXT"
+        let mut dir = cpm.read_dir(&disk).unwrap();
+        let slot = dir
+            .chunks(ENTRY_SIZE)
+            .position(|b| b[0] == 0 && &b[1..12] == b"HOGE    TXT")
+            .unwrap();
+        dir[slot * ENTRY_SIZE + 16] = 0xff;
+        LBA_2D
+            .write_sectors(&mut disk, cpm.block_lba(0), &dir)
+            .unwrap();
+
+        let expected = Err(format!("Broken directory (entry {}, block 255)", slot));
+        assert_eq!(
+            cpm.write(&mut disk, "NEW.DAT", &[0; 10], FileType::Other),
+            expected
+        );
+        assert!(cpm.list(&disk).is_err());
+        assert!(!cpm.detect(&disk));
+    }
+}
@@ -0,0 +1,321 @@
+use crate::disk::Disk;
+use crate::filesystem::{find_entry, join_name, name_field, split_name};
+use crate::filesystem::{DirEntry, FileSystem, FileType, LBA_2D};
+
+/// Hu-BASIC (X1) 2D
+///
+///   * FAT       LBA 14 (1 byte / cluster)
+///   * Directory LBA 16-31 (32 byte / entry)
+///   * Cluster   16 sectors (4KB), 80 clusters
+///
+/// FAT の値
+///
+///   * 0x00       free
+///   * 0x01-0x7f  next cluster
+///   * 0x80-0x8f  last cluster, (low nibble + 1) sectors used
+///
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HuBasic;
+
+const FAT_LBA: usize = 14;
+const DIR_LBA: usize = 16;
+const DIR_SECTORS: usize = 16;
+const CLUSTER_SECTORS: usize = 16;
+const CLUSTERS: usize = 80;
+const ENTRY_SIZE: usize = 32;
+
+// Mode (Entry +0)
+const MODE_BIN: u8 = 0x01;
+const MODE_BAS: u8 = 0x02;
+const MODE_ASC: u8 = 0x04;
+const MODE_DIR: u8 = 0x80;
+const MODE_DELETED: u8 = 0x00;
+const MODE_END: u8 = 0xff;
+
+/// Raw Directory Entry (Helper struct)
+///
+struct RawEntry {
+    slot: usize,
+    bytes: Vec<u8>,
+}
+
+impl RawEntry {
+    fn to_dir_entry(&self) -> DirEntry {
+        let b = &self.bytes;
+        let mode = b[0];
+        let file_type = if mode & MODE_DIR != 0 {
+            FileType::Directory
+        } else if mode & MODE_BIN != 0 {
+            FileType::Binary
+        } else if mode & MODE_BAS != 0 {
+            FileType::Basic
+        } else if mode & MODE_ASC != 0 {
+            FileType::Ascii
+        } else {
+            FileType::Other
+        };
+        let word = |pos: usize| u16::from_le_bytes([b[pos], b[pos + 1]]);
+        let is_binary = file_type == FileType::Binary;
+
+        DirEntry {
+            name: join_name(&b[1..14], &b[14..17]),
+            file_type,
+            attr: mode,
+            size: word(18) as usize,
+            start: word(30) as usize,
+            load_addr: if is_binary { Some(word(20)) } else { None },
+            exec_addr: if is_binary { Some(word(22)) } else { None },
+        }
+    }
+}
+
+impl HuBasic {
+    /// Read a File of Directory Entry
+    ///
+    fn read_entry(&self, disk: &Disk, entry: &DirEntry) -> Result<Vec<u8>, String> {
+        let fat = self.read_fat(disk)?;
+
+        let mut data = Vec::new();
+        for (cluster, sectors) in self.chain(&fat, entry.start)? {
+            data.extend(LBA_2D.read_sectors(disk, cluster * CLUSTER_SECTORS, sectors)?);
+        }
+
+        if entry.file_type == FileType::Ascii {
+            // ASCII files end at EOF (0x1a)
+            if let Some(eof) = data.iter().position(|c| *c == 0x1a) {
+                data.truncate(eof);
+            }
+        } else if entry.size <= data.len() {
+            data.truncate(entry.size);
+        }
+        Ok(data)
+    }
+
+    fn read_fat(&self, disk: &Disk) -> Result<Vec<u8>, String> {
+        Ok(LBA_2D.read(disk, FAT_LBA)?.to_vec())
+    }
+
+    fn read_entries(&self, disk: &Disk) -> Result<Vec<RawEntry>, String> {
+        let dir = LBA_2D.read_sectors(disk, DIR_LBA, DIR_SECTORS)?;
+        Ok(dir
+            .chunks(ENTRY_SIZE)
+            .enumerate()
+            .map(|(slot, bytes)| RawEntry {
+                slot,
+                bytes: bytes.to_vec(),
+            })
+            .collect())
+    }
+
+    /// Cluster Chain
+    ///
+    /// # Return
+    ///
+    ///   * Ok(Vec<(Cluster, Used Sectors)>)
+    ///
+    fn chain(&self, fat: &[u8], start: usize) -> Result<Vec<(usize, usize)>, String> {
+        let mut chain = Vec::new();
+        let mut cluster = start;
+        loop {
+            if cluster >= CLUSTERS || chain.len() >= CLUSTERS {
+                return Err(format!("Broken FAT chain at cluster {}", cluster));
+            }
+            let next = fat[cluster];
+            match next {
+                0x80..=0x8f => {
+                    chain.push((cluster, ((next & 0x0f) + 1) as usize));
+                    return Ok(chain);
+                }
+                0x01..=0x7f => {
+                    chain.push((cluster, CLUSTER_SECTORS));
+                    cluster = next as usize;
+                }
+                _ => return Err(format!("Broken FAT chain at cluster {}", cluster)),
+            }
+        }
+    }
+}
+
+impl FileSystem for HuBasic {
+    fn get_name(&self) -> &'static str {
+        "Hu-BASIC"
+    }
+
+    fn detect(&self, disk: &Disk) -> bool {
+        LBA_2D.fits(disk)
+            && self
+                .read_fat(disk)
+                .map(|fat| fat[0] == 0x01 && fat[1] == 0x8f)
+                .unwrap_or(false)
+    }
+
+    /// ASCII files have no size, the size is counted up to EOF (0x1a)
+    ///
+    fn list(&self, disk: &Disk) -> Result<Vec<DirEntry>, String> {
+        let mut entries: Vec<DirEntry> = self
+            .read_entries(disk)?
+            .iter()
+            .take_while(|entry| entry.bytes[0] != MODE_END)
+            .filter(|entry| entry.bytes[0] != MODE_DELETED)
+            .map(|entry| entry.to_dir_entry())
+            .collect();
+
+        for entry in entries.iter_mut() {
+            if entry.file_type == FileType::Ascii {
+                entry.size = self.read_entry(disk, entry).map_or(0, |data| data.len());
+            }
+        }
+        Ok(entries)
+    }
+
+    fn read(&self, disk: &Disk, name: &str) -> Result<Vec<u8>, String> {
+        let entry = find_entry(&self.list(disk)?, name)?;
+        self.read_entry(disk, &entry)
+    }
+
+    fn write(
+        &self,
+        disk: &mut Disk,
+        name: &str,
+        data: &[u8],
+        file_type: FileType,
+    ) -> Result<(), String> {
+        let (base, ext) = split_name(name);
+        let name_bytes = name_field(&base, 13, 0x20)?;
+        let ext_bytes = name_field(&ext, 3, 0x20)?;
+        if self
+            .list(disk)?
+            .iter()
+            .any(|entry| entry.name.eq_ignore_ascii_case(name))
+        {
+            return Err(format!("\"{}\" already exists", name));
+        }
+
+        let (mode, size, mut body) = match file_type {
+            FileType::Binary => (MODE_BIN, data.len(), data.to_vec()),
+            FileType::Basic => (MODE_BAS, data.len(), data.to_vec()),
+            FileType::Ascii => (MODE_ASC, 0, [data, &[0x1a]].concat()),
+            _ => return Err(format!("{} can't be written", file_type.get_name())),
+        };
+        if data.len() > 0xffff {
+            return Err(format!("\"{}\" is too large ({} byte)", name, data.len()));
+        }
+        if body.is_empty() {
+            body.push(0);
+        }
+
+        // Directory Slot
+        let slot = self
+            .read_entries(disk)?
+            .iter()
+            .find(|entry| entry.bytes[0] == MODE_END || entry.bytes[0] == MODE_DELETED)
+            .map(|entry| entry.slot)
+            .ok_or_else(|| "Directory full".to_string())?;
+
+        // Clusters
+        let cluster_size = CLUSTER_SECTORS * LBA_2D.sector_size;
+        let mut fat = self.read_fat(disk)?;
+        let clusters: Vec<usize> = (2..CLUSTERS)
+            .filter(|cluster| fat[*cluster] == 0x00)
+            .take(body.len().div_ceil(cluster_size))
+            .collect();
+        if clusters.len() < body.len().div_ceil(cluster_size) {
+            return Err("Disk full".to_string());
+        }
+
+        for (n, (cluster, chunk)) in clusters.iter().zip(body.chunks(cluster_size)).enumerate() {
+            LBA_2D.write_sectors(disk, cluster * CLUSTER_SECTORS, chunk)?;
+            fat[*cluster] = match clusters.get(n + 1) {
+                Some(next) => *next as u8,
+                None => 0x80 | (chunk.len().div_ceil(LBA_2D.sector_size) - 1) as u8,
+            };
+        }
+        LBA_2D.write(disk, FAT_LBA, &fat)?;
+
+        // Directory Entry
+        let mut entry = vec![0u8; ENTRY_SIZE];
+        entry[0] = mode;
+        entry[1..14].copy_from_slice(&name_bytes);
+        entry[14..17].copy_from_slice(&ext_bytes);
+        entry[17] = 0x20; // Password
+        entry[18..20].copy_from_slice(&(size as u16).to_le_bytes());
+        entry[30..32].copy_from_slice(&(clusters[0] as u16).to_le_bytes());
+
+        let lba = DIR_LBA + (slot * ENTRY_SIZE) / LBA_2D.sector_size;
+        let mut sector = LBA_2D.read(disk, lba)?.to_vec();
+        let pos = (slot * ENTRY_SIZE) % LBA_2D.sector_size;
+        sector[pos..pos + ENTRY_SIZE].copy_from_slice(&entry);
+        LBA_2D.write(disk, lba, &sector)
+    }
+
+    fn format(&self, disk: &mut Disk) -> Result<(), String> {
+        let mut fat = vec![0x00u8; LBA_2D.sector_size];
+        fat[0] = 0x01;
+        fat[1] = 0x8f;
+        fat[CLUSTERS..0x80].iter_mut().for_each(|c| *c = 0x8f);
+        LBA_2D.write(disk, FAT_LBA, &fat)?;
+
+        LBA_2D.write_sectors(
+            disk,
+            DIR_LBA,
+            &vec![MODE_END; DIR_SECTORS * LBA_2D.sector_size],
+        )
+    }
+}
+
+// ================================================================================
+//
+//  Test Code
+//
+// ================================================================================
+#[cfg(test)]
+mod test {
+    use crate::fileio::D88FileIO;
+    use crate::filesystem::hubasic::HuBasic;
+    use crate::filesystem::{FileSystem, FileType};
+    use crate::raw::{blank, Geometry};
+
+    const HUBASIC: &str = "../../sample/HuBASIC_Format_2D.d88";
+
+    #[test]
+    fn test_list_read() {
+        let d88fileio = D88FileIO::open(HUBASIC);
+        let entries = HuBasic.list(&d88fileio.disk).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].name, "StopTheClock.Bas");
+        assert_eq!(entries[0].file_type, FileType::Basic);
+        assert_eq!(entries[0].size, 1346);
+        assert_eq!(entries[0].start, 2);
+
+        let data = HuBasic.read(&d88fileio.disk, "stoptheclock.bas").unwrap();
+        assert_eq!(data.len(), 1346);
+        let text = HuBasic.read(&d88fileio.disk, "StopTheClock.Asc").unwrap();
+        assert!(!text.is_empty() && !text.contains(&0x1a));
+        assert!(HuBasic.read(&d88fileio.disk, "NONE").is_err());
+    }
+
+    #[test]
+    fn test_format_write() {
+        let mut disk = blank(&Geometry::from_name("2D").unwrap(), 0x00);
+        assert!(!HuBasic.detect(&disk));
+        assert!(HuBasic.format(&mut disk).is_ok());
+        assert!(HuBasic.detect(&disk));
+
+        let data: Vec<u8> = (0..5000).map(|n| n as u8).collect();
+        assert!(HuBasic
+            .write(&mut disk, "GAME.BIN", &data, FileType::Binary)
+            .is_ok());
+        assert!(HuBasic
+            .write(&mut disk, "README", b"HELLO\r\n", FileType::Ascii)
+            .is_ok());
+        assert!(HuBasic
+            .write(&mut disk, "game.bin", &data, FileType::Binary)
+            .is_err());
+
+        let entries = HuBasic.list(&disk).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].start, 4);
+        assert_eq!(HuBasic.read(&disk, "GAME.BIN").unwrap(), data);
+        assert_eq!(HuBasic.read(&disk, "README").unwrap(), b"HELLO\r\n");
+    }
+}
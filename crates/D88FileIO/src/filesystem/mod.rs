@@ -0,0 +1,302 @@
+//! Filesystems on Disk Images
+//!
+//! いまは 2D (40トラック x 2面 x 16セクタ x 256バイト) の
+//! Hu-BASIC、N88-BASIC、X1turbo CP/M に対応する。
+//!
+pub mod cpm;
+pub mod hubasic;
+pub mod n88;
+
+use crate::disk::Disk;
+
+/// File Type
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileType {
+    /// Machine Code
+    Binary,
+    /// BASIC Program (Tokenized)
+    Basic,
+    /// Text
+    Ascii,
+    /// Sub Directory
+    Directory,
+    /// Other
+    Other,
+}
+
+impl FileType {
+    /// File Type Name
+    ///
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            FileType::Binary => "BIN",
+            FileType::Basic => "BAS",
+            FileType::Ascii => "ASC",
+            FileType::Directory => "DIR",
+            FileType::Other => "---",
+        }
+    }
+
+    /// File Type of Name ("bin", "bas", "asc")
+    ///
+    pub fn from_name(name: &str) -> Option<FileType> {
+        match name.to_ascii_lowercase().as_str() {
+            "bin" => Some(FileType::Binary),
+            "bas" => Some(FileType::Basic),
+            "asc" => Some(FileType::Ascii),
+            _ => None,
+        }
+    }
+}
+
+/// Directory Entry
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirEntry {
+    /// File Name ("NAME.EXT")
+    pub name: String,
+    pub file_type: FileType,
+    /// Attribute Byte (raw, CP/M: User Number)
+    pub attr: u8,
+    /// File Size (byte)
+    pub size: usize,
+    /// Start Cluster (Block)
+    pub start: usize,
+    pub load_addr: Option<u16>,
+    pub exec_addr: Option<u16>,
+}
+
+/// Filesystem
+///
+pub trait FileSystem {
+    /// Filesystem Name
+    fn get_name(&self) -> &'static str;
+
+    /// Filesystem is found on the Disk
+    fn detect(&self, disk: &Disk) -> bool;
+
+    /// List Files
+    fn list(&self, disk: &Disk) -> Result<Vec<DirEntry>, String>;
+
+    /// Read a File
+    fn read(&self, disk: &Disk, name: &str) -> Result<Vec<u8>, String>;
+
+    /// Write a File (new file only)
+    fn write(
+        &self,
+        disk: &mut Disk,
+        name: &str,
+        data: &[u8],
+        file_type: FileType,
+    ) -> Result<(), String>;
+
+    /// Make an empty Filesystem
+    fn format(&self, disk: &mut Disk) -> Result<(), String>;
+}
+
+/// Supported Filesystems (in Detection Order)
+///
+pub fn filesystems() -> Vec<Box<dyn FileSystem>> {
+    vec![
+        Box::new(hubasic::HuBasic),
+        Box::new(n88::N88Basic),
+        Box::new(cpm::Cpm::x1turbo()),
+    ]
+}
+
+/// Detect Filesystem
+///
+/// # Return
+///
+///   * Some(Box<dyn FileSystem>)
+///   * None  Unknown
+///
+pub fn detect(disk: &Disk) -> Option<Box<dyn FileSystem>> {
+    filesystems().into_iter().find(|fs| fs.detect(disk))
+}
+
+/// Filesystem of Name ("hubasic", "n88", "cpm")
+///
+pub fn from_name(name: &str) -> Option<Box<dyn FileSystem>> {
+    let fs: Box<dyn FileSystem> = match name.to_ascii_lowercase().as_str() {
+        "hubasic" | "hu-basic" => Box::new(hubasic::HuBasic),
+        "n88" | "n88basic" | "n88-basic" => Box::new(n88::N88Basic),
+        "cpm" | "cp/m" => Box::new(cpm::Cpm::x1turbo()),
+        _ => return None,
+    };
+    Some(fs)
+}
+
+/// Split "NAME.EXT" into Name and Extension
+///
+pub(crate) fn split_name(name: &str) -> (String, String) {
+    match name.rfind('.') {
+        Some(pos) => (name[..pos].to_string(), name[pos + 1..].to_string()),
+        None => (name.to_string(), String::new()),
+    }
+}
+
+/// Join Name and Extension (trailing spaces are removed)
+///
+pub(crate) fn join_name(name: &[u8], ext: &[u8]) -> String {
+    let to_str = |bytes: &[u8]| {
+        bytes
+            .iter()
+            .map(|c| {
+                if (0x20..0x7f).contains(c) {
+                    *c as char
+                } else {
+                    '?'
+                }
+            })
+            .collect::<String>()
+            .trim_end()
+            .to_string()
+    };
+    let (name, ext) = (to_str(name), to_str(ext));
+    if ext.is_empty() {
+        name
+    } else {
+        format!("{}.{}", name, ext)
+    }
+}
+
+/// Fixed Length Field padded with `pad`
+///
+pub(crate) fn name_field(s: &str, len: usize, pad: u8) -> Result<Vec<u8>, String> {
+    if !s.is_ascii() || s.len() > len {
+        return Err(format!(
+            "\"{}\"? Name must be ASCII up to {} characters",
+            s, len
+        ));
+    }
+    let mut field = s.as_bytes().to_vec();
+    field.resize(len, pad);
+    Ok(field)
+}
+
+/// Logical Sector Access (2D, 16 sectors x 256 byte per track-side)
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Lba {
+    pub sectors: usize,
+    pub sector_size: usize,
+}
+
+/// Standard 2D Logical Sectors
+pub(crate) const LBA_2D: Lba = Lba {
+    sectors: 16,
+    sector_size: 256,
+};
+
+impl Lba {
+    /// Disk has this Geometry (every track-side is formatted)
+    ///
+    pub fn fits(&self, disk: &Disk) -> bool {
+        disk.track_tbl.len() >= 80
+            && disk.track_tbl.iter().take(80).all(|track| {
+                (1..=self.sectors).all(|id| {
+                    track.sector_tbl.iter().any(|sector| {
+                        sector.header.sector as usize == id && sector.data.len() == self.sector_size
+                    })
+                })
+            })
+    }
+
+    /// Sector Data of LBA
+    ///
+    pub fn read<'a>(&self, disk: &'a Disk, lba: usize) -> Result<&'a [u8], String> {
+        let (idx, id) = (lba / self.sectors, (lba % self.sectors) + 1);
+        disk.track_tbl
+            .get(idx)
+            .and_then(|track| {
+                track
+                    .sector_tbl
+                    .iter()
+                    .find(|sector| sector.header.sector as usize == id)
+            })
+            .map(|sector| sector.data.as_slice())
+            .ok_or_else(|| format!("Logical sector {} not found", lba))
+    }
+
+    /// Write Sector Data of LBA (`data` shorter than a sector is padded with 0x00)
+    ///
+    pub fn write(&self, disk: &mut Disk, lba: usize, data: &[u8]) -> Result<(), String> {
+        let (idx, id) = (lba / self.sectors, (lba % self.sectors) + 1);
+        let sector = disk
+            .track_tbl
+            .get_mut(idx)
+            .and_then(|track| {
+                track
+                    .sector_tbl
+                    .iter_mut()
+                    .find(|sector| sector.header.sector as usize == id)
+            })
+            .ok_or_else(|| format!("Logical sector {} not found", lba))?;
+
+        let len = data.len().min(sector.data.len());
+        sector.data[..len].copy_from_slice(&data[..len]);
+        sector.data[len..].iter_mut().for_each(|byte| *byte = 0);
+        Ok(())
+    }
+
+    /// Read continuous Sectors
+    ///
+    pub fn read_sectors(&self, disk: &Disk, lba: usize, count: usize) -> Result<Vec<u8>, String> {
+        let mut data = Vec::new();
+        for lba in lba..(lba + count) {
+            data.extend_from_slice(self.read(disk, lba)?);
+        }
+        Ok(data)
+    }
+
+    /// Write continuous Sectors
+    ///
+    pub fn write_sectors(&self, disk: &mut Disk, lba: usize, data: &[u8]) -> Result<(), String> {
+        for (n, chunk) in data.chunks(self.sector_size).enumerate() {
+            self.write(disk, lba + n, chunk)?;
+        }
+        Ok(())
+    }
+}
+
+/// Find Directory Entry (case-insensitive)
+///
+pub fn find_entry(entries: &[DirEntry], name: &str) -> Result<DirEntry, String> {
+    entries
+        .iter()
+        .find(|entry| entry.name.eq_ignore_ascii_case(name))
+        .cloned()
+        .ok_or_else(|| format!("\"{}\" Not Found", name))
+}
+
+// ================================================================================
+//
+//  Test Code
+//
+// ================================================================================
+#[cfg(test)]
+mod test {
+    use crate::fileio::D88FileIO;
+    use crate::filesystem::{detect, from_name, split_name};
+
+    const HUBASIC: &str = "../../sample/HuBASIC_Format_2D.d88";
+    const CPM: &str = "../../sample/CPM_Format_2D_turboCPM_X1turbo.d88";
+
+    #[test]
+    fn test_detect() {
+        let d88fileio = D88FileIO::open(HUBASIC);
+        assert_eq!(detect(&d88fileio.disk).unwrap().get_name(), "Hu-BASIC");
+
+        let d88fileio = D88FileIO::open(CPM);
+        assert_eq!(detect(&d88fileio.disk).unwrap().get_name(), "CP/M");
+
+        assert_eq!(from_name("N88").unwrap().get_name(), "N88-BASIC");
+        assert!(from_name("fat12").is_none());
+        assert_eq!(
+            split_name("A.B.TXT"),
+            ("A.B".to_string(), "TXT".to_string())
+        );
+    }
+}
@@ -0,0 +1,317 @@
+use crate::disk::Disk;
+use crate::filesystem::{find_entry, join_name, name_field, split_name};
+use crate::filesystem::{DirEntry, FileSystem, FileType, LBA_2D};
+
+/// N88-BASIC (PC-8801) 2D
+///
+///   * Directory Track 18 Side 1, Sector 1-12 (16 byte / entry)
+///   * FAT       Track 18 Side 1, Sector 14, 15, 16 (3 copies)
+///   * Cluster   8 sectors (half a track-side), 160 clusters
+///
+/// FAT の値
+///
+///   * 0x00-0x9f  next cluster
+///   * 0xc1-0xc8  last cluster, (value - 0xc0) sectors used
+///   * 0xfe       reserved
+///   * 0xff       free
+///
+#[derive(Debug, Clone, Copy, Default)]
+pub struct N88Basic;
+
+const DIR_LBA: usize = 37 * 16;
+const DIR_SECTORS: usize = 12;
+const FAT_LBA: [usize; 3] = [DIR_LBA + 13, DIR_LBA + 14, DIR_LBA + 15];
+const CLUSTER_SECTORS: usize = 8;
+const CLUSTERS: usize = 160;
+const ENTRY_SIZE: usize = 16;
+
+// Reserved Clusters (Track 0 Side 0, Directory Track)
+const RESERVED: [usize; 4] = [0, 1, 74, 75];
+
+// Attribute (Entry +9)
+const ATTR_BIN: u8 = 0x01;
+const ATTR_BAS: u8 = 0x80;
+const ATTR_ASC: u8 = 0x00;
+
+const NAME_DELETED: u8 = 0x00;
+const NAME_END: u8 = 0xff;
+
+impl N88Basic {
+    /// Read a File of Directory Entry
+    ///
+    fn read_entry(&self, disk: &Disk, entry: &DirEntry) -> Result<Vec<u8>, String> {
+        let fat = self.read_fat(disk)?;
+
+        let mut data = Vec::new();
+        for (cluster, sectors) in self.chain(&fat, entry.start)? {
+            data.extend(LBA_2D.read_sectors(disk, N88Basic::cluster_lba(cluster), sectors)?);
+        }
+
+        if entry.file_type == FileType::Ascii {
+            // ASCII files end at EOF (0x1a)
+            if let Some(eof) = data.iter().position(|c| *c == 0x1a) {
+                data.truncate(eof);
+            }
+        } else {
+            data.truncate(entry.size);
+        }
+        Ok(data)
+    }
+
+    fn read_fat(&self, disk: &Disk) -> Result<Vec<u8>, String> {
+        Ok(LBA_2D.read(disk, FAT_LBA[0])?.to_vec())
+    }
+
+    fn write_fat(&self, disk: &mut Disk, fat: &[u8]) -> Result<(), String> {
+        for lba in FAT_LBA.iter() {
+            LBA_2D.write(disk, *lba, fat)?;
+        }
+        Ok(())
+    }
+
+    /// Raw Directory Entries (Slot, 16 byte)
+    ///
+    fn read_entries(&self, disk: &Disk) -> Result<Vec<(usize, Vec<u8>)>, String> {
+        let dir = LBA_2D.read_sectors(disk, DIR_LBA, DIR_SECTORS)?;
+        Ok(dir
+            .chunks(ENTRY_SIZE)
+            .map(|bytes| bytes.to_vec())
+            .enumerate()
+            .collect())
+    }
+
+    /// Cluster Chain
+    ///
+    /// # Return
+    ///
+    ///   * Ok(Vec<(Cluster, Used Sectors)>)
+    ///
+    fn chain(&self, fat: &[u8], start: usize) -> Result<Vec<(usize, usize)>, String> {
+        let mut chain = Vec::new();
+        let mut cluster = start;
+        loop {
+            if cluster >= CLUSTERS || chain.len() >= CLUSTERS {
+                return Err(format!("Broken FAT chain at cluster {}", cluster));
+            }
+            let next = fat[cluster];
+            match next {
+                0xc1..=0xc8 => {
+                    chain.push((cluster, (next - 0xc0) as usize));
+                    return Ok(chain);
+                }
+                0x00..=0x9f => {
+                    chain.push((cluster, CLUSTER_SECTORS));
+                    cluster = next as usize;
+                }
+                _ => return Err(format!("Broken FAT chain at cluster {}", cluster)),
+            }
+        }
+    }
+
+    /// First LBA of Cluster
+    ///
+    fn cluster_lba(cluster: usize) -> usize {
+        cluster * CLUSTER_SECTORS
+    }
+}
+
+impl FileSystem for N88Basic {
+    fn get_name(&self) -> &'static str {
+        "N88-BASIC"
+    }
+
+    fn detect(&self, disk: &Disk) -> bool {
+        LBA_2D.fits(disk)
+            && self
+                .read_fat(disk)
+                .map(|fat| {
+                    fat[74] == 0xfe
+                        && fat[75] == 0xfe
+                        && fat[..CLUSTERS]
+                            .iter()
+                            .all(|c| matches!(c, 0x00..=0x9f | 0xc1..=0xc8 | 0xfe | 0xff))
+                })
+                .unwrap_or(false)
+    }
+
+    fn list(&self, disk: &Disk) -> Result<Vec<DirEntry>, String> {
+        let fat = self.read_fat(disk)?;
+        let mut entries = Vec::new();
+        for (_, b) in self.read_entries(disk)? {
+            if b[0] == NAME_END {
+                break;
+            }
+            if b[0] == NAME_DELETED {
+                continue;
+            }
+
+            let attr = b[9];
+            let start = b[10] as usize;
+            let file_type = if attr & ATTR_BAS != 0 {
+                FileType::Basic
+            } else if attr & ATTR_BIN != 0 {
+                FileType::Binary
+            } else {
+                FileType::Ascii
+            };
+            let size = self
+                .chain(&fat, start)
+                .map(|chain| chain.iter().map(|(_, n)| n).sum::<usize>() * LBA_2D.sector_size)
+                .unwrap_or(0);
+
+            entries.push(DirEntry {
+                name: join_name(&b[0..6], &b[6..9]),
+                file_type,
+                attr,
+                size,
+                start,
+                load_addr: None,
+                exec_addr: None,
+            });
+        }
+
+        // ASCII files are counted up to EOF (0x1a),
+        // Binary files begin with Start / End Address
+        for entry in entries.iter_mut() {
+            if entry.file_type == FileType::Ascii {
+                entry.size = self.read_entry(disk, entry).map_or(0, |data| data.len());
+            } else if entry.file_type == FileType::Binary {
+                if let Ok(head) = LBA_2D.read(disk, N88Basic::cluster_lba(entry.start)) {
+                    let start = u16::from_le_bytes([head[0], head[1]]);
+                    let end = u16::from_le_bytes([head[2], head[3]]);
+                    if start <= end && (end - start) as usize + 5 <= entry.size {
+                        entry.size = (end - start) as usize + 5;
+                        entry.load_addr = Some(start);
+                    }
+                }
+            }
+        }
+        Ok(entries)
+    }
+
+    fn read(&self, disk: &Disk, name: &str) -> Result<Vec<u8>, String> {
+        let entry = find_entry(&self.list(disk)?, name)?;
+        self.read_entry(disk, &entry)
+    }
+
+    fn write(
+        &self,
+        disk: &mut Disk,
+        name: &str,
+        data: &[u8],
+        file_type: FileType,
+    ) -> Result<(), String> {
+        let (base, ext) = split_name(name);
+        let name_bytes = name_field(&base, 6, 0x20)?;
+        let ext_bytes = name_field(&ext, 3, 0x20)?;
+        if self
+            .list(disk)?
+            .iter()
+            .any(|entry| entry.name.eq_ignore_ascii_case(name))
+        {
+            return Err(format!("\"{}\" already exists", name));
+        }
+
+        let (attr, body) = match file_type {
+            FileType::Binary => (ATTR_BIN, data.to_vec()),
+            FileType::Basic => (ATTR_BAS, data.to_vec()),
+            FileType::Ascii => (ATTR_ASC, [data, &[0x1a]].concat()),
+            _ => return Err(format!("{} can't be written", file_type.get_name())),
+        };
+        let body = if body.is_empty() { vec![0] } else { body };
+
+        let slot = self
+            .read_entries(disk)?
+            .iter()
+            .find(|(_, b)| b[0] == NAME_END || b[0] == NAME_DELETED)
+            .map(|(slot, _)| *slot)
+            .ok_or_else(|| "Directory full".to_string())?;
+
+        let cluster_size = CLUSTER_SECTORS * LBA_2D.sector_size;
+        let mut fat = self.read_fat(disk)?;
+        let count = body.len().div_ceil(cluster_size);
+        let clusters: Vec<usize> = (0..CLUSTERS)
+            .filter(|cluster| fat[*cluster] == 0xff && !RESERVED.contains(cluster))
+            .take(count)
+            .collect();
+        if clusters.len() < count {
+            return Err("Disk full".to_string());
+        }
+
+        for (n, (cluster, chunk)) in clusters.iter().zip(body.chunks(cluster_size)).enumerate() {
+            LBA_2D.write_sectors(disk, N88Basic::cluster_lba(*cluster), chunk)?;
+            fat[*cluster] = match clusters.get(n + 1) {
+                Some(next) => *next as u8,
+                None => 0xc0 + chunk.len().div_ceil(LBA_2D.sector_size) as u8,
+            };
+        }
+        self.write_fat(disk, &fat)?;
+
+        let mut entry = vec![0xffu8; ENTRY_SIZE];
+        entry[0..6].copy_from_slice(&name_bytes);
+        entry[6..9].copy_from_slice(&ext_bytes);
+        entry[9] = attr;
+        entry[10] = clusters[0] as u8;
+
+        let lba = DIR_LBA + (slot * ENTRY_SIZE) / LBA_2D.sector_size;
+        let mut sector = LBA_2D.read(disk, lba)?.to_vec();
+        let pos = (slot * ENTRY_SIZE) % LBA_2D.sector_size;
+        sector[pos..pos + ENTRY_SIZE].copy_from_slice(&entry);
+        LBA_2D.write(disk, lba, &sector)
+    }
+
+    fn format(&self, disk: &mut Disk) -> Result<(), String> {
+        let mut fat = vec![0xffu8; LBA_2D.sector_size];
+        RESERVED.iter().for_each(|cluster| fat[*cluster] = 0xfe);
+        self.write_fat(disk, &fat)?;
+
+        LBA_2D.write_sectors(
+            disk,
+            DIR_LBA,
+            &vec![NAME_END; DIR_SECTORS * LBA_2D.sector_size],
+        )
+    }
+}
+
+// ================================================================================
+//
+//  Test Code
+//
+// ================================================================================
+#[cfg(test)]
+mod test {
+    use crate::filesystem::n88::N88Basic;
+    use crate::filesystem::{detect, FileSystem, FileType};
+    use crate::raw::{blank, Geometry};
+
+    #[test]
+    fn test_format_write() {
+        let mut disk = blank(&Geometry::from_name("2D").unwrap(), 0xe5);
+        assert!(N88Basic.format(&mut disk).is_ok());
+        assert_eq!(detect(&disk).unwrap().get_name(), "N88-BASIC");
+
+        // BSAVE image: Start, End, Data
+        let mut bin = vec![0x00, 0xc0, 0x0f, 0xc0];
+        bin.extend((0..16).map(|n| n as u8));
+        let text: Vec<u8> = b"10 PRINT \"HELLO\"\r\n".repeat(200);
+
+        assert!(N88Basic
+            .write(&mut disk, "HELLO.ASC", &text, FileType::Ascii)
+            .is_ok());
+        assert!(N88Basic
+            .write(&mut disk, "MC", &bin, FileType::Binary)
+            .is_ok());
+
+        let entries = N88Basic.list(&disk).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!((entries[0].start, entries[1].start), (2, 4));
+        assert_eq!(entries[1].load_addr, Some(0xc000));
+        assert_eq!((entries[0].size, entries[1].size), (text.len(), 20));
+
+        assert_eq!(N88Basic.read(&disk, "hello.asc").unwrap(), text);
+        assert_eq!(N88Basic.read(&disk, "MC").unwrap(), bin);
+        assert!(N88Basic
+            .write(&mut disk, "TOOLONG", &bin, FileType::Binary)
+            .is_err());
+    }
+}
@@ -3,8 +3,30 @@
 
 pub const MAX_SECTOR: u16 = 164;
 
+// Media Type (D88_Header.disk_type)
+//
+//   0x30(1D), 0x40(1DD) は D77(FM-7/FM-77) 等で使われる片面メディア
+pub const DISK_TYPE_2D: u8 = 0x00;
+pub const DISK_TYPE_2DD: u8 = 0x10;
+pub const DISK_TYPE_2HD: u8 = 0x20;
+pub const DISK_TYPE_1D: u8 = 0x30;
+pub const DISK_TYPE_1DD: u8 = 0x40;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+///  Disk Image Format
+///
+///  ディスクイメージの形式(D88 / D77)
+///
+pub enum ImageFormat {
+    #[default]
+    D88,
+    D77,
+}
+
 #[repr(C)]
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 ///  File Header at D88 File
 ///
 ///  D88ファイルのヘッダ情報
@@ -15,6 +37,7 @@ pub struct D88_Header {
     pub write_protect: u8,
     pub disk_type: u8,
     pub disk_size: u32,
+    #[cfg_attr(feature = "serde", serde(with = "serde_big_array::BigArray"))]
     pub track_offset_tbl: [u32; 164],
 }
 
@@ -33,6 +56,7 @@ impl Default for D88_Header {
 
 #[repr(C)]
 #[derive(Debug, Clone, Copy, Default, Eq, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 ///  Sector Header at D88 File
 ///
 ///  D88ファイルのセクタのヘッダ情報
@@ -0,0 +1,240 @@
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+use md5::Md5;
+use sha1::{Digest as _, Sha1};
+
+use crate::disk::Disk;
+use crate::sector::Sector;
+
+/// Hash Values (CRC32 / MD5 / SHA-1)
+///
+/// ファイル、ディスク内容、トラック、セクタのハッシュ値
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Digest {
+    pub crc32: u32,
+    pub md5: [u8; 16],
+    pub sha1: [u8; 20],
+}
+
+impl Digest {
+    /// Hash of Byte Array
+    ///
+    /// # Argument
+    ///
+    ///   * `buf` Byte Array
+    ///
+    /// # Return
+    ///
+    ///   * Digest
+    ///
+    pub fn from_bytes(buf: &[u8]) -> Self {
+        let mut hasher = Hasher::new();
+        hasher.update(buf);
+        hasher.finalize()
+    }
+
+    /// Hash of File
+    ///
+    /// D88ファイル全体(ヘッダを含む)のハッシュ値
+    ///
+    /// # Argument
+    ///
+    ///   * `path` File Path
+    ///
+    /// # Return
+    ///
+    ///   * Ok(Digest)
+    ///   * Err(())     File Read Error
+    ///
+    #[allow(clippy::result_unit_err)]
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, ()> {
+        let mut fh = fs::File::open(path).map_err(|_| ())?;
+
+        let mut hasher = Hasher::new();
+        let mut buf = [0u8; 0x1000];
+        loop {
+            match fh.read(&mut buf) {
+                Ok(0) => break,
+                Ok(size) => hasher.update(&buf[..size]),
+                Err(_) => return Err(()),
+            }
+        }
+
+        Ok(hasher.finalize())
+    }
+
+    pub fn get_crc32(&self) -> String {
+        format!("{:08x}", self.crc32)
+    }
+
+    pub fn get_md5(&self) -> String {
+        to_hex(&self.md5)
+    }
+
+    pub fn get_sha1(&self) -> String {
+        to_hex(&self.sha1)
+    }
+}
+
+/// Incremental Hasher
+///
+/// CRC32 / MD5 / SHA-1 を同時に計算する
+///
+#[derive(Default)]
+pub struct Hasher {
+    crc32: crc32fast::Hasher,
+    md5: Md5,
+    sha1: Sha1,
+}
+
+impl Hasher {
+    /// Constructor
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add Data
+    ///
+    pub fn update(&mut self, buf: &[u8]) {
+        self.crc32.update(buf);
+        self.md5.update(buf);
+        self.sha1.update(buf);
+    }
+
+    /// Get Hash Values
+    ///
+    pub fn finalize(self) -> Digest {
+        Digest {
+            crc32: self.crc32.finalize(),
+            md5: self.md5.finalize().into(),
+            sha1: self.sha1.finalize().into(),
+        }
+    }
+}
+
+impl Sector {
+    /// Hash of Sector Data
+    ///
+    pub fn digest(&self) -> Digest {
+        Digest::from_bytes(&self.data)
+    }
+}
+
+impl Disk {
+    /// Content Hash
+    ///
+    /// セクタデータだけを論理順(`logical_sectors()`)に連結したハッシュ値。
+    /// ディスク名、ライトプロテクト、予約領域、セクタヘッダは含まないので、
+    /// 名前を変えただけのコピーは同じ値になる。
+    ///
+    pub fn content_digest(&self) -> Digest {
+        let mut hasher = Hasher::new();
+        for (_, sector) in self.logical_sectors() {
+            hasher.update(&sector.data);
+        }
+        hasher.finalize()
+    }
+
+    /// Track Hash
+    ///
+    /// トラック内のセクタデータをセクタ番号順に連結したハッシュ値
+    ///
+    /// # Argument
+    ///
+    ///   * `idx` Index to `track_tbl`
+    ///
+    /// # Return
+    ///
+    ///   * Some(Digest)
+    ///   * None         No such track
+    ///
+    pub fn track_digest(&self, idx: usize) -> Option<Digest> {
+        let track = self.track_tbl.get(idx)?;
+
+        let mut sector_tbl: Vec<&Sector> = track.sector_tbl.iter().collect();
+        sector_tbl.sort_by_key(|sector| (sector.header.sector, sector.offset));
+
+        let mut hasher = Hasher::new();
+        for sector in sector_tbl {
+            hasher.update(&sector.data);
+        }
+        Some(hasher.finalize())
+    }
+}
+
+fn to_hex(buf: &[u8]) -> String {
+    buf.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// ================================================================================
+//
+//  Test Code
+//
+// ================================================================================
+#[cfg(test)]
+mod test {
+    use crate::fileio::D88FileIO;
+    use crate::hash::Digest;
+
+    const HUBASIC: &str = "../../sample/HuBASIC_Format_2D.d88";
+    const CPM: &str = "../../sample/CPM_Format_2D_turboCPM_X1turbo.d88";
+
+    #[test]
+    fn test_file_digest() {
+        let digest = Digest::from_file(HUBASIC).unwrap();
+        assert_eq!(digest.get_crc32(), "c692e84d");
+        assert_eq!(digest.get_md5(), "8486cf60a94b956b9fba22c5cc8bf904");
+        assert_eq!(
+            digest.get_sha1(),
+            "65b61bdf8a2948b9dd7fee18eda2957cc907bfaf"
+        );
+
+        let digest = Digest::from_file(CPM).unwrap();
+        assert_eq!(digest.get_crc32(), "7c13c13f");
+    }
+
+    #[test]
+    fn test_content_digest() {
+        let mut d88fileio = D88FileIO::open(HUBASIC);
+        let digest = d88fileio.disk.content_digest();
+        assert_eq!(digest.get_crc32(), "f93b1b9b");
+        assert_eq!(digest.get_md5(), "26a3d6e2728b94f26a2183d76b953401");
+        assert_eq!(
+            digest.get_sha1(),
+            "287f344b00b981c182869189577f56fb5f5d3abc"
+        );
+
+        // Not affected by sort order and disk name
+        d88fileio.file_offset_sort();
+        d88fileio.disk.header.disk_name = [0u8; 17];
+        d88fileio.disk.header.write_protect = 0x10;
+        assert_eq!(d88fileio.disk.content_digest(), digest);
+
+        let d88fileio = D88FileIO::open(CPM);
+        assert_eq!(
+            d88fileio.disk.content_digest().get_sha1(),
+            "5e68c68e390ac3a49cf09510248531468982fc38"
+        );
+    }
+
+    #[test]
+    fn test_track_sector_digest() {
+        let d88fileio = D88FileIO::open(HUBASIC);
+
+        let digest = d88fileio.disk.track_digest(1).unwrap(); // Track 0, Side 1
+        assert_eq!(digest.get_crc32(), "0278a205");
+        assert_eq!(
+            digest.get_sha1(),
+            "54fbf05bfadfb48e31240d2d8e3f6a106eb6329a"
+        );
+        assert!(d88fileio.disk.track_digest(80).is_none());
+
+        let digest = d88fileio.get_sector(0, 1, 0).unwrap().digest();
+        assert_eq!(digest.get_crc32(), "c3fa3294");
+        assert_eq!(digest.get_md5(), "23f17376662cffd1b6d23ef6e30c5d71");
+    }
+}
@@ -1,3 +1,4 @@
+#![allow(non_snake_case)]
 //
 pub mod disk;
 pub mod sector;
@@ -7,6 +8,31 @@ pub mod track;
 pub mod fileio;
 pub mod format;
 
+//
+pub mod archive;
+pub mod basic;
+pub mod boot;
+pub mod charset;
+pub mod check;
+pub mod classify;
+pub mod dat;
+#[cfg(feature = "desc")]
+pub mod desc;
+pub mod diff;
+pub mod edit;
+pub mod extract;
+pub mod filesystem;
+pub mod hash;
+pub mod map;
+pub mod merge;
+pub mod protect;
+pub mod raw;
+pub mod report;
+pub mod search;
+pub mod select;
+pub mod summary;
+pub mod z80;
+
 //
 #[cfg(test)]
 mod tests {}
@@ -0,0 +1,254 @@
+use crate::classify::is_fill;
+use crate::disk::Disk;
+use crate::sector::Sector;
+
+/// State of a Sector at Surface Map
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SectorState {
+    /// Status OK
+    Ok,
+    /// Status OK, all bytes are the same value (e.g. 0xE5 after format)
+    Empty,
+    /// Deleted Data
+    Deleted,
+    /// CRC Error, No Address Mark, No Data Mark
+    Error,
+    /// Sector ID not found in the track
+    Missing,
+}
+
+impl SectorState {
+    /// State of a Sector
+    ///
+    pub fn of(sector: &Sector) -> Self {
+        if !sector.is_status_ok() {
+            SectorState::Error
+        } else if (sector.header.status == 0x10) || (sector.header.deleted_mark == 0x10) {
+            SectorState::Deleted
+        } else if is_fill(&sector.data) {
+            SectorState::Empty
+        } else {
+            SectorState::Ok
+        }
+    }
+
+    /// Name (Helper function)
+    ///
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            SectorState::Ok => "OK",
+            SectorState::Empty => "Empty",
+            SectorState::Deleted => "Deleted",
+            SectorState::Error => "CRC Error",
+            SectorState::Missing => "Missing",
+        }
+    }
+}
+
+/// Row of Surface Map (one Track, one Side)
+///
+#[derive(Debug, Clone)]
+pub struct MapRow {
+    /// Index to `track_tbl`
+    pub track_idx: usize,
+    pub track: usize,
+    pub side: usize,
+    /// `cells[n]` is Sector ID `SurfaceMap::ids[n]`. `None` is no such sector in the track.
+    pub cells: Vec<Option<SectorState>>,
+}
+
+/// Surface Map
+///
+/// トラック×セクタの状態マップ
+///
+#[derive(Debug, Clone, Default)]
+pub struct SurfaceMap {
+    pub rows: Vec<MapRow>,
+    /// Sector ID of each column (ascending)
+    pub ids: Vec<u8>,
+}
+
+impl SurfaceMap {
+    /// Build Surface Map
+    ///
+    /// 列はディスク上にあるセクタID(R)と 1..`number_of_sec` を昇順に並べたもの。
+    /// R=0 や 0xF5 のような範囲外のID(プロテクトによく使われる)もそのIDの列1つに表示する。
+    /// 各トラックで 1..`number_of_sec` の範囲に無いIDは `Missing`。
+    /// 同じIDのセクタが複数ある場合は最も悪い状態を表示する。
+    ///
+    /// # Argument
+    ///
+    ///   * `disk` Disk
+    ///
+    /// # Return
+    ///
+    ///   * SurfaceMap
+    ///
+    pub fn new(disk: &Disk) -> Self {
+        let sectors = || {
+            disk.track_tbl
+                .iter()
+                .flat_map(|track| track.sector_tbl.iter())
+        };
+        let max_expected = sectors()
+            .map(|sector| sector.header.number_of_sec.min(255) as u8)
+            .max()
+            .unwrap_or(0);
+        let mut ids: Vec<u8> = sectors()
+            .map(|sector| sector.header.sector)
+            .chain(1..=max_expected)
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+
+        let mut rows = Vec::new();
+        for (idx, track) in disk.track_tbl.iter().enumerate() {
+            if !disk.is_track_slot(idx) {
+                continue;
+            }
+            let (track_no, side_no) = disk.track_position(idx);
+
+            let expected = track
+                .sector_tbl
+                .iter()
+                .map(|sector| sector.header.number_of_sec as usize)
+                .max()
+                .unwrap_or(0);
+
+            let mut cells: Vec<Option<SectorState>> = ids
+                .iter()
+                .map(|id| {
+                    if (1..=expected).contains(&(*id as usize)) {
+                        Some(SectorState::Missing)
+                    } else {
+                        None
+                    }
+                })
+                .collect();
+
+            for sector in track.sector_tbl.iter() {
+                let column = ids.binary_search(&sector.header.sector).unwrap();
+
+                let state = SectorState::of(sector);
+                cells[column] = match cells[column] {
+                    Some(SectorState::Missing) | None => Some(state),
+                    Some(prev) => Some(prev.max(state)),
+                };
+            }
+
+            rows.push(MapRow {
+                track_idx: idx,
+                track: track_no,
+                side: side_no,
+                cells,
+            });
+        }
+
+        Self { rows, ids }
+    }
+
+    /// Count of each State
+    ///
+    pub fn count(&self, state: SectorState) -> usize {
+        self.rows
+            .iter()
+            .flat_map(|row| row.cells.iter())
+            .filter(|cell| **cell == Some(state))
+            .count()
+    }
+}
+
+// ================================================================================
+//
+//  Test Code
+//
+// ================================================================================
+#[cfg(test)]
+mod test {
+    use crate::fileio::D88FileIO;
+    use crate::format::DISK_TYPE_1D;
+    use crate::map::{SectorState, SurfaceMap};
+    use crate::raw::{self, Geometry};
+
+    const HUBASIC: &str = "../../sample/HuBASIC_Format_2D.d88";
+
+    #[test]
+    fn test_surface_map() {
+        let d88fileio = D88FileIO::open(HUBASIC);
+        let map = SurfaceMap::new(&d88fileio.disk);
+
+        assert_eq!(map.rows.len(), 80);
+        assert_eq!(map.ids, (1..=16).collect::<Vec<u8>>());
+        assert_eq!(map.rows[0].cells[0], Some(SectorState::Empty)); // e5 e5 ...
+        assert_eq!(map.rows[0].cells[14], Some(SectorState::Ok)); // FAT
+        assert_eq!(
+            map.count(SectorState::Ok) + map.count(SectorState::Empty),
+            80 * 16
+        );
+    }
+
+    #[test]
+    fn test_surface_map_even_track_only() {
+        // 1D, Track 0..39 at even index
+        let mut disk = raw::blank(&Geometry::from_name("2D").unwrap(), 0xe5);
+        disk.header.disk_type = DISK_TYPE_1D;
+        for (idx, track) in disk.track_tbl.iter_mut().enumerate() {
+            if (idx % 2) == 1 {
+                track.sector_tbl.clear();
+                track.number_of_sector = 0;
+            }
+        }
+        disk.update_offset();
+        assert!(disk.is_even_track_only());
+
+        let map = SurfaceMap::new(&disk);
+        assert_eq!(map.rows.len(), 40);
+        for (track_no, row) in map.rows.iter().enumerate() {
+            assert_eq!((row.track, row.side), (track_no, 0));
+        }
+        assert_eq!(map.count(SectorState::Empty), 40 * 16);
+    }
+
+    #[test]
+    fn test_surface_map_damaged() {
+        let mut d88fileio = D88FileIO::open(HUBASIC);
+
+        let track = &mut d88fileio.disk.track_tbl[1];
+        track.sector_tbl[0].header.status = 0xb0;
+        track.sector_tbl[1].header.deleted_mark = 0x10;
+        track.sector_tbl.remove(2);
+
+        let map = SurfaceMap::new(&d88fileio.disk);
+        let row = &map.rows[1];
+        assert_eq!((row.track, row.side), (0, 1));
+        assert_eq!(row.cells[0], Some(SectorState::Error));
+        assert_eq!(row.cells[1], Some(SectorState::Deleted));
+        assert_eq!(row.cells[2], Some(SectorState::Missing));
+        assert_eq!(map.count(SectorState::Missing), 1);
+    }
+
+    #[test]
+    fn test_surface_map_out_of_range_id() {
+        let mut d88fileio = D88FileIO::open(HUBASIC);
+
+        let track = &mut d88fileio.disk.track_tbl[2];
+        track.sector_tbl[0].header.sector = 0;
+        track.sector_tbl[1].header.sector = 0xf5;
+
+        let state0 = SectorState::of(&track.sector_tbl[0]);
+        let state1 = SectorState::of(&track.sector_tbl[1]);
+
+        let map = SurfaceMap::new(&d88fileio.disk);
+        assert_eq!(map.ids.len(), 18);
+        assert_eq!((map.ids[0], map.ids[17]), (0, 0xf5));
+
+        let row = &map.rows[2];
+        assert_eq!(row.cells[0], Some(state0)); // R=0
+        assert_eq!(row.cells[1], Some(SectorState::Missing)); // R=1
+        assert_eq!(row.cells[2], Some(SectorState::Missing)); // R=2
+        assert_eq!(row.cells[17], Some(state1)); // R=F5
+        assert_eq!(map.rows[0].cells[0], None);
+        assert_eq!(map.rows[0].cells[17], None);
+    }
+}
@@ -0,0 +1,392 @@
+use crate::disk::Disk;
+use crate::sector::Sector;
+use crate::track::Track;
+
+/// How the Sector was chosen
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    /// Status OK, all OK copies have the same data
+    Ok,
+    /// Status OK, data chosen by majority of OK copies
+    Majority,
+    /// OK copies differ and no majority
+    Conflict,
+    /// No copy with status OK
+    NoGoodCopy,
+}
+
+/// Merge Report of a Sector
+///
+#[derive(Debug, Clone)]
+pub struct MergedSector {
+    /// Index to `track_tbl` of the merged Disk
+    pub track_idx: usize,
+    /// Sector ID (R)
+    pub sector: u8,
+    /// Occurrence of the same Sector ID in a Track (0 Start)
+    pub nth: usize,
+    /// Index of Source Disk
+    pub source: usize,
+    /// Number of Disks which have this Sector
+    pub copies: usize,
+    /// Number of Copies with status OK
+    pub good_copies: usize,
+    /// Number of Copies which have the same data as chosen one
+    pub votes: usize,
+    pub resolution: Resolution,
+}
+
+impl MergedSector {
+    /// Resolved
+    ///
+    pub fn is_resolved(&self) -> bool {
+        matches!(self.resolution, Resolution::Ok | Resolution::Majority)
+    }
+}
+
+/// Merge Result
+///
+#[derive(Debug, Default)]
+pub struct MergeResult {
+    pub disk: Disk,
+    pub sectors: Vec<MergedSector>,
+}
+
+impl MergeResult {
+    /// Unresolved Sectors
+    ///
+    pub fn unresolved(&self) -> Vec<&MergedSector> {
+        self.sectors.iter().filter(|s| !s.is_resolved()).collect()
+    }
+}
+
+/// Merge Disks (Best-of Reconstruction)
+///
+/// 同じディスクの複数のダンプから、セクタ毎に最良のコピーを選んで1枚のディスクを再構成する。
+///
+///   1. Status が OK のコピーを優先する。
+///   2. OK のコピー同士でデータが異なる場合は多数決で決める。
+///   3. OK のコピーが無い、または多数決で決まらないセクタは未解決とし、
+///      最も多いデータ(同数なら先のディスク)を採用する。
+///
+/// トラックは (トラック番号, 面) で対応させるので、トラックテーブルの並びが違うダンプ
+/// (偶数エントリのみと詰めた並び、D77 と D88 など)も合わせられる。
+/// ディスクヘッダとトラックテーブルの並びは最初のディスクに従い、その並びに無いトラック
+/// (片面ディスクに対する裏面など)は含めない。セクタの並び順は、そのトラックを持つ最初のディスクに従う。
+///
+/// # Argument
+///
+///   * `disks` Source Disks
+///
+/// # Return
+///
+///   * Some(MergeResult)
+///   * None               No Source Disk
+///
+pub fn merge(disks: &[&Disk]) -> Option<MergeResult> {
+    let first = disks.first()?;
+
+    let mut result = MergeResult {
+        disk: Disk {
+            header: first.header,
+            track_tbl: Vec::new(),
+            format: first.format,
+        },
+        sectors: Vec::new(),
+    };
+
+    // Track Positions (Track, Side) of all Disks, in the layout of the first Disk
+    let mut positions: Vec<(usize, (usize, usize))> = disks
+        .iter()
+        .flat_map(|disk| {
+            disk.track_tbl
+                .iter()
+                .enumerate()
+                .filter(|(_, track)| !track.sector_tbl.is_empty())
+                .map(move |(idx, _)| disk.track_position(idx))
+        })
+        .filter_map(|(track_no, side_no)| {
+            first
+                .layout_index(track_no, side_no)
+                .map(|idx| (idx, (track_no, side_no)))
+        })
+        .collect();
+    positions.sort_unstable();
+    positions.dedup();
+
+    let num_of_track = positions.last().map_or(0, |(idx, _)| idx + 1);
+    result
+        .disk
+        .track_tbl
+        .resize_with(num_of_track, Track::default);
+
+    for (track_idx, (track_no, side_no)) in positions {
+        let tracks: Vec<Option<&Track>> = disks
+            .iter()
+            .map(|disk| disk.get_track(track_no, side_no))
+            .collect();
+        let mut track = Track::default();
+
+        for (sector_id, nth) in sector_keys(&tracks) {
+            // Copies of this Sector: (Source Disk, &Sector)
+            let copies: Vec<(usize, &Sector)> = tracks
+                .iter()
+                .enumerate()
+                .filter_map(|(src, track)| {
+                    find_sector((*track)?, sector_id, nth).map(|sector| (src, sector))
+                })
+                .collect();
+
+            let (source, votes, good_copies, resolution) = choose(&copies);
+            let sector = copies
+                .iter()
+                .find(|(src, _)| *src == source)
+                .map(|(_, sector)| (*sector).clone())
+                .unwrap();
+
+            track.sector_tbl.push(sector);
+            result.sectors.push(MergedSector {
+                track_idx,
+                sector: sector_id,
+                nth,
+                source,
+                copies: copies.len(),
+                good_copies,
+                votes,
+                resolution,
+            });
+        }
+
+        // Number of Sector may differ between Source Disks
+        let number_of_sector = track.sector_tbl.len() as u16;
+        for sector in track.sector_tbl.iter_mut() {
+            sector.header.number_of_sec = number_of_sector;
+        }
+        track.number_of_sector = number_of_sector;
+
+        result.disk.track_tbl[track_idx] = track;
+    }
+
+    result.disk.update_offset();
+    Some(result)
+}
+
+/// Choose a Copy (Helper function)
+///
+/// # Return
+///
+///   * (Source Disk, Votes, Number of OK Copies, Resolution)
+///
+fn choose(copies: &[(usize, &Sector)]) -> (usize, usize, usize, Resolution) {
+    let good: Vec<&(usize, &Sector)> = copies.iter().filter(|(_, s)| s.is_status_ok()).collect();
+
+    let candidates: Vec<&(usize, &Sector)> = if good.is_empty() {
+        copies.iter().collect()
+    } else {
+        good.clone()
+    };
+
+    // Votes for each candidate (same data)
+    let votes: Vec<usize> = candidates
+        .iter()
+        .map(|(_, a)| candidates.iter().filter(|(_, b)| a.data == b.data).count())
+        .collect();
+    let max_votes = *votes.iter().max().unwrap_or(&0);
+    let (best, _) = votes
+        .iter()
+        .enumerate()
+        .find(|(_, v)| **v == max_votes)
+        .unwrap();
+    let source = candidates[best].0;
+
+    // Another data has the same votes
+    let tie = candidates
+        .iter()
+        .zip(votes.iter())
+        .filter(|(_, v)| **v == max_votes)
+        .any(|((_, s), _)| s.data != candidates[best].1.data);
+
+    let resolution = if good.is_empty() {
+        Resolution::NoGoodCopy
+    } else if max_votes == candidates.len() {
+        Resolution::Ok
+    } else if tie {
+        Resolution::Conflict
+    } else {
+        Resolution::Majority
+    };
+
+    (source, max_votes, good.len(), resolution)
+}
+
+/// Sector Keys of a Track (Helper function)
+///
+/// 各ディスクのファイル上の順に (セクタID, 出現回数) を集める。
+///
+fn sector_keys(tracks: &[Option<&Track>]) -> Vec<(u8, usize)> {
+    let mut keys: Vec<(u8, usize)> = Vec::new();
+
+    for track in tracks.iter().flatten() {
+        let mut seen: Vec<u8> = Vec::new();
+        for sector in sorted_by_offset(track) {
+            let nth = seen.iter().filter(|r| **r == sector.header.sector).count();
+            seen.push(sector.header.sector);
+
+            if !keys.contains(&(sector.header.sector, nth)) {
+                keys.push((sector.header.sector, nth));
+            }
+        }
+    }
+
+    keys
+}
+
+fn find_sector(track: &Track, sector_id: u8, nth: usize) -> Option<&Sector> {
+    sorted_by_offset(track)
+        .into_iter()
+        .filter(|sector| sector.header.sector == sector_id)
+        .nth(nth)
+}
+
+fn sorted_by_offset(track: &Track) -> Vec<&Sector> {
+    let mut sector_tbl: Vec<&Sector> = track.sector_tbl.iter().collect();
+    sector_tbl.sort_by_key(|sector| sector.offset);
+    sector_tbl
+}
+
+// ================================================================================
+//
+//  Test Code
+//
+// ================================================================================
+#[cfg(test)]
+mod test {
+    use crate::diff::diff;
+    use crate::disk::Disk;
+    use crate::fileio::D88FileIO;
+    use crate::format::DISK_TYPE_1D;
+    use crate::merge::{merge, Resolution};
+    use crate::raw::{blank, Geometry};
+    use crate::track::Track;
+
+    const HUBASIC: &str = "../../sample/HuBASIC_Format_2D.d88";
+
+    #[test]
+    fn test_merge_crc_error() {
+        let orig = D88FileIO::open(HUBASIC);
+
+        // Dump A: CRC error at Track 0 Side 1 Sector 1
+        let mut a = D88FileIO::open(HUBASIC);
+        a.disk.track_tbl[1].sector_tbl[0].header.status = 0xb0;
+        a.disk.track_tbl[1].sector_tbl[0].data[0] ^= 0xff;
+
+        // Dump B: CRC error at Track 1 Side 0 Sector 2, missing Track 2 Side 0 Sector 16
+        let mut b = D88FileIO::open(HUBASIC);
+        b.disk.track_tbl[2].sector_tbl[1].header.status = 0xb0;
+        b.disk.track_tbl[2].sector_tbl[1].data[5] ^= 0xff;
+        b.disk.track_tbl[4].sector_tbl.pop();
+
+        let result = merge(&[&a.disk, &b.disk]).unwrap();
+        assert!(result.unresolved().is_empty());
+        assert!(diff(&orig.disk, &result.disk).is_same());
+
+        let sector = &result.sectors[16]; // Track 0 Side 1 Sector 1
+        assert_eq!((sector.track_idx, sector.sector), (1, 1));
+        assert_eq!(
+            (sector.source, sector.copies, sector.good_copies),
+            (1, 2, 1)
+        );
+        assert_eq!(sector.resolution, Resolution::Ok);
+    }
+
+    #[test]
+    fn test_merge_majority_and_unresolved() {
+        let mut a = D88FileIO::open(HUBASIC);
+        let mut b = D88FileIO::open(HUBASIC);
+        let mut c = D88FileIO::open(HUBASIC);
+
+        // Majority: A and C agree
+        b.disk.track_tbl[0].sector_tbl[0].data[0] = 0x00;
+
+        // No good copy
+        for d88fileio in [&mut b, &mut c] {
+            d88fileio.disk.track_tbl[3].sector_tbl[0].header.status = 0xb0;
+        }
+        a.disk.track_tbl[3].sector_tbl[0].header.status = 0xa0;
+
+        let result = merge(&[&a.disk, &b.disk, &c.disk]).unwrap();
+
+        assert_eq!(result.sectors[0].resolution, Resolution::Majority);
+        assert_eq!(result.sectors[0].votes, 2);
+        assert_eq!(result.sectors[0].source, 0);
+
+        let unresolved = result.unresolved();
+        assert_eq!(unresolved.len(), 1);
+        assert_eq!(unresolved[0].track_idx, 3);
+        assert_eq!(unresolved[0].resolution, Resolution::NoGoodCopy);
+    }
+
+    /// Single-sided Disk (Side 0 of a blank 2D Disk, Data = Track No.) (Helper function)
+    ///
+    ///   * `even_only` Only even entries of the track table are used (else packed 1D)
+    ///
+    fn single_sided(even_only: bool) -> Disk {
+        let mut disk = blank(&Geometry::from_name("2D").unwrap(), 0xe5);
+        let tracks: Vec<Track> = disk.track_tbl.drain(..).step_by(2).collect();
+        for (track_no, track) in tracks.into_iter().enumerate() {
+            let mut track = track;
+            for sector in track.sector_tbl.iter_mut() {
+                sector.data.fill(track_no as u8);
+            }
+            disk.track_tbl.push(track);
+            if even_only {
+                disk.track_tbl.push(Track::default());
+            }
+        }
+        if !even_only {
+            disk.header.disk_type = DISK_TYPE_1D;
+        }
+        disk.update_offset();
+        disk
+    }
+
+    #[test]
+    fn test_merge_track_layout() {
+        let mut packed = single_sided(false);
+        let mut even = single_sided(true);
+        assert!(even.is_even_track_only());
+        assert_eq!(packed.track_tbl.len(), 40);
+
+        // Packed: CRC error at Track 1 Sector 1, Even: CRC error at Track 2 Sector 3
+        packed.track_tbl[1].sector_tbl[0].header.status = 0xb0;
+        packed.track_tbl[1].sector_tbl[0].data[0] ^= 0xff;
+        even.track_tbl[4].sector_tbl[2].header.status = 0xb0;
+        even.track_tbl[4].sector_tbl[2].data[0] ^= 0xff;
+
+        for (disks, len) in [([&packed, &even], 40), ([&even, &packed], 79)] {
+            let result = merge(&disks).unwrap();
+            assert!(result.unresolved().is_empty());
+            assert_eq!(result.disk.track_tbl.len(), len);
+            assert_eq!(result.sectors.len(), 40 * 16);
+            for track_no in 0..40 {
+                let track = result.disk.get_track(track_no, 0).unwrap();
+                assert_eq!(track.sector_tbl.len(), 16);
+                assert!(track
+                    .sector_tbl
+                    .iter()
+                    .all(|sector| sector.is_status_ok() && sector.data[0] == track_no as u8));
+            }
+        }
+    }
+
+    #[test]
+    fn test_merge_write() {
+        let a = D88FileIO::open(HUBASIC);
+        let result = merge(&[&a.disk]).unwrap();
+
+        let mut img: Vec<u8> = Vec::new();
+        assert_eq!(result.disk.write(&mut img), Ok(348848));
+        assert_eq!(img, std::fs::read(HUBASIC).unwrap());
+    }
+}
@@ -0,0 +1,385 @@
+use crate::disk::Disk;
+use crate::sector::Sector;
+use crate::track::Track;
+
+/// Finding of Copy Protection Analysis
+///
+/// 1トラック内で見つかった、通常のフォーマットと異なる点
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Finding {
+    /// Same Sector ID appears more than once
+    DuplicateSectorId { sector: u8, count: usize },
+    /// C/H of Sector ID don't match the physical Track/Side
+    IdMismatch { sector: u8, track: u8, side: u8 },
+    /// N > 3 (larger than 1024 byte)
+    NonStandardN { sector: u8, n: u8 },
+    /// `size_of_data` differs from 128 << N
+    SizeMismatch {
+        sector: u8,
+        n: u8,
+        size_of_data: u16,
+    },
+    /// ID CRC / Data CRC Error, No Address Mark, No Data Mark
+    CrcError { sector: u8, status: u8 },
+    /// Deleted Data Mark
+    DeletedData { sector: u8 },
+    /// Number of Sector differs from most tracks
+    UnusualSectorCount { count: usize, typical: usize },
+    /// `number_of_sec` at Sector Header differs from actual count
+    NumberOfSectorMismatch { number_of_sec: u16, count: usize },
+    /// Single and Double Density in one Track
+    MixedDensity,
+    /// Different N in one Track
+    MixedSectorSize,
+}
+
+impl Finding {
+    /// Pattern Name (Helper function)
+    ///
+    pub fn get_pattern(&self) -> &'static str {
+        match self {
+            Finding::DuplicateSectorId { .. } => "Duplicate ID",
+            Finding::IdMismatch { .. } => "ID Mismatch",
+            Finding::NonStandardN { .. } => "Non-standard N",
+            Finding::SizeMismatch { .. } => "Long/Short Sector",
+            Finding::CrcError { .. } => "CRC Error",
+            Finding::DeletedData { .. } => "Deleted Data",
+            Finding::UnusualSectorCount { .. } => "Unusual Sector Count",
+            Finding::NumberOfSectorMismatch { .. } => "Sector Count Mismatch",
+            Finding::MixedDensity => "Mixed Density",
+            Finding::MixedSectorSize => "Mixed Sector Size",
+        }
+    }
+
+    /// Detail (Helper function)
+    ///
+    pub fn get_detail(&self) -> String {
+        match self {
+            Finding::DuplicateSectorId { sector, count } => {
+                format!("Sector({}) appears {} times", sector, count)
+            }
+            Finding::IdMismatch {
+                sector,
+                track,
+                side,
+            } => format!("Sector({}) has ID C={}, H={}", sector, track, side),
+            Finding::NonStandardN { sector, n } => format!("Sector({}) N={}", sector, n),
+            Finding::SizeMismatch {
+                sector,
+                n,
+                size_of_data,
+            } => format!(
+                "Sector({}) N={}, DataSize({} byte)",
+                sector, n, size_of_data
+            ),
+            Finding::CrcError { sector, status } => {
+                format!("Sector({}) Status({:02x}h)", sector, status)
+            }
+            Finding::DeletedData { sector } => format!("Sector({})", sector),
+            Finding::UnusualSectorCount { count, typical } => {
+                format!("{} sectors (typical {})", count, typical)
+            }
+            Finding::NumberOfSectorMismatch {
+                number_of_sec,
+                count,
+            } => format!("NumOfSector({}), actual {} sectors", number_of_sec, count),
+            Finding::MixedDensity => "Single and double density sectors".to_string(),
+            Finding::MixedSectorSize => "Different N in one track".to_string(),
+        }
+    }
+}
+
+/// Analysis of a Track
+///
+#[derive(Debug, Clone)]
+pub struct TrackAnalysis {
+    /// Index to `track_tbl`
+    pub track_idx: usize,
+    pub track: usize,
+    pub side: usize,
+    pub number_of_sector: usize,
+    pub findings: Vec<Finding>,
+}
+
+impl TrackAnalysis {
+    /// No Finding
+    ///
+    pub fn is_normal(&self) -> bool {
+        self.findings.is_empty()
+    }
+
+    /// Classification (Helper function)
+    ///
+    /// 見つかったパターン名を重複無しで並べたもの。
+    ///
+    pub fn get_classification(&self) -> String {
+        if self.number_of_sector == 0 {
+            return "Unformatted".to_string();
+        }
+        if self.findings.is_empty() {
+            return "Normal".to_string();
+        }
+
+        let mut patterns: Vec<&str> = Vec::new();
+        for finding in self.findings.iter() {
+            if !patterns.contains(&finding.get_pattern()) {
+                patterns.push(finding.get_pattern());
+            }
+        }
+        patterns.join(", ")
+    }
+}
+
+/// Copy Protection Analysis
+///
+/// セクタヘッダから、プロテクトに使われる特徴をトラック毎に調べる。
+///
+/// # Argument
+///
+///   * `disk` Disk
+///
+/// # Return
+///
+///   * Vec<TrackAnalysis>  One entry for each (Track, Side) in `track_tbl`
+///
+pub fn analyze(disk: &Disk) -> Vec<TrackAnalysis> {
+    let typical = typical_sector_count(disk);
+
+    disk.track_tbl
+        .iter()
+        .enumerate()
+        .filter(|(idx, _)| disk.is_track_slot(*idx))
+        .map(|(idx, track)| {
+            let (track_no, side_no) = disk.track_position(idx);
+            TrackAnalysis {
+                track_idx: idx,
+                track: track_no,
+                side: side_no,
+                number_of_sector: track.sector_tbl.len(),
+                findings: analyze_track(track, track_no, side_no, typical),
+            }
+        })
+        .collect()
+}
+
+/// Analyze a Track (Helper function)
+///
+fn analyze_track(track: &Track, track_no: usize, side_no: usize, typical: usize) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    if track.sector_tbl.is_empty() {
+        return findings;
+    }
+
+    let mut sector_tbl: Vec<&Sector> = track.sector_tbl.iter().collect();
+    sector_tbl.sort_by_key(|sector| sector.offset);
+
+    // Duplicate Sector ID
+    let mut checked: Vec<u8> = Vec::new();
+    for sector in sector_tbl.iter() {
+        let id = sector.header.sector;
+        if checked.contains(&id) {
+            continue;
+        }
+        checked.push(id);
+
+        let count = sector_tbl.iter().filter(|s| s.header.sector == id).count();
+        if count > 1 {
+            findings.push(Finding::DuplicateSectorId { sector: id, count });
+        }
+    }
+
+    // Each Sector
+    for sector in sector_tbl.iter() {
+        let hdr = &sector.header;
+
+        if (hdr.track as usize != track_no) || (hdr.side as usize != side_no) {
+            findings.push(Finding::IdMismatch {
+                sector: hdr.sector,
+                track: hdr.track,
+                side: hdr.side,
+            });
+        }
+
+        if hdr.sector_size > 3 {
+            findings.push(Finding::NonStandardN {
+                sector: hdr.sector,
+                n: hdr.sector_size,
+            });
+        }
+
+        if sector.get_sector_size_byte() != Some(hdr.size_of_data as usize) {
+            findings.push(Finding::SizeMismatch {
+                sector: hdr.sector,
+                n: hdr.sector_size,
+                size_of_data: hdr.size_of_data,
+            });
+        }
+
+        if !sector.is_status_ok() {
+            findings.push(Finding::CrcError {
+                sector: hdr.sector,
+                status: hdr.status,
+            });
+        }
+
+        if (hdr.deleted_mark == 0x10) || (hdr.status == 0x10) {
+            findings.push(Finding::DeletedData { sector: hdr.sector });
+        }
+    }
+
+    // Track
+    let count = sector_tbl.len();
+    if count != typical {
+        findings.push(Finding::UnusualSectorCount { count, typical });
+    }
+
+    let number_of_sec = sector_tbl[0].header.number_of_sec;
+    if number_of_sec as usize != count {
+        findings.push(Finding::NumberOfSectorMismatch {
+            number_of_sec,
+            count,
+        });
+    }
+
+    let density = sector_tbl[0].header.density;
+    if sector_tbl.iter().any(|s| s.header.density != density) {
+        findings.push(Finding::MixedDensity);
+    }
+
+    let n = sector_tbl[0].header.sector_size;
+    if sector_tbl.iter().any(|s| s.header.sector_size != n) {
+        findings.push(Finding::MixedSectorSize);
+    }
+
+    findings
+}
+
+/// Most frequent Number of Sector (Helper function)
+///
+fn typical_sector_count(disk: &Disk) -> usize {
+    let mut counts: Vec<(usize, usize)> = Vec::new(); // (Number of Sector, Tracks)
+
+    for track in disk.track_tbl.iter().filter(|t| !t.sector_tbl.is_empty()) {
+        let count = track.sector_tbl.len();
+        if let Some(entry) = counts.iter_mut().find(|(c, _)| *c == count) {
+            entry.1 += 1;
+        } else {
+            counts.push((count, 1));
+        }
+    }
+
+    counts
+        .iter()
+        .max_by_key(|(count, tracks)| (*tracks, *count))
+        .map_or(0, |(count, _)| *count)
+}
+
+// ================================================================================
+//
+//  Test Code
+//
+// ================================================================================
+#[cfg(test)]
+mod test {
+    use crate::fileio::D88FileIO;
+    use crate::format::DISK_TYPE_1D;
+    use crate::protect::{analyze, Finding};
+    use crate::raw::{self, Geometry};
+
+    const HUBASIC: &str = "../../sample/HuBASIC_Format_2D.d88";
+
+    #[test]
+    fn test_analyze_normal() {
+        let d88fileio = D88FileIO::open(HUBASIC);
+
+        let result = analyze(&d88fileio.disk);
+        assert_eq!(result.len(), 80);
+        assert!(result.iter().all(|t| t.is_normal()));
+        assert_eq!(result[0].get_classification(), "Normal");
+    }
+
+    #[test]
+    fn test_analyze_even_track_only() {
+        // 1D, Track 0..39 at even index
+        let mut disk = raw::blank(&Geometry::from_name("2D").unwrap(), 0xe5);
+        disk.header.disk_type = DISK_TYPE_1D;
+        for (idx, track) in disk.track_tbl.iter_mut().enumerate() {
+            if (idx % 2) == 1 {
+                track.sector_tbl.clear();
+                track.number_of_sector = 0;
+            }
+        }
+        disk.update_offset();
+        assert!(disk.is_even_track_only());
+
+        let result = analyze(&disk);
+        assert_eq!(result.len(), 40);
+        for (track_no, t) in result.iter().enumerate() {
+            assert_eq!((t.track_idx, t.track, t.side), (track_no * 2, track_no, 0));
+        }
+        assert!(result.iter().all(|t| t.is_normal()));
+    }
+
+    #[test]
+    fn test_analyze_protect() {
+        let mut d88fileio = D88FileIO::open(HUBASIC);
+
+        // Track 0, Side 1
+        let track = &mut d88fileio.disk.track_tbl[1];
+        track.sector_tbl[1].header.sector = 1;
+        track.sector_tbl[2].header.track = 40;
+        track.sector_tbl[3].header.status = 0xb0;
+        track.sector_tbl[4].header.deleted_mark = 0x10;
+        track.sector_tbl[5].header.sector_size = 6;
+        track.sector_tbl[6].header.density = 0x40;
+
+        // Track 1, Side 0
+        d88fileio.disk.track_tbl[2].sector_tbl.pop();
+
+        let result = analyze(&d88fileio.disk);
+
+        let findings = &result[1].findings;
+        assert!(findings.contains(&Finding::DuplicateSectorId {
+            sector: 1,
+            count: 2
+        }));
+        assert!(findings.contains(&Finding::IdMismatch {
+            sector: 3,
+            track: 40,
+            side: 1
+        }));
+        assert!(findings.contains(&Finding::CrcError {
+            sector: 4,
+            status: 0xb0
+        }));
+        assert!(findings.contains(&Finding::DeletedData { sector: 5 }));
+        assert!(findings.contains(&Finding::NonStandardN { sector: 6, n: 6 }));
+        assert!(findings.contains(&Finding::SizeMismatch {
+            sector: 6,
+            n: 6,
+            size_of_data: 256
+        }));
+        assert!(findings.contains(&Finding::MixedDensity));
+        assert!(findings.contains(&Finding::MixedSectorSize));
+        assert_eq!(
+            result[1].get_classification(),
+            "Duplicate ID, ID Mismatch, CRC Error, Deleted Data, Non-standard N, \
+             Long/Short Sector, Mixed Density, Mixed Sector Size"
+        );
+
+        assert_eq!(
+            result[2].findings,
+            vec![
+                Finding::UnusualSectorCount {
+                    count: 15,
+                    typical: 16
+                },
+                Finding::NumberOfSectorMismatch {
+                    number_of_sec: 16,
+                    count: 15
+                }
+            ]
+        );
+    }
+}
@@ -0,0 +1,232 @@
+use crate::disk::Disk;
+use crate::format::{D88_SectorHdr, DISK_TYPE_2D, DISK_TYPE_2DD, DISK_TYPE_2HD};
+use crate::sector::Sector;
+use crate::track::Track;
+
+/// Disk Geometry
+///
+/// 標準フォーマットのトラック数、面数、セクタ数、セクタサイズ。
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Geometry {
+    pub disk_type: u8,
+    pub tracks: usize,
+    pub sides: usize,
+    pub sectors: usize,
+    /// Sector Size (byte)
+    pub sector_size: usize,
+}
+
+/// Standard Geometries
+///
+///   * 2D   40 tracks x 2 sides x 16 sectors x  256 byte (320KB)
+///   * 2DD  80 tracks x 2 sides x 16 sectors x  256 byte (640KB)
+///   * 2HD  77 tracks x 2 sides x  8 sectors x 1024 byte (1232KB)
+///
+pub const GEOMETRIES: [Geometry; 3] = [
+    Geometry {
+        disk_type: DISK_TYPE_2D,
+        tracks: 40,
+        sides: 2,
+        sectors: 16,
+        sector_size: 256,
+    },
+    Geometry {
+        disk_type: DISK_TYPE_2DD,
+        tracks: 80,
+        sides: 2,
+        sectors: 16,
+        sector_size: 256,
+    },
+    Geometry {
+        disk_type: DISK_TYPE_2HD,
+        tracks: 77,
+        sides: 2,
+        sectors: 8,
+        sector_size: 1024,
+    },
+];
+
+impl Geometry {
+    /// Geometry of Disk Type (DISK_TYPE_2D, ...)
+    ///
+    pub fn from_disk_type(disk_type: u8) -> Option<Geometry> {
+        GEOMETRIES
+            .iter()
+            .find(|geometry| geometry.disk_type == disk_type)
+            .copied()
+    }
+
+    /// Geometry of Disk Type Name ("2D", "2DD", "2HD")
+    ///
+    pub fn from_name(name: &str) -> Option<Geometry> {
+        match name.to_ascii_uppercase().as_str() {
+            "2D" => Geometry::from_disk_type(DISK_TYPE_2D),
+            "2DD" => Geometry::from_disk_type(DISK_TYPE_2DD),
+            "2HD" => Geometry::from_disk_type(DISK_TYPE_2HD),
+            _ => None,
+        }
+    }
+
+    /// Geometry of Raw Image Size
+    ///
+    pub fn from_image_size(size: usize) -> Option<Geometry> {
+        GEOMETRIES
+            .iter()
+            .find(|geometry| geometry.image_size() == size)
+            .copied()
+    }
+
+    /// Raw Image Size (byte)
+    ///
+    pub fn image_size(&self) -> usize {
+        self.tracks * self.sides * self.sectors * self.sector_size
+    }
+
+    /// Sector Size Code (N)
+    ///
+    pub fn size_code(&self) -> u8 {
+        (self.sector_size / 128).trailing_zeros() as u8
+    }
+}
+
+/// Blank Disk
+///
+/// 全セクタを `fill` で埋めた標準フォーマットのディスク。
+///
+/// # Argument
+///
+///   * `geometry` Geometry
+///   * `fill`     Fill Byte (e.g. 0xe5)
+///
+/// # Return
+///
+///   * Disk (offsets are updated)
+///
+pub fn blank(geometry: &Geometry, fill: u8) -> Disk {
+    let mut disk = Disk::default();
+    disk.header.disk_type = geometry.disk_type;
+
+    for track_no in 0..geometry.tracks {
+        for side_no in 0..geometry.sides {
+            let sector_tbl = (1..=geometry.sectors)
+                .map(|id| Sector {
+                    offset: 0,
+                    header: D88_SectorHdr {
+                        track: track_no as u8,
+                        side: side_no as u8,
+                        sector: id as u8,
+                        sector_size: geometry.size_code(),
+                        number_of_sec: geometry.sectors as u16,
+                        size_of_data: geometry.sector_size as u16,
+                        ..D88_SectorHdr::default()
+                    },
+                    data: vec![fill; geometry.sector_size],
+                })
+                .collect();
+            disk.track_tbl.push(Track {
+                number_of_sector: geometry.sectors as u16,
+                sector_tbl,
+            });
+        }
+    }
+
+    disk.update_offset();
+    disk
+}
+
+/// Raw (Flat) Image to Disk
+///
+/// # Argument
+///
+///   * `data`     Raw Image (Logical Order)
+///   * `geometry` Geometry (None: from image size)
+///
+/// # Return
+///
+///   * Ok(Disk)
+///   * Err(String)  Error Message
+///
+pub fn from_raw(data: &[u8], geometry: Option<Geometry>) -> Result<Disk, String> {
+    let geometry = match geometry.or_else(|| Geometry::from_image_size(data.len())) {
+        Some(geometry) => geometry,
+        None => {
+            return Err(format!(
+                "Unknown raw image size ({} byte), specify the disk type",
+                data.len()
+            ))
+        }
+    };
+    if data.len() != geometry.image_size() {
+        return Err(format!(
+            "Raw image size is {} byte, {} byte expected",
+            data.len(),
+            geometry.image_size()
+        ));
+    }
+
+    let mut disk = blank(&geometry, 0);
+    for (sector, chunk) in disk
+        .track_tbl
+        .iter_mut()
+        .flat_map(|track| track.sector_tbl.iter_mut())
+        .zip(data.chunks(geometry.sector_size))
+    {
+        sector.data.copy_from_slice(chunk);
+    }
+    Ok(disk)
+}
+
+/// Disk to Raw (Flat) Image
+///
+/// 論理順に並べたセクタデータ。欠けているセクタは `fill` で埋める。
+///
+pub fn to_raw(disk: &Disk, fill: u8) -> Vec<u8> {
+    crate::extract::extract_sectors(disk, &crate::select::Selection::default(), Some(fill))
+        .into_iter()
+        .flat_map(|sector| sector.data)
+        .collect()
+}
+
+// ================================================================================
+//
+//  Test Code
+//
+// ================================================================================
+#[cfg(test)]
+mod test {
+    use crate::fileio::D88FileIO;
+    use crate::raw::{blank, from_raw, to_raw, Geometry};
+
+    const HUBASIC: &str = "../../sample/HuBASIC_Format_2D.d88";
+
+    #[test]
+    fn test_geometry() {
+        let geometry = Geometry::from_name("2d").unwrap();
+        assert_eq!(geometry.image_size(), 327680);
+        assert_eq!(geometry.size_code(), 1);
+        assert_eq!(Geometry::from_image_size(1261568).unwrap().size_code(), 3);
+        assert!(Geometry::from_name("1D").is_none());
+
+        let disk = blank(&geometry, 0xe5);
+        assert_eq!(disk.track_tbl.len(), 80);
+        assert_eq!(disk.header.disk_size, 348848);
+        assert_eq!(disk.track_tbl[79].sector_tbl[15].header.track, 39);
+    }
+
+    #[test]
+    fn test_raw_round_trip() {
+        let d88fileio = D88FileIO::open(HUBASIC);
+        let img = to_raw(&d88fileio.disk, 0xe5);
+        assert_eq!(img.len(), 327680);
+
+        let disk = from_raw(&img, None).unwrap();
+        let mut d88: Vec<u8> = Vec::new();
+        assert!(disk.write(&mut d88).is_ok());
+        let mut original: Vec<u8> = Vec::new();
+        assert!(d88fileio.disk.write(&mut original).is_ok());
+        assert_eq!(d88[0x20..], original[0x20..]); // except Disk Name
+
+        assert!(from_raw(&img[1..], None).is_err());
+    }
+}
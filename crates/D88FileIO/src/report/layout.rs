@@ -0,0 +1,134 @@
+//! Hex Dump Layout
+//!
+//! 16進ダンプの形(1行のバイト数、区切り、オフセットの基準)。
+//!
+
+/// Offset Base of Sector Data (`--offset`)
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OffsetBase {
+    /// Offset at D88 File
+    File,
+    /// Offset in the Sector
+    Sector,
+    /// Offset in Logical Order (LBA x Sector Size)
+    Lba,
+}
+
+/// Hex Dump Layout
+///
+/// 16進ダンプ1行の形。
+///
+///   * `width`       1行のバイト数(8, 16, 32)
+///   * `group`       区切りのスペース無しで並べるバイト数(1, 2, 4, 8)
+///   * `offset_base` セクタデータのオフセットの基準
+///   * `skip_fill`   全て同じ値のセクタを表示しない
+///   * `squeeze`     前の行と同じ行を"*"にまとめる
+///   * `char_column` 文字欄を表示する
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HexLayout {
+    pub width: usize,
+    pub group: usize,
+    pub offset_base: OffsetBase,
+    pub skip_fill: bool,
+    pub squeeze: bool,
+    pub char_column: bool,
+}
+
+impl Default for HexLayout {
+    fn default() -> Self {
+        Self {
+            width: 16,
+            group: 1,
+            offset_base: OffsetBase::File,
+            skip_fill: false,
+            squeeze: false,
+            char_column: true,
+        }
+    }
+}
+
+impl HexLayout {
+    /// Offset Bar (Helper function)
+    ///
+    /// # Return
+    ///
+    ///   * ("Offset  +0 +1 ...", "------  ------...")
+    ///
+    pub fn get_offset_bar(&self) -> (String, String) {
+        let mut labels = String::from("");
+        for pos in (0..self.width).step_by(self.group) {
+            let label = if pos < 16 {
+                format!("+{:x}", pos)
+            } else {
+                format!("{:02x}", pos)
+            };
+            labels.push_str(&format!("{:<1$} ", label, self.group * 2));
+        }
+
+        let hex_len = labels.len();
+        let bar_len = if self.char_column {
+            hex_len + self.width
+        } else {
+            hex_len
+        };
+        (
+            format!("Offset  {:<1$}", labels, bar_len),
+            format!("------  {:<1$}", "-".repeat(hex_len - 1), bar_len),
+        )
+    }
+
+    /// Hex String (Helper function)
+    ///
+    /// `buf` を`group`バイトごとに区切って16進で並べる。
+    /// `width` に満たない行はスペースで埋める。
+    ///
+    pub fn get_hex_str(&self, buf: &[u8]) -> String {
+        let mut hex_str = String::from("");
+        for pos in 0..buf.len().max(self.width) {
+            match buf.get(pos) {
+                Some(byte) => hex_str.push_str(&format!("{:02x}", byte)),
+                None => hex_str.push_str("  "),
+            }
+            if (pos + 1).is_multiple_of(self.group) {
+                hex_str.push(' ');
+            }
+        }
+        hex_str
+    }
+}
+
+// ================================================================================
+//
+//  Test Code
+//
+// ================================================================================
+#[cfg(test)]
+mod test {
+    use crate::report::layout::HexLayout;
+
+    #[test]
+    fn test_hex_layout() {
+        let layout = HexLayout::default();
+        let (bar1, bar2) = layout.get_offset_bar();
+        assert_eq!(
+            bar1,
+            "Offset  +0 +1 +2 +3 +4 +5 +6 +7 +8 +9 +a +b +c +d +e +f                 "
+        );
+        assert_eq!(
+            bar2,
+            "------  -----------------------------------------------                 "
+        );
+        assert_eq!(layout.get_hex_str(&[0x01, 0x02]).len(), 48);
+
+        let layout = HexLayout {
+            width: 8,
+            group: 4,
+            char_column: false,
+            ..HexLayout::default()
+        };
+        assert_eq!(layout.get_offset_bar().0, "Offset  +0       +4       ");
+        assert_eq!(layout.get_hex_str(&[0xe5; 6]), "e5e5e5e5 e5e5     ");
+    }
+}
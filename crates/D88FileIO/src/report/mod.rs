@@ -0,0 +1,641 @@
+//! Report Rendering
+//!
+//! D88ファイルのレポート(ヘッダのサマリ、トラックオフセットテーブル、セクタのサマリ、16進ダンプ)を
+//! `io::Write` に書き出す。色付けは `Style` で差し替える。
+//!
+//! ```no_run
+//! use D88FileIO::charset::Charset;
+//! use D88FileIO::fileio;
+//! use D88FileIO::report::layout::HexLayout;
+//! use D88FileIO::report::style::Plain;
+//! use D88FileIO::report::{HexDump, Report};
+//!
+//! let d88fileio = fileio::D88FileIO::open("sample/HuBASIC_Format_2D.d88");
+//! let dump = HexDump::new(Charset::Ascii, HexLayout::default(), &Plain);
+//! let report = Report::new(&d88fileio.disk, dump);
+//!
+//! let mut out = Vec::new();
+//! report.write_info(&mut out).unwrap();
+//! ```
+//!
+pub mod layout;
+pub mod style;
+
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::mem;
+
+use crate::boot::Boot;
+use crate::charset::Charset;
+use crate::classify::{classify, is_fill, Content, Usage};
+use crate::disk::Disk;
+use crate::filesystem::detect;
+use crate::format::{D88_Header, D88_SectorHdr};
+use crate::report::layout::{HexLayout, OffsetBase};
+use crate::report::style::{Color, Style};
+use crate::sector::Sector;
+
+/// Hex Dump
+///
+/// バイト列を `layout` の形で、文字欄は `charset` で表示する。
+///
+#[derive(Clone, Copy)]
+pub struct HexDump<'a> {
+    pub charset: Charset,
+    pub layout: HexLayout,
+    pub style: &'a dyn Style,
+}
+
+impl<'a> HexDump<'a> {
+    /// Constructor
+    ///
+    /// # Argument
+    ///
+    ///   * `charset` Character Set of the Character Column
+    ///   * `layout` Hex Dump Layout
+    ///   * `style` Output Style
+    ///
+    pub fn new(charset: Charset, layout: HexLayout, style: &'a dyn Style) -> Self {
+        Self {
+            charset,
+            layout,
+            style,
+        }
+    }
+
+    /// Write Offset Bar
+    ///
+    /// "Offset  +0 +1 ..." と "------  ------..." の2行。
+    ///
+    pub fn write_offset_bar<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        let (bar1, bar2) = self.layout.get_offset_bar();
+        writeln!(w, "{}", bar1)?;
+        writeln!(w, "{}", bar2)
+    }
+
+    /// Write Data
+    ///
+    /// `data` を`layout.width`バイトずつ書き出す。
+    /// `layout.squeeze` なら前の行と同じ行は"*"1行にまとめる(`hexdump -C`と同じく、最後が"*"なら終端のオフセットを書く)。
+    ///
+    /// # Argument
+    ///
+    ///   * `w` Writer
+    ///   * `data` Data
+    ///   * `offset` Offset of the first byte
+    ///   * `color` Color of the Hex Column
+    ///
+    pub fn write_data<W: Write>(
+        &self,
+        w: &mut W,
+        data: &[u8],
+        offset: u64,
+        color: Color,
+    ) -> io::Result<()> {
+        let cells = self.charset.render(data);
+        let width = self.layout.width;
+
+        let mut prev: Option<&[u8]> = None;
+        let mut squeezed = false;
+        for (idx, row) in data.chunks(width).enumerate() {
+            if self.layout.squeeze && prev == Some(row) {
+                if !squeezed {
+                    writeln!(w, "*")?;
+                    squeezed = true;
+                }
+                continue;
+            }
+            prev = Some(row);
+            squeezed = false;
+
+            let pos = idx * width;
+            self.write_row(
+                w,
+                row,
+                &cells[pos..pos + row.len()],
+                offset + pos as u64,
+                color,
+            )?;
+            writeln!(w)?;
+        }
+        if squeezed {
+            writeln!(w, "{:06x}", offset + data.len() as u64)?;
+        }
+        Ok(())
+    }
+
+    /// Write Header Rows (Helper function)
+    ///
+    /// ヘッダ(16byte)を`layout.width`バイトずつ書き出す。
+    /// 最後の行は改行しない(後ろにヘッダの内容を書く)。`squeeze` はしない。
+    ///
+    fn write_header_rows<W: Write>(
+        &self,
+        w: &mut W,
+        buf16: &[u8],
+        offset: u64,
+        color: Color,
+    ) -> io::Result<()> {
+        let header = &buf16[..16];
+        let cells = self.charset.render(header);
+        let width = self.layout.width;
+
+        for pos in (0..header.len()).step_by(width) {
+            if pos > 0 {
+                writeln!(w)?;
+            }
+            let end = (pos + width).min(header.len());
+            self.write_row(
+                w,
+                &header[pos..end],
+                &cells[pos..end],
+                offset + pos as u64,
+                color,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Write a Row (Helper function)
+    ///
+    /// オフセット、16進、文字欄を書き出す(改行はしない)。
+    /// 区切りと文字欄は`layout`に従う。
+    ///
+    /// # Argument
+    ///
+    ///   * `w` Writer
+    ///   * `row` Bytes of the Row
+    ///   * `cells` Character Cells of `row` (`Charset::render`)
+    ///   * `offset` Offset of the first byte
+    ///   * `color` Color of the Hex Column
+    ///
+    fn write_row<W: Write>(
+        &self,
+        w: &mut W,
+        row: &[u8],
+        cells: &[String],
+        offset: u64,
+        color: Color,
+    ) -> io::Result<()> {
+        // Offset Address
+        //
+        write!(
+            w,
+            "{}  ",
+            self.style.paint(Color::Cyan, &format!("{:06x}", offset))
+        )?;
+
+        // Hex
+        //
+        let hex_str = self.layout.get_hex_str(row);
+        write!(w, "{}", self.style.paint(color, &hex_str))?;
+
+        // Character
+        //
+        if self.layout.char_column {
+            let mut char_str = String::from("");
+            for (i, cell) in cells.iter().enumerate() {
+                char_str.push_str(if cell.is_empty() && i == 0 { " " } else { cell });
+            }
+            let pad = self.layout.width.saturating_sub(row.len());
+            write!(w, "{}{} ", char_str, " ".repeat(pad))?;
+        }
+        Ok(())
+    }
+}
+
+/// Report of a Disk
+///
+/// `d88dmp` の "info", "sectors", "dump" と同じ形で書き出す。
+///
+pub struct Report<'a> {
+    pub disk: &'a Disk,
+    pub dump: HexDump<'a>,
+
+    /// Sector Offset at D88 File -> Logical Offset (`OffsetBase::Lba`)
+    logical_offsets: HashMap<u64, u64>,
+}
+
+impl<'a> Report<'a> {
+    /// Constructor
+    ///
+    /// # Argument
+    ///
+    ///   * `disk` Disk
+    ///   * `dump` Hex Dump (Charset, Layout, Style)
+    ///
+    pub fn new(disk: &'a Disk, dump: HexDump<'a>) -> Self {
+        let mut logical_offsets = HashMap::new();
+        if dump.layout.offset_base == OffsetBase::Lba {
+            let mut logical_offset = 0_u64;
+            for (_, sector) in disk.logical_sectors() {
+                logical_offsets.insert(sector.offset, logical_offset);
+                logical_offset += sector.data.len() as u64;
+            }
+        }
+
+        Self {
+            disk,
+            dump,
+            logical_offsets,
+        }
+    }
+
+    /// Write D88 Information
+    ///
+    /// ヘッダのサマリとトラックオフセットテーブル("info")。
+    ///
+    pub fn write_info<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        self.write_header_summary(w)?;
+        writeln!(w)?;
+        writeln!(w)?;
+        self.write_track_offset_table(w)
+    }
+
+    /// Write D88 File Header Summary
+    ///
+    pub fn write_header_summary<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        writeln!(w, "D88 File Header Summary")?;
+        writeln!(w, "  {}", self.get_disk_name())?;
+        writeln!(w, "  Protect{}", self.disk.get_disk_write_protect())?;
+        writeln!(w, "  {}", self.disk.get_disk_type())?;
+        writeln!(w, "  {}", self.disk.get_disk_size())?;
+        writeln!(w, "  {}", self.disk.get_image_format())?;
+        if let Some(fs) = detect(self.disk) {
+            writeln!(w, "  Filesystem({})", fs.get_name())?;
+        }
+        let boot = Boot::identify(self.disk);
+        writeln!(w, "  Boot({})", boot.get_name())?;
+        for (name, value) in boot.get_fields() {
+            writeln!(w, "    {}({})", name, value)?;
+        }
+        Ok(())
+    }
+
+    /// Write Track Offset Table
+    ///
+    /// 1行に8トラック。オフセットが0(トラック無し)は"------"。
+    ///
+    pub fn write_track_offset_table<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        writeln!(w, "Track Offset Table")?;
+        writeln!(w)?;
+        writeln!(w, "Track No.")?;
+        writeln!(
+            w,
+            "hex dec   +0     +1     +2     +3     +4     +5     +6     +7    "
+        )?;
+        write!(
+            w,
+            "--- ----  ------ ------ ------ ------ ------ ------ ------ ------"
+        )?;
+
+        for (n, track_offset) in self.disk.header.track_offset_tbl.iter().enumerate() {
+            let track_offset_formated = get_track_offset_str(*track_offset);
+            if (n % 8) == 0 {
+                writeln!(w)?;
+                write!(
+                    w,
+                    "{}  {} ",
+                    self.dump
+                        .style
+                        .paint(Color::Cyan, &format!("{0:2x}h {0:3}d", n)),
+                    track_offset_formated
+                )?;
+            } else {
+                write!(w, "{} ", track_offset_formated)?;
+            }
+        }
+        writeln!(w)
+    }
+
+    /// Write Sector Summary
+    ///
+    /// セクタヘッダの一覧と中身の種類("sectors")。最後に使用量と種類ごとのセクタ数を書く。
+    ///
+    /// # Argument
+    ///
+    ///   * `w` Writer
+    ///   * `sectors` Vec<(Index of `track_tbl`, Sector)>
+    ///
+    pub fn write_sector_summary<W: Write>(
+        &self,
+        w: &mut W,
+        sectors: &[(usize, &Sector)],
+    ) -> io::Result<()> {
+        writeln!(w, "Sector Summary")?;
+        writeln!(w)?;
+        writeln!(
+            w,
+            "Track    Side Sector   Data      Content             Header Parameter"
+        )?;
+        writeln!(w, "hex dec  No.  No. Num  Offset    Type        Entropy")?;
+        writeln!(w, "--- ---- ---  --- ---  -------  ----------  -------  ---------------------------------- ...")?;
+
+        let style = self.dump.style;
+        let mut prev_idx = None;
+        for (idx, sector) in sectors.iter() {
+            let track = &self.disk.track_tbl[*idx];
+
+            let tso_formated = if prev_idx != Some(*idx) {
+                format!(
+                    "{0:02x}h {0:3}d {1:3}  {2:3} {3:3} ",
+                    sector.header.track,
+                    sector.header.side,
+                    sector.header.sector,
+                    track.number_of_sector
+                )
+            } else {
+                format!("... .... ...  {:3} ... ", sector.header.sector)
+            };
+            prev_idx = Some(*idx);
+            write!(w, "{} ", style.paint(Color::Cyan, &tso_formated))?;
+
+            let offset_formated = format!("{:06x}h ", sector.offset);
+            write!(w, "{} ", style.paint(Color::Cyan, &offset_formated))?;
+
+            let classification = classify(&sector.data);
+            write!(
+                w,
+                "{:10}  {:7.3}  ",
+                classification.content.get_name(),
+                classification.entropy
+            )?;
+
+            writeln!(
+                w,
+                "{}, {}, {}, {}, {}, {}",
+                sector.get_sector_size(),
+                sector.get_num_of_sector(),
+                sector.get_status(),
+                sector.get_density(),
+                sector.get_mark(),
+                sector.get_data_size(),
+            )?;
+        }
+
+        // Used / Empty Capacity
+        let usage = Usage::new(sectors.iter().map(|(_, sector)| *sector));
+        writeln!(w)?;
+        writeln!(
+            w,
+            "Used({} byte, {} sec), Empty({} byte, {} sec)",
+            usage.used_bytes, usage.used_sectors, usage.empty_bytes, usage.empty_sectors
+        )?;
+        let counts: Vec<String> = Content::ALL
+            .iter()
+            .zip(usage.counts.iter())
+            .map(|(content, count)| format!("{}({})", content.get_name(), count))
+            .collect();
+        writeln!(w, "{}", counts.join(", "))
+    }
+
+    /// Write Byte Image of the whole Disk
+    ///
+    /// D88ファイルヘッダと、全トラックのセクタ("dump")。
+    ///
+    pub fn write_dump<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        self.write_header_dump(w)?;
+        for track in self.disk.track_tbl.iter() {
+            for sector in track.sector_tbl.iter() {
+                self.write_sector(w, sector)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write Byte Image of D88 File Header
+    ///
+    /// オフセットの見出し、ヘッダ(32byte)とトラックオフセットテーブル(4byte x 164)。
+    ///
+    pub fn write_header_dump<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        let header = &self.disk.header;
+        let byte_img;
+        unsafe {
+            byte_img = mem::transmute::<D88_Header, [u8; mem::size_of::<D88_Header>()]>(*header);
+        }
+
+        self.dump.write_offset_bar(w)?;
+
+        self.dump
+            .write_header_rows(w, &byte_img, 0x00000, Color::Green)?; //   0 - 15 byte
+        writeln!(w, "{}", self.get_disk_name())?;
+
+        self.dump
+            .write_header_rows(w, &byte_img[16..], 0x00010, Color::Green)?; //  16 - 31 byte
+        writeln!(
+            w,
+            "{}, {}, {}",
+            self.disk.get_disk_write_protect(),
+            self.disk.get_disk_type(),
+            self.disk.get_disk_size(),
+        )?;
+
+        // Offset to Track (32bit(4byte) x 164)
+        //
+        let mut offset = 0x00020_u64;
+        for buf32x4 in header.track_offset_tbl.chunks(4) {
+            let buf8x16: Vec<u8> = buf32x4
+                .iter()
+                .flat_map(|track_offset| track_offset.to_le_bytes())
+                .collect();
+            self.dump
+                .write_header_rows(w, &buf8x16, offset, Color::Yellow)?;
+
+            write!(w, "TrackOffset ")?;
+            for track_offset in buf32x4.iter() {
+                write!(w, "{} ", get_track_offset_str(*track_offset))?;
+            }
+            writeln!(w)?;
+            offset += 16;
+        }
+        Ok(())
+    }
+
+    /// Write Byte Image of a Sector
+    ///
+    /// セクタヘッダ(16byte)とその内容、セクタデータ。
+    /// `layout.skip_fill` なら全て同じ値のセクタは書かない。
+    ///
+    pub fn write_sector<W: Write>(&self, w: &mut W, sector: &Sector) -> io::Result<()> {
+        if self.dump.layout.skip_fill && is_fill(&sector.data) {
+            return Ok(());
+        }
+
+        // Sector Header (Byte Image)
+        //
+        let byte_img;
+        unsafe {
+            byte_img = mem::transmute::<D88_SectorHdr, [u8; mem::size_of::<D88_SectorHdr>()]>(
+                sector.header,
+            );
+        }
+        self.dump.write_header_rows(
+            w,
+            &byte_img,
+            sector.offset - mem::size_of::<D88_SectorHdr>() as u64,
+            Color::Green,
+        )?;
+        writeln!(
+            w,
+            "{}, {}, {}, {}, {}, {}, {}, {}, {}",
+            sector.get_track(),
+            sector.get_side(),
+            sector.get_sector(),
+            sector.get_sector_size(),
+            sector.get_num_of_sector(),
+            sector.get_status(),
+            sector.get_density(),
+            sector.get_mark(),
+            sector.get_data_size(),
+        )?;
+
+        // Sector Data
+        //
+        let offset = match self.dump.layout.offset_base {
+            OffsetBase::File => sector.offset,
+            OffsetBase::Sector => 0,
+            OffsetBase::Lba => *self.logical_offsets.get(&sector.offset).unwrap_or(&0),
+        };
+        self.dump.write_data(w, &sector.data, offset, Color::White)
+    }
+
+    /// Disk Name in `charset` (Helper function)
+    ///
+    /// `Charset::Ascii` なら Shift-JIS で表示する。
+    ///
+    fn get_disk_name(&self) -> String {
+        let charset = match self.dump.charset {
+            Charset::Ascii => Charset::Sjis,
+            charset => charset,
+        };
+        format!("Name({})", self.disk.get_disk_name_with(charset))
+    }
+}
+
+/// Track Offset ("------" if 0) (Helper function)
+///
+fn get_track_offset_str(track_offset: u32) -> String {
+    if track_offset == 0 {
+        "------".to_string()
+    } else {
+        format!("{:06x}", track_offset)
+    }
+}
+
+// ================================================================================
+//
+//  Test Code
+//
+// ================================================================================
+#[cfg(test)]
+mod test {
+    use crate::charset::Charset;
+    use crate::fileio::D88FileIO;
+    use crate::report::layout::{HexLayout, OffsetBase};
+    use crate::report::style::{Ansi, Color, Plain, Style};
+    use crate::report::{HexDump, Report};
+    use crate::select::Selection;
+
+    const SAMPLES: [&str; 2] = ["HuBASIC_Format_2D", "CPM_Format_2D_turboCPM_X1turbo"];
+
+    /// Compare with "../../sample/golden/`name`.txt"
+    ///
+    fn assert_golden(name: &str, out: Vec<u8>) {
+        let path = format!("../../sample/golden/{}.txt", name);
+        let golden = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), golden, "{}", path);
+    }
+
+    /// Render a Sample Disk (Helper function)
+    ///
+    fn render<F>(sample: &str, dump: HexDump, f: F) -> Vec<u8>
+    where
+        F: Fn(&Report, &mut Vec<u8>) -> std::io::Result<()>,
+    {
+        let d88fileio = D88FileIO::open(format!("../../sample/{}.d88", sample));
+        let report = Report::new(&d88fileio.disk, dump);
+        let mut out = Vec::new();
+        f(&report, &mut out).unwrap();
+        out
+    }
+
+    /// Selected Sectors as `write_sector_summary` Argument (Helper function)
+    ///
+    fn write_selected(report: &Report, out: &mut Vec<u8>, position: &str) -> std::io::Result<()> {
+        let mut selection = Selection::default();
+        selection.parse_position(position).unwrap();
+        report.write_sector_summary(out, &selection.select(report.disk))
+    }
+
+    #[test]
+    fn test_golden() {
+        let plain = HexDump::new(Charset::Ascii, HexLayout::default(), &Plain);
+        for sample in SAMPLES {
+            let out = render(sample, plain, |report, out| report.write_info(out));
+            assert_golden(&format!("{}.info", sample), out);
+
+            let out = render(sample, plain, |report, out| {
+                write_selected(report, out, "0-3,*,*")
+            });
+            assert_golden(&format!("{}.sectors", sample), out);
+
+            let out = render(sample, plain, |report, out| {
+                report.dump.write_offset_bar(out)?;
+                for sector in report.disk.get_track(0, 0).unwrap().sector_tbl.iter() {
+                    report.write_sector(out, sector)?;
+                }
+                Ok(())
+            });
+            assert_golden(&format!("{}.dump", sample), out);
+        }
+
+        let out = render(SAMPLES[0], plain, |report, out| {
+            report.write_header_dump(out)
+        });
+        assert_golden("HuBASIC_Format_2D.header", out);
+
+        let ansi = HexDump {
+            style: &Ansi,
+            ..plain
+        };
+        let out = render(SAMPLES[0], ansi, |report, out| report.write_info(out));
+        assert_golden("HuBASIC_Format_2D.info.ansi", out);
+
+        let layout = HexLayout {
+            width: 8,
+            group: 2,
+            offset_base: OffsetBase::Lba,
+            squeeze: true,
+            ..HexLayout::default()
+        };
+        let dump = HexDump::new(Charset::Sjis, layout, &Plain);
+        let out = render(SAMPLES[1], dump, |report, out| {
+            report.dump.write_offset_bar(out)?;
+            for sector in report.disk.get_track(1, 0).unwrap().sector_tbl[..2].iter() {
+                report.write_sector(out, sector)?;
+            }
+            Ok(())
+        });
+        assert_golden("CPM_Format_2D_turboCPM_X1turbo.layout", out);
+
+        // Header rows at the layout width
+        for width in [8, 32] {
+            let layout = HexLayout {
+                width,
+                ..HexLayout::default()
+            };
+            let dump = HexDump::new(Charset::Ascii, layout, &Plain);
+            let out = render(SAMPLES[0], dump, |report, out| {
+                report.write_header_dump(out)?;
+                report.write_sector(out, &report.disk.track_tbl[0].sector_tbl[0])
+            });
+            assert_golden(&format!("HuBASIC_Format_2D.header.w{}", width), out);
+        }
+    }
+
+    #[test]
+    fn test_style() {
+        assert_eq!(Plain.paint(Color::Cyan, "00"), "00");
+        assert_eq!(Ansi.paint(Color::Cyan, "00"), "\x1b[36m00\x1b[0m");
+    }
+}
@@ -0,0 +1,64 @@
+//! Output Style
+//!
+//! レポートの色付けを差し替える。
+//!
+//!   * `Plain` 色無し(`--no-color`、ファイルやテストへの出力)
+//!   * `Ansi`  ANSI エスケープシーケンスで色を付ける(端末への出力)
+//!
+
+/// Color of Report Items
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    /// Offset, Track No.
+    Cyan,
+    /// Header (D88 File Header, Sector Header)
+    Green,
+    /// Track Offset Table
+    Yellow,
+    /// Sector Data
+    White,
+}
+
+impl Color {
+    /// ANSI Foreground Color Code
+    ///
+    pub fn get_ansi_code(&self) -> u8 {
+        match self {
+            Color::Cyan => 36,
+            Color::Green => 32,
+            Color::Yellow => 33,
+            Color::White => 37,
+        }
+    }
+}
+
+/// Output Style
+///
+pub trait Style {
+    /// Paint `text` in `color`
+    ///
+    fn paint(&self, color: Color, text: &str) -> String;
+}
+
+/// No Color
+///
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Plain;
+
+impl Style for Plain {
+    fn paint(&self, _color: Color, text: &str) -> String {
+        text.to_string()
+    }
+}
+
+/// ANSI Color
+///
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Ansi;
+
+impl Style for Ansi {
+    fn paint(&self, color: Color, text: &str) -> String {
+        format!("\x1b[{}m{}\x1b[0m", color.get_ansi_code(), text)
+    }
+}
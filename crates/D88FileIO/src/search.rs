@@ -0,0 +1,323 @@
+use crate::disk::Disk;
+use crate::select::Selection;
+
+/// Search Pattern
+///
+/// 1バイト毎の値。`None` は任意の1バイト(ワイルドカード)に一致する。
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Pattern {
+    pub bytes: Vec<Option<u8>>,
+}
+
+impl Pattern {
+    /// Parse Hex Pattern (e.g. "c3 00 ?? 4d", "C3??4D")
+    ///
+    /// `??` (or `?`, `**`) is a wildcard. Spaces are ignored.
+    ///
+    /// # Argument
+    ///
+    ///   * `s` Hex String
+    ///
+    /// # Return
+    ///
+    ///   * Ok(Pattern)
+    ///   * Err(String)  Error Message
+    ///
+    pub fn from_hex(s: &str) -> Result<Pattern, String> {
+        let mut bytes = Vec::new();
+        for word in s.split_whitespace() {
+            if (word == "?") || (word == "??") || (word == "**") {
+                bytes.push(None);
+                continue;
+            }
+            if !word.len().is_multiple_of(2) {
+                return Err(format!("\"{}\"? Hex Pattern is pairs of digits", word));
+            }
+
+            for pair in word.as_bytes().chunks(2) {
+                let pair = std::str::from_utf8(pair).unwrap_or("");
+                if (pair == "??") || (pair == "**") {
+                    bytes.push(None);
+                } else if let Ok(byte) = u8::from_str_radix(pair, 16) {
+                    bytes.push(Some(byte));
+                } else {
+                    return Err(format!("\"{}\"? Not Hex Pattern", word));
+                }
+            }
+        }
+
+        Pattern::from_bytes(bytes)
+    }
+
+    /// ASCII String
+    ///
+    pub fn from_ascii(s: &str) -> Result<Pattern, String> {
+        if let Some(c) = s.chars().find(|c| !c.is_ascii()) {
+            return Err(format!("'{}'? Not ASCII Character", c));
+        }
+        Pattern::from_bytes(s.bytes().map(Some).collect())
+    }
+
+    /// Shift-JIS String
+    ///
+    pub fn from_sjis(s: &str) -> Result<Pattern, String> {
+        let (bytes, _, unmappable) = encoding_rs::SHIFT_JIS.encode(s);
+        if unmappable {
+            return Err(format!("\"{}\"? Can't be encoded in Shift-JIS", s));
+        }
+        Pattern::from_bytes(bytes.iter().map(|byte| Some(*byte)).collect())
+    }
+
+    /// JIS X 0201 String (ASCII + 半角カナ)
+    ///
+    /// `¥` は 0x5C、`‾` は 0x7E、全角カタカナは半角カナ(濁点・半濁点は2バイト)になる。
+    ///
+    pub fn from_jisx0201(s: &str) -> Result<Pattern, String> {
+        let mut bytes = Vec::new();
+        for c in s.chars() {
+            match c {
+                '¥' => bytes.push(0x5c),
+                '‾' => bytes.push(0x7e),
+                c if c.is_ascii() => bytes.push(c as u8),
+                '\u{ff61}'..='\u{ff9f}' => bytes.push((c as u32 - 0xff61 + 0xa1) as u8),
+                _ => {
+                    // 全角 → 半角 (Shift-JIS の半角カナを経由)
+                    let half = to_halfwidth_kana(c)
+                        .ok_or(format!("'{}'? Can't be encoded in JIS X 0201", c))?;
+                    bytes.extend(half);
+                }
+            }
+        }
+        Pattern::from_bytes(bytes.into_iter().map(Some).collect())
+    }
+
+    /// Pattern Length
+    ///
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Pattern is Empty
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Match at the head of `data`
+    ///
+    pub fn matches(&self, data: &[u8]) -> bool {
+        (data.len() >= self.bytes.len())
+            && self
+                .bytes
+                .iter()
+                .zip(data.iter())
+                .all(|(pattern, byte)| pattern.is_none_or(|pattern| pattern == *byte))
+    }
+
+    /// Check Pattern (Helper function)
+    ///
+    fn from_bytes(bytes: Vec<Option<u8>>) -> Result<Pattern, String> {
+        if bytes.is_empty() {
+            return Err("Search Pattern is empty".to_string());
+        }
+        if bytes.iter().all(|byte| byte.is_none()) {
+            return Err("Search Pattern is only wildcards".to_string());
+        }
+        Ok(Pattern { bytes })
+    }
+}
+
+/// Fullwidth Katakana to JIS X 0201 (Helper function)
+///
+fn to_halfwidth_kana(c: char) -> Option<Vec<u8>> {
+    const KANA: &str = "。「」、・ヲァィゥェォャュョッーアイウエオカキクケコサシスセソタチツテトナニヌネノハヒフヘホマミムメモヤユヨラリルレロワン゛゜";
+    const VOICED: &str = "ガギグゲゴザジズゼゾダヂヅデドバビブベボ";
+    const SEMI_VOICED: &str = "パピプペポ";
+    const VOICED_BASE: &str = "カキクケコサシスセソタチツテトハヒフヘホ";
+    const SEMI_VOICED_BASE: &str = "ハヒフヘホ";
+
+    if let Some(idx) = KANA.chars().position(|k| k == c) {
+        return Some(vec![0xa1 + idx as u8]);
+    }
+    if c == 'ヴ' {
+        return Some(vec![0xb3, 0xde]);
+    }
+    if let Some(idx) = VOICED.chars().position(|k| k == c) {
+        let base = VOICED_BASE.chars().nth(idx)?;
+        return to_halfwidth_kana(base).map(|mut v| {
+            v.push(0xde);
+            v
+        });
+    }
+    if let Some(idx) = SEMI_VOICED.chars().position(|k| k == c) {
+        let base = SEMI_VOICED_BASE.chars().nth(idx)?;
+        return to_halfwidth_kana(base).map(|mut v| {
+            v.push(0xdf);
+            v
+        });
+    }
+    None
+}
+
+/// Search Hit
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hit {
+    /// Index to `track_tbl`
+    pub track_idx: usize,
+    /// Track Number (Physical Position)
+    pub track: usize,
+    /// Side Number (Physical Position)
+    pub side: usize,
+    /// Sector ID (R)
+    pub sector: u8,
+    /// Byte Offset in the Sector Data
+    pub offset: usize,
+    /// Absolute File Offset
+    pub file_offset: u64,
+    /// Number of Sectors the Match spans (1: in one Sector)
+    pub span: usize,
+}
+
+/// Search Sector Data
+///
+/// 選択したセクタのデータを論理順につないで検索する。
+/// セクタをまたぐ一致は、先頭バイトのあるセクタで報告する。
+///
+/// # Argument
+///
+///   * `disk`      Disk
+///   * `selection` Selection
+///   * `pattern`   Pattern
+///
+/// # Return
+///
+///   * Vec<Hit>  Hits in Logical Order
+///
+pub fn search(disk: &Disk, selection: &Selection, pattern: &Pattern) -> Vec<Hit> {
+    let selected = selection.select(disk);
+
+    // Start position of each Sector in the joined data
+    let mut data: Vec<u8> = Vec::new();
+    let mut starts: Vec<usize> = Vec::with_capacity(selected.len());
+    for (_, sector) in selected.iter() {
+        starts.push(data.len());
+        data.extend_from_slice(&sector.data);
+    }
+
+    let mut hits = Vec::new();
+    if pattern.is_empty() || (data.len() < pattern.len()) {
+        return hits;
+    }
+
+    for pos in 0..=(data.len() - pattern.len()) {
+        if !pattern.matches(&data[pos..]) {
+            continue;
+        }
+
+        // Sector which has the first / last byte
+        let first = starts.partition_point(|start| *start <= pos) - 1;
+        let last = starts.partition_point(|start| *start < pos + pattern.len()) - 1;
+
+        let (idx, sector) = selected[first];
+        let (track_no, side_no) = disk.track_position(idx);
+        let offset = pos - starts[first];
+        hits.push(Hit {
+            track_idx: idx,
+            track: track_no,
+            side: side_no,
+            sector: sector.header.sector,
+            offset,
+            file_offset: sector.offset + offset as u64,
+            span: last - first + 1,
+        });
+    }
+
+    hits
+}
+
+// ================================================================================
+//
+//  Test Code
+//
+// ================================================================================
+#[cfg(test)]
+mod test {
+    use crate::fileio::D88FileIO;
+    use crate::search::{search, Pattern};
+    use crate::select::Selection;
+
+    const HUBASIC: &str = "../../sample/HuBASIC_Format_2D.d88";
+
+    #[test]
+    fn test_pattern() {
+        let pattern = Pattern::from_hex("c3 00 ?? 4D").unwrap();
+        assert_eq!(pattern.bytes, vec![Some(0xc3), Some(0), None, Some(0x4d)]);
+        assert_eq!(Pattern::from_hex("C300??4d").unwrap(), pattern);
+        assert!(pattern.matches(&[0xc3, 0, 0x55, 0x4d, 0]));
+        assert!(!pattern.matches(&[0xc3, 0, 0x55]));
+
+        for hex in ["", "??", "c", "c3 0g"] {
+            assert!(Pattern::from_hex(hex).is_err(), "{}", hex);
+        }
+
+        assert_eq!(
+            Pattern::from_sjis("ア").unwrap().bytes,
+            vec![Some(0x83), Some(0x41)]
+        );
+        assert_eq!(
+            Pattern::from_jisx0201("ｱｶﾞ¥").unwrap(),
+            Pattern::from_hex("b1 b6 de 5c").unwrap()
+        );
+        assert_eq!(
+            Pattern::from_jisx0201("アガパ").unwrap(),
+            Pattern::from_hex("b1 b6 de ca df").unwrap()
+        );
+        assert!(Pattern::from_jisx0201("漢").is_err());
+        assert!(Pattern::from_ascii("ア").is_err());
+    }
+
+    #[test]
+    fn test_search() {
+        let mut d88fileio = D88FileIO::open(HUBASIC);
+
+        // Track 0 Side 0 Sector 1 .. Sector 2
+        let sector1 = &mut d88fileio.disk.track_tbl[0].sector_tbl[0];
+        sector1.data[0x10..0x14].copy_from_slice(b"SAVE");
+        let len = sector1.data.len();
+        sector1.data[len - 2..].copy_from_slice(b"DA");
+        let data_offset = sector1.offset;
+        d88fileio.disk.track_tbl[0].sector_tbl[1].data[..2].copy_from_slice(b"TA");
+
+        let hits = search(
+            &d88fileio.disk,
+            &Selection::default(),
+            &Pattern::from_ascii("SAVE").unwrap(),
+        );
+        assert_eq!(hits.len(), 1);
+        assert_eq!((hits[0].track, hits[0].side, hits[0].sector), (0, 0, 1));
+        assert_eq!(hits[0].offset, 0x10);
+        assert_eq!(hits[0].file_offset, data_offset + 0x10);
+        assert_eq!(hits[0].span, 1);
+
+        let hits = search(
+            &d88fileio.disk,
+            &Selection::default(),
+            &Pattern::from_ascii("DATA").unwrap(),
+        );
+        assert_eq!(hits.len(), 1);
+        assert_eq!((hits[0].sector, hits[0].offset), (1, len - 2));
+        assert_eq!(hits[0].span, 2);
+
+        // Not found out of the Selection
+        let mut selection = Selection::default();
+        assert!(selection.set_track("1-39").is_ok());
+        let hits = search(
+            &d88fileio.disk,
+            &selection,
+            &Pattern::from_ascii("SAVE").unwrap(),
+        );
+        assert!(hits.is_empty());
+    }
+}
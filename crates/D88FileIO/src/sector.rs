@@ -1,11 +1,13 @@
-use std::io::{BufReader, Read};
+use std::io::{Read, Write};
 use std::io::{Seek, SeekFrom};
 use std::mem;
 
 use crate::format::D88_SectorHdr;
-///
 
-#[derive(Default, Debug)]
+/// Sector
+///
+#[derive(Default, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Sector {
     pub offset: u64,
     pub header: D88_SectorHdr,
@@ -16,7 +18,7 @@ impl Sector {
     ///
     /// # Argument
     ///
-    ///   * `reader` &mut BufReader<std::fs::File>
+    ///   * `reader` Reader (Read + Seek)
     ///
     /// # Return
     ///
@@ -24,11 +26,7 @@ impl Sector {
     ///   * Err(())    
     ///
     #[allow(clippy::result_unit_err)]
-    pub fn preset(
-        &mut self,
-        reader: &mut BufReader<std::fs::File>,
-        offset: u64,
-    ) -> Result<u64, ()> {
+    pub fn preset<R: Read + Seek>(&mut self, reader: &mut R, offset: u64) -> Result<u64, ()> {
         if offset == 0 {
             return Err(());
         }
@@ -50,8 +48,9 @@ impl Sector {
             }
             let sector_offset = offset + mem::size_of::<D88_SectorHdr>() as u64;
 
+            // Next sector follows the data ("size_of_data" may differ from 128 << N)
             let ret_sector_size =
-                mem::size_of::<D88_SectorHdr>() + ((128 << d88_sector_header.sector_size) as usize);
+                mem::size_of::<D88_SectorHdr>() + (d88_sector_header.size_of_data as usize);
 
             let mut sector_data: Vec<u8> = vec![0; d88_sector_header.size_of_data.into()];
             if reader.seek(SeekFrom::Start(sector_offset)).is_err() {
@@ -72,7 +71,42 @@ impl Sector {
         }
     }
 
+    /// Write Sector
+    ///
+    /// セクタヘッダとデータを書き込む。
+    ///
+    /// # Argument
+    ///
+    ///   * `writer` Writer
+    ///
+    /// # Return
     ///
+    ///   * Ok(usize)  Written Size (with Sector Header)
+    ///   * Err(())
+    ///
+    #[allow(clippy::result_unit_err)]
+    pub fn write<W: Write>(&self, writer: &mut W) -> Result<usize, ()> {
+        let byte_img;
+        unsafe {
+            byte_img =
+                mem::transmute::<D88_SectorHdr, [u8; mem::size_of::<D88_SectorHdr>()]>(self.header);
+        }
+
+        writer.write_all(&byte_img).map_err(|_| ())?;
+        writer.write_all(&self.data).map_err(|_| ())?;
+
+        Ok(byte_img.len() + self.data.len())
+    }
+
+    /// Status is not Error
+    ///
+    /// 正常(0x00)、削除済みデータ(0x10)なら`true`
+    ///
+    pub fn is_status_ok(&self) -> bool {
+        matches!(self.header.status, 0x00 | 0x10)
+    }
+
+    /// Sector Header (Helper function)
     ///
     pub fn get_track(&self) -> String {
         format!("Track({})", self.header.track)
@@ -87,7 +121,26 @@ impl Sector {
     }
 
     pub fn get_sector_size(&self) -> String {
-        format!("Size({} byte/sec)", 128 << (self.header.sector_size))
+        if let Some(size) = self.get_sector_size_byte() {
+            format!("Size({} byte/sec)", size)
+        } else {
+            format!("Size(?? N={})", self.header.sector_size)
+        }
+    }
+
+    /// Sector Size by N (128 << N)
+    ///
+    /// # Return
+    ///
+    ///   * Some(usize)  N = 0-7
+    ///   * None         Illegal N
+    ///
+    pub fn get_sector_size_byte(&self) -> Option<usize> {
+        if self.header.sector_size <= 7 {
+            Some(128 << self.header.sector_size)
+        } else {
+            None
+        }
     }
 
     pub fn get_num_of_sector(&self) -> String {
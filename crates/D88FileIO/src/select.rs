@@ -0,0 +1,288 @@
+use std::ops::RangeInclusive;
+
+use crate::disk::Disk;
+use crate::sector::Sector;
+
+/// Sector Selection
+///
+/// トラック、サイド、セクタID(R)、論理セクタ番号(LBA)の範囲でセクタを選ぶ。
+/// `None` はすべてを選ぶ(`*`)。
+///
+/// 範囲の書き方
+///
+///   * `5`       5 only
+///   * `0-3`     0, 1, 2, 3
+///   * `10..20`  10 to 19
+///   * `10..=20` 10 to 20
+///   * `*`       all
+///   * `0,2,4-6` list (except at `-p`)
+///
+/// LBA は `Disk::logical_sectors()` の並び(0 Start)。
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Selection {
+    pub track: Option<Vec<RangeInclusive<usize>>>,
+    pub side: Option<Vec<RangeInclusive<usize>>>,
+    pub sector: Option<Vec<RangeInclusive<usize>>>,
+    pub lba: Option<Vec<RangeInclusive<usize>>>,
+}
+
+impl Selection {
+    /// All Sectors are selected
+    ///
+    pub fn is_all(&self) -> bool {
+        self.track.is_none() && self.side.is_none() && self.sector.is_none() && self.lba.is_none()
+    }
+
+    /// Parse "TRACK,SIDE,SECTOR" (e.g. "0-3,0,1-16", "5,*,*")
+    ///
+    /// # Argument
+    ///
+    ///   * `s` Position String
+    ///
+    /// # Return
+    ///
+    ///   * Ok(())
+    ///   * Err(String)  Error Message
+    ///
+    pub fn parse_position(&mut self, s: &str) -> Result<(), String> {
+        let pos: Vec<&str> = s.split(',').collect();
+        if pos.len() != 3 {
+            return Err(format!(
+                "\"{}\"? Position is TRACK,SIDE,SECTOR (e.g. 0,0,1  0-3,0,1-16  5,*,*)",
+                s
+            ));
+        }
+
+        self.set_track(pos[0])?;
+        self.set_side(pos[1])?;
+        self.set_sector(pos[2])
+    }
+
+    /// Set Track Range
+    ///
+    pub fn set_track(&mut self, s: &str) -> Result<(), String> {
+        set_ranges(&mut self.track, s, "Track", 0, 163)
+    }
+
+    /// Set Side Range (0:front, 1:back)
+    ///
+    pub fn set_side(&mut self, s: &str) -> Result<(), String> {
+        set_ranges(&mut self.side, s, "Side", 0, 1)
+    }
+
+    /// Set Sector ID Range (0 to 255, R=0 is used by some protected disks)
+    ///
+    pub fn set_sector(&mut self, s: &str) -> Result<(), String> {
+        set_ranges(&mut self.sector, s, "Sector", 0, 255)
+    }
+
+    /// Set Logical Sector Range (0 Start)
+    ///
+    pub fn set_lba(&mut self, s: &str) -> Result<(), String> {
+        set_ranges(&mut self.lba, s, "LBA", 0, usize::MAX - 1)
+    }
+
+    /// Match a Sector
+    ///
+    /// # Argument
+    ///
+    ///   * `track`  Track Number (Physical Position)
+    ///   * `side`   Side  Number (Physical Position)
+    ///   * `sector` Sector ID (R)
+    ///   * `lba`    Logical Sector Number
+    ///
+    pub fn matches(&self, track: usize, side: usize, sector: usize, lba: usize) -> bool {
+        let in_ranges = |ranges: &Option<Vec<RangeInclusive<usize>>>, n: usize| match ranges {
+            Some(ranges) => ranges.iter().any(|range| range.contains(&n)),
+            None => true,
+        };
+
+        in_ranges(&self.track, track)
+            && in_ranges(&self.side, side)
+            && in_ranges(&self.sector, sector)
+            && in_ranges(&self.lba, lba)
+    }
+
+    /// Selected Sectors in Logical Order
+    ///
+    /// # Argument
+    ///
+    ///   * `disk` Disk
+    ///
+    /// # Return
+    ///
+    ///   * Vec<(Index to `track_tbl`, &Sector)>
+    ///
+    pub fn select<'a>(&self, disk: &'a Disk) -> Vec<(usize, &'a Sector)> {
+        disk.logical_sectors()
+            .into_iter()
+            .enumerate()
+            .filter(|(lba, (idx, sector))| {
+                let (track_no, side_no) = disk.track_position(*idx);
+                self.matches(track_no, side_no, sector.header.sector as usize, *lba)
+            })
+            .map(|(_, entry)| entry)
+            .collect()
+    }
+}
+
+/// Parse and Set Ranges (Helper function)
+///
+fn set_ranges(
+    ranges: &mut Option<Vec<RangeInclusive<usize>>>,
+    s: &str,
+    name: &str,
+    min: usize,
+    max: usize,
+) -> Result<(), String> {
+    if ranges.is_some() {
+        return Err(format!("{} is specified twice", name));
+    }
+    *ranges = parse_ranges(s, name, min, max)?;
+    Ok(())
+}
+
+/// Parse Ranges (Helper function)
+///
+/// # Return
+///
+///   * Ok(None)          `*`
+///   * Ok(Some(ranges))
+///   * Err(String)       Error Message
+///
+fn parse_ranges(
+    s: &str,
+    name: &str,
+    min: usize,
+    max: usize,
+) -> Result<Option<Vec<RangeInclusive<usize>>>, String> {
+    let s = s.trim();
+    if s == "*" {
+        return Ok(None);
+    }
+
+    let mut ranges = Vec::new();
+    for item in s.split(',') {
+        let item = item.trim();
+        let parse = |n: &str| -> Result<usize, String> {
+            n.trim()
+                .parse::<usize>()
+                .map_err(|_| format!("\"{}\"? Not {} Number", item, name))
+        };
+
+        let range = if let Some((start, end)) = item.split_once("..=") {
+            parse(start)?..=parse(end)?
+        } else if let Some((start, end)) = item.split_once("..") {
+            let (start, end) = (parse(start)?, parse(end)?);
+            if end <= start {
+                return Err(format!("\"{}\"? {} range is empty", item, name));
+            }
+            start..=(end - 1)
+        } else if let Some((start, end)) = item.split_once('-') {
+            parse(start)?..=parse(end)?
+        } else {
+            let n = parse(item)?;
+            n..=n
+        };
+
+        if range.start() > range.end() {
+            return Err(format!(
+                "\"{}\"? {} range start is larger than end",
+                item, name
+            ));
+        }
+        if (*range.start() < min) || (*range.end() > max) {
+            return Err(format!("\"{}\"? {} must be {} to {}", item, name, min, max));
+        }
+        ranges.push(range);
+    }
+
+    Ok(Some(ranges))
+}
+
+// ================================================================================
+//
+//  Test Code
+//
+// ================================================================================
+#[cfg(test)]
+mod test {
+    use crate::fileio::D88FileIO;
+    use crate::select::Selection;
+
+    const HUBASIC: &str = "../../sample/HuBASIC_Format_2D.d88";
+
+    #[test]
+    fn test_parse_position() {
+        let mut selection = Selection::default();
+        assert!(selection.is_all());
+        assert!(selection.parse_position("0-3,0,1-16").is_ok());
+        assert_eq!(selection.track, Some(vec![0..=3]));
+        assert_eq!(selection.side, Some(vec![0..=0]));
+        assert_eq!(selection.sector, Some(vec![1..=16]));
+
+        let mut selection = Selection::default();
+        assert!(selection.parse_position("5,*,*").is_ok());
+        assert_eq!(selection.track, Some(vec![5..=5]));
+        assert_eq!((selection.side, selection.sector), (None, None));
+
+        let mut selection = Selection::default();
+        assert!(selection.parse_position("0,0,0").is_ok());
+        assert_eq!(selection.sector, Some(vec![0..=0]));
+
+        let mut selection = Selection::default();
+        assert!(selection.set_track("10..20").is_ok());
+        assert!(selection.set_lba("0..=31,64").is_ok());
+        assert_eq!(selection.track, Some(vec![10..=19]));
+        assert_eq!(selection.lba, Some(vec![0..=31, 64..=64]));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        for pos in [
+            "0,0", "0,0,1,2", "a,0,1", "0,2,1", "0,0,256", "3-1,0,1", "0,0,5..5",
+        ] {
+            let mut selection = Selection::default();
+            assert!(selection.parse_position(pos).is_err(), "{}", pos);
+        }
+
+        let mut selection = Selection::default();
+        assert!(selection.parse_position("0,0,1").is_ok());
+        assert_eq!(
+            selection.set_track("1"),
+            Err("Track is specified twice".to_string())
+        );
+    }
+
+    #[test]
+    fn test_select() {
+        let d88fileio = D88FileIO::open(HUBASIC);
+        let disk = &d88fileio.disk;
+
+        let mut selection = Selection::default();
+        assert_eq!(selection.select(disk).len(), 80 * 16);
+
+        assert!(selection.parse_position("1,1,3-4").is_ok());
+        let selected = selection.select(disk);
+        assert_eq!(selected.len(), 2);
+        assert_eq!(selected[0].0, 3);
+        assert_eq!(selected[0].1.header.sector, 3);
+        assert_eq!(selected[1].1.header.sector, 4);
+
+        let mut selection = Selection::default();
+        assert!(selection.set_lba("16..18").is_ok());
+        let selected = selection.select(disk);
+        assert_eq!(selected.len(), 2);
+        assert_eq!((selected[0].0, selected[0].1.header.sector), (1, 1));
+
+        let mut selection = Selection::default();
+        assert!(selection.set_side("1").is_ok());
+        assert!(selection.set_track("39").is_ok());
+        assert_eq!(selection.select(disk).len(), 16);
+
+        let mut selection = Selection::default();
+        assert!(selection.parse_position("0,0,17").is_ok());
+        assert!(selection.select(disk).is_empty());
+    }
+}
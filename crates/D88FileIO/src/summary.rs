@@ -0,0 +1,86 @@
+use crate::charset::Charset;
+use crate::disk::Disk;
+use crate::filesystem::detect;
+
+/// Disk Summary
+///
+/// 一括処理(複数ファイル)の集計表1行分。
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Summary {
+    /// Disk Name (up to NUL)
+    pub disk_name: String,
+    /// Media Type Name ("2D", "2DD", "2HD", ..)
+    pub disk_type: String,
+    /// Disk Size at D88 Header (byte)
+    pub disk_size: u32,
+    /// Tracks (track-sides) which have sectors
+    pub tracks: usize,
+    pub sectors: usize,
+    /// Sectors with CRC Error, No Address Mark, No Data Mark
+    pub crc_errors: usize,
+    /// Detected Filesystem
+    pub filesystem: Option<String>,
+    /// CRC32 of Sector Data (`Disk::content_digest()`)
+    pub content_crc32: String,
+}
+
+impl Summary {
+    /// Summary of a Disk
+    ///
+    /// # Argument
+    ///
+    ///   * `disk` Disk
+    ///
+    /// # Return
+    ///
+    ///   * Summary
+    ///
+    pub fn new(disk: &Disk) -> Self {
+        let sectors = disk
+            .track_tbl
+            .iter()
+            .flat_map(|track| track.sector_tbl.iter());
+
+        Self {
+            disk_name: disk.get_disk_name_with(Charset::Sjis),
+            disk_type: disk.get_disk_type_name().to_string(),
+            disk_size: disk.header.disk_size,
+            tracks: disk
+                .track_tbl
+                .iter()
+                .filter(|track| !track.sector_tbl.is_empty())
+                .count(),
+            sectors: sectors.clone().count(),
+            crc_errors: sectors.filter(|sector| !sector.is_status_ok()).count(),
+            filesystem: detect(disk).map(|fs| fs.get_name().to_string()),
+            content_crc32: disk.content_digest().get_crc32(),
+        }
+    }
+}
+
+// ================================================================================
+//
+//  Test Code
+//
+// ================================================================================
+#[cfg(test)]
+mod test {
+    use crate::fileio::D88FileIO;
+    use crate::summary::Summary;
+
+    #[test]
+    fn test_summary() {
+        let mut d88fileio = D88FileIO::open("../../sample/HuBASIC_Format_2D.d88");
+        let summary = Summary::new(&d88fileio.disk);
+        assert_eq!(summary.disk_name, "by_github_ORYZAPA");
+        assert_eq!(summary.disk_type, "2D");
+        assert_eq!(summary.disk_size, 348848);
+        assert_eq!((summary.tracks, summary.sectors), (80, 1280));
+        assert_eq!(summary.crc_errors, 0);
+        assert_eq!(summary.filesystem.as_deref(), Some("Hu-BASIC"));
+
+        d88fileio.disk.track_tbl[3].sector_tbl[0].header.status = 0xb0;
+        assert_eq!(Summary::new(&d88fileio.disk).crc_errors, 1);
+    }
+}
@@ -1,10 +1,13 @@
-use std::io::BufReader;
+use std::io::{Read, Seek, Write};
+use std::mem;
 
-use crate::format::MAX_SECTOR;
+use crate::format::{D88_SectorHdr, MAX_SECTOR};
 use crate::sector::Sector;
 
+/// Track
 ///
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Track {
     pub number_of_sector: u16,
     pub sector_tbl: Vec<Sector>,
@@ -15,7 +18,7 @@ impl Track {
     ///
     /// # Argument
     ///
-    ///   * `reader` &mut BufReader<std::fs::File>
+    ///   * `reader` Reader (Read + Seek)
     ///
     /// # Return
     ///
@@ -23,11 +26,7 @@ impl Track {
     ///   * Err(())    
     ///
     #[allow(clippy::result_unit_err)]
-    pub fn preset(
-        &mut self,
-        reader: &mut BufReader<std::fs::File>,
-        offset_: u64,
-    ) -> Result<usize, ()> {
+    pub fn preset<R: Read + Seek>(&mut self, reader: &mut R, offset_: u64) -> Result<usize, ()> {
         if offset_ == 0 {
             return Err(());
         }
@@ -65,6 +64,39 @@ impl Track {
         Ok(track_size)
     }
 
+    /// Write Track
+    ///
+    /// `sector_tbl` の順にセクタを書き込む。
+    ///
+    /// # Argument
+    ///
+    ///   * `writer` Writer
+    ///
+    /// # Return
+    ///
+    ///   * Ok(usize)  Written Size
+    ///   * Err(())
+    ///
+    #[allow(clippy::result_unit_err)]
+    pub fn write<W: Write>(&self, writer: &mut W) -> Result<usize, ()> {
+        let mut track_size = 0;
+        for sector in self.sector_tbl.iter() {
+            track_size += sector.write(writer)?;
+        }
+        Ok(track_size)
+    }
+
+    /// Track Size at D88 File
+    ///
+    /// セクタヘッダを含むサイズ
+    ///
+    pub fn get_track_size(&self) -> usize {
+        self.sector_tbl
+            .iter()
+            .map(|sector| mem::size_of::<D88_SectorHdr>() + sector.data.len())
+            .sum()
+    }
+
     /// Sector Table Sort by Sector Order
     ///
     /// # Argument
@@ -77,8 +109,7 @@ impl Track {
     ///
     pub fn sector_sort(&mut self) {
         // Sort Sector Table
-        self.sector_tbl
-            .sort_by(|a: &Sector, b: &Sector| a.header.sector.cmp(&b.header.sector));
+        self.sector_tbl.sort_by_key(|a: &Sector| a.header.sector);
     }
 
     /// Sector Table Sort by File Offset Order
@@ -93,7 +124,6 @@ impl Track {
     ///
     pub fn file_offset_sort(&mut self) {
         // Sort Sector Table
-        self.sector_tbl
-            .sort_by(|a: &Sector, b: &Sector| a.offset.cmp(&b.offset));
+        self.sector_tbl.sort_by_key(|a: &Sector| a.offset);
     }
 }
@@ -0,0 +1,621 @@
+//! Z80 Disassembler
+//!
+//! 公式命令と非公式命令(IXH/IXL、SLL、DDCB/FDCB の "LD r,RLC (IX+d)" など)を逆アセンブルする。
+//! 数値は `$` を付けた16進で表す。
+//! 意味を持たないプレフィックス(DD 00 の DD など)と未定義の ED xx は `DB` にする。
+//!
+use std::collections::BTreeMap;
+
+const R: [&str; 8] = ["B", "C", "D", "E", "H", "L", "(HL)", "A"];
+const RP: [&str; 4] = ["BC", "DE", "HL", "SP"];
+const RP2: [&str; 4] = ["BC", "DE", "HL", "AF"];
+const CC: [&str; 8] = ["NZ", "Z", "NC", "C", "PO", "PE", "P", "M"];
+const ALU: [&str; 8] = [
+    "ADD A,", "ADC A,", "SUB ", "SBC A,", "AND ", "XOR ", "OR ", "CP ",
+];
+const ROT: [&str; 8] = ["RLC", "RRC", "RL", "RR", "SLA", "SRA", "SLL", "SRL"];
+const IM: [&str; 8] = ["0", "0", "1", "2", "0", "0", "1", "2"];
+const BLOCK: [[&str; 4]; 4] = [
+    ["LDI", "CPI", "INI", "OUTI"],
+    ["LDD", "CPD", "IND", "OUTD"],
+    ["LDIR", "CPIR", "INIR", "OTIR"],
+    ["LDDR", "CPDR", "INDR", "OTDR"],
+];
+
+/// Z80 Instruction
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instruction {
+    /// Address
+    pub addr: u16,
+    /// Machine Code
+    pub bytes: Vec<u8>,
+    /// Mnemonic and Operands ("LD A,$05")
+    pub text: String,
+    /// Destination of JP, JR, DJNZ, CALL
+    pub target: Option<u16>,
+}
+
+impl Instruction {
+    /// Text with Label for the Destination
+    ///
+    /// "JP $C012" -> "JP L_C012"
+    ///
+    pub fn get_text_with_label(&self, labels: &BTreeMap<u16, String>) -> String {
+        match self
+            .target
+            .and_then(|target| labels.get(&target).map(|l| (target, l)))
+        {
+            Some((target, label)) => self.text.replacen(&format!("${:04X}", target), label, 1),
+            None => self.text.clone(),
+        }
+    }
+}
+
+/// Disassemble
+///
+/// # Argument
+///
+///   * `data`   Machine Code
+///   * `origin` Address of `data[0]`
+///
+pub fn disassemble(data: &[u8], origin: u16) -> Vec<Instruction> {
+    let mut instructions = Vec::new();
+    let mut pos = 0;
+    while pos < data.len() {
+        let instruction = disassemble_one(&data[pos..], origin.wrapping_add(pos as u16));
+        pos += instruction.bytes.len();
+        instructions.push(instruction);
+    }
+    instructions
+}
+
+/// Labels of Jump Destinations
+///
+/// 逆アセンブルした範囲内で、命令の先頭に当たる飛び先に "L_XXXX" を付ける。
+///
+/// # Return
+///
+///   * BTreeMap<Address, Label>
+///
+pub fn get_labels(instructions: &[Instruction]) -> BTreeMap<u16, String> {
+    let starts: Vec<u16> = instructions.iter().map(|inst| inst.addr).collect();
+    instructions
+        .iter()
+        .filter_map(|inst| inst.target)
+        .filter(|target| starts.contains(target))
+        .map(|target| (target, format!("L_{:04X}", target)))
+        .collect()
+}
+
+/// Disassemble an Instruction
+///
+/// `data` が命令の途中で終わっていれば、残りを `DB` にする。
+///
+/// # Argument
+///
+///   * `data` Machine Code (1 byte or more)
+///   * `addr` Address of `data[0]`
+///
+pub fn disassemble_one(data: &[u8], addr: u16) -> Instruction {
+    let mut decoder = Decoder::new(data, addr);
+    let text = match data[0] {
+        0xdd | 0xfd => {
+            decoder.index = Some(if data[0] == 0xdd { "IX" } else { "IY" });
+            decoder.pos = 1;
+            let text = decoder.main();
+            if decoder.used_index {
+                text
+            } else {
+                decoder.pos = 1;
+                decoder.truncated = false;
+                decoder.target = None;
+                get_db(&data[..1])
+            }
+        }
+        _ => decoder.main(),
+    };
+
+    let len = decoder.pos.min(data.len());
+    if decoder.truncated {
+        return Instruction {
+            addr,
+            bytes: data[..len].to_vec(),
+            text: get_db(&data[..len]),
+            target: None,
+        };
+    }
+    Instruction {
+        addr,
+        bytes: data[..len].to_vec(),
+        text,
+        target: decoder.target,
+    }
+}
+
+/// "DB $xx,$xx" (Helper function)
+///
+fn get_db(bytes: &[u8]) -> String {
+    let bytes: Vec<String> = bytes.iter().map(|byte| format!("${:02X}", byte)).collect();
+    format!("DB {}", bytes.join(","))
+}
+
+/// Instruction Decoder (Helper struct)
+///
+/// `index` が Some("IX"), Some("IY") なら HL, H, L, (HL) を IX, IXH, IXL, (IX+d) に置き換える。
+///
+struct Decoder<'a> {
+    data: &'a [u8],
+    addr: u16,
+    pos: usize,
+    index: Option<&'static str>,
+    disp: Option<i8>,
+    used_index: bool,
+    truncated: bool,
+    target: Option<u16>,
+}
+
+impl<'a> Decoder<'a> {
+    fn new(data: &'a [u8], addr: u16) -> Self {
+        Self {
+            data,
+            addr,
+            pos: 0,
+            index: None,
+            disp: None,
+            used_index: false,
+            truncated: false,
+            target: None,
+        }
+    }
+
+    fn fetch(&mut self) -> u8 {
+        let byte = self.data.get(self.pos).copied().unwrap_or_else(|| {
+            self.truncated = true;
+            0
+        });
+        self.pos += 1;
+        byte
+    }
+
+    fn n(&mut self) -> String {
+        format!("${:02X}", self.fetch())
+    }
+
+    fn nn(&mut self) -> u16 {
+        u16::from_le_bytes([self.fetch(), self.fetch()])
+    }
+
+    fn nn_str(&mut self) -> String {
+        format!("${:04X}", self.nn())
+    }
+
+    /// Absolute Destination
+    fn jump(&mut self) -> String {
+        let target = self.nn();
+        self.target = Some(target);
+        format!("${:04X}", target)
+    }
+
+    /// Relative Destination (JR, DJNZ)
+    fn rel(&mut self) -> String {
+        let disp = self.fetch() as i8;
+        let target = self
+            .addr
+            .wrapping_add(self.pos as u16)
+            .wrapping_add(disp as u16);
+        self.target = Some(target);
+        format!("${:04X}", target)
+    }
+
+    /// HL, IX, IY
+    fn hl(&mut self) -> String {
+        match self.index {
+            Some(index) => {
+                self.used_index = true;
+                index.to_string()
+            }
+            None => "HL".to_string(),
+        }
+    }
+
+    /// (HL), (IX+d), (IY+d)
+    fn mem_hl(&mut self) -> String {
+        match self.index {
+            Some(index) => {
+                self.used_index = true;
+                let disp = match self.disp {
+                    Some(disp) => disp,
+                    None => {
+                        let disp = self.fetch() as i8;
+                        self.disp = Some(disp);
+                        disp
+                    }
+                };
+                let sign = if disp < 0 { '-' } else { '+' };
+                format!("({}{}${:02X})", index, sign, disp.unsigned_abs())
+            }
+            None => "(HL)".to_string(),
+        }
+    }
+
+    fn r(&mut self, idx: u8) -> String {
+        match (idx, self.index) {
+            (4 | 5, Some(index)) => {
+                self.used_index = true;
+                format!("{}{}", index, if idx == 4 { "H" } else { "L" })
+            }
+            (6, _) => self.mem_hl(),
+            _ => R[idx as usize].to_string(),
+        }
+    }
+
+    fn rp(&mut self, p: u8) -> String {
+        if p == 2 {
+            self.hl()
+        } else {
+            RP[p as usize].to_string()
+        }
+    }
+
+    fn rp2(&mut self, p: u8) -> String {
+        if p == 2 {
+            self.hl()
+        } else {
+            RP2[p as usize].to_string()
+        }
+    }
+
+    /// Unprefixed (or DD, FD) Opcode
+    fn main(&mut self) -> String {
+        let op = self.fetch();
+        let (x, y, z) = (op >> 6, (op >> 3) & 7, op & 7);
+        let (p, q) = (y >> 1, y & 1);
+
+        match x {
+            0 => match z {
+                0 => match y {
+                    0 => "NOP".to_string(),
+                    1 => "EX AF,AF'".to_string(),
+                    2 => format!("DJNZ {}", self.rel()),
+                    3 => format!("JR {}", self.rel()),
+                    _ => format!("JR {},{}", CC[y as usize - 4], self.rel()),
+                },
+                1 if q == 0 => {
+                    let rp = self.rp(p);
+                    format!("LD {},{}", rp, self.nn_str())
+                }
+                1 => {
+                    let hl = self.hl();
+                    format!("ADD {},{}", hl, self.rp(p))
+                }
+                2 => match (q, p) {
+                    (0, 0) => "LD (BC),A".to_string(),
+                    (0, 1) => "LD (DE),A".to_string(),
+                    (0, 2) => {
+                        let nn = self.nn_str();
+                        format!("LD ({}),{}", nn, self.hl())
+                    }
+                    (0, _) => format!("LD ({}),A", self.nn_str()),
+                    (_, 0) => "LD A,(BC)".to_string(),
+                    (_, 1) => "LD A,(DE)".to_string(),
+                    (_, 2) => {
+                        let hl = self.hl();
+                        format!("LD {},({})", hl, self.nn_str())
+                    }
+                    _ => format!("LD A,({})", self.nn_str()),
+                },
+                3 => format!("{} {}", ["INC", "DEC"][q as usize], self.rp(p)),
+                4 => format!("INC {}", self.r(y)),
+                5 => format!("DEC {}", self.r(y)),
+                6 => {
+                    let r = self.r(y);
+                    format!("LD {},{}", r, self.n())
+                }
+                _ => ["RLCA", "RRCA", "RLA", "RRA", "DAA", "CPL", "SCF", "CCF"][y as usize]
+                    .to_string(),
+            },
+            1 => {
+                if y == 6 && z == 6 {
+                    "HALT".to_string()
+                } else if self.index.is_some() && (y == 6 || z == 6) {
+                    // LD H,(IX+d) は H のまま
+                    let mem = self.mem_hl();
+                    if y == 6 {
+                        format!("LD {},{}", mem, R[z as usize])
+                    } else {
+                        format!("LD {},{}", R[y as usize], mem)
+                    }
+                } else {
+                    let dst = self.r(y);
+                    format!("LD {},{}", dst, self.r(z))
+                }
+            }
+            2 => format!("{}{}", ALU[y as usize], self.r(z)),
+            _ => match z {
+                0 => format!("RET {}", CC[y as usize]),
+                1 if q == 0 => format!("POP {}", self.rp2(p)),
+                1 => match p {
+                    0 => "RET".to_string(),
+                    1 => "EXX".to_string(),
+                    2 => format!("JP ({})", self.hl()),
+                    _ => format!("LD SP,{}", self.hl()),
+                },
+                2 => format!("JP {},{}", CC[y as usize], self.jump()),
+                3 => match y {
+                    0 => format!("JP {}", self.jump()),
+                    1 => self.cb(),
+                    2 => format!("OUT ({}),A", self.n()),
+                    3 => format!("IN A,({})", self.n()),
+                    4 => format!("EX (SP),{}", self.hl()),
+                    5 => "EX DE,HL".to_string(),
+                    6 => "DI".to_string(),
+                    _ => "EI".to_string(),
+                },
+                4 => format!("CALL {},{}", CC[y as usize], self.jump()),
+                5 if q == 0 => format!("PUSH {}", self.rp2(p)),
+                5 => match p {
+                    0 => format!("CALL {}", self.jump()),
+                    // DD, ED, FD の前の DD, FD は無効
+                    _ if self.index.is_some() => String::new(),
+                    2 => self.ed(),
+                    _ => String::new(),
+                },
+                6 => format!("{}{}", ALU[y as usize], self.n()),
+                _ => format!("RST ${:02X}", y * 8),
+            },
+        }
+    }
+
+    /// CB Prefix (DDCB d op, FDCB d op)
+    fn cb(&mut self) -> String {
+        if self.index.is_some() {
+            self.disp = Some(self.fetch() as i8);
+        }
+        let op = self.fetch();
+        let (x, y, z) = (op >> 6, (op >> 3) & 7, op & 7);
+
+        let (mnemonic, mem) = if self.index.is_some() {
+            (z != 6, self.mem_hl())
+        } else {
+            (false, self.r(z))
+        };
+        let text = match x {
+            0 => format!("{} {}", ROT[y as usize], mem),
+            1 => return format!("BIT {},{}", y, mem),
+            2 => format!("RES {},{}", y, mem),
+            _ => format!("SET {},{}", y, mem),
+        };
+        // 非公式: 結果をレジスタにも書く
+        if mnemonic {
+            format!("LD {},{}", R[z as usize], text)
+        } else {
+            text
+        }
+    }
+
+    /// ED Prefix
+    fn ed(&mut self) -> String {
+        let op = self.fetch();
+        let (x, y, z) = (op >> 6, (op >> 3) & 7, op & 7);
+        let (p, q) = (y >> 1, y & 1);
+
+        match (x, z) {
+            (1, 0) if y == 6 => "IN F,(C)".to_string(),
+            (1, 0) => format!("IN {},(C)", R[y as usize]),
+            (1, 1) if y == 6 => "OUT (C),0".to_string(),
+            (1, 1) => format!("OUT (C),{}", R[y as usize]),
+            (1, 2) => format!("{} HL,{}", ["SBC", "ADC"][q as usize], RP[p as usize]),
+            (1, 3) if q == 0 => format!("LD ({}),{}", self.nn_str(), RP[p as usize]),
+            (1, 3) => format!("LD {},({})", RP[p as usize], self.nn_str()),
+            (1, 4) => "NEG".to_string(),
+            (1, 5) if y == 1 => "RETI".to_string(),
+            (1, 5) => "RETN".to_string(),
+            (1, 6) => format!("IM {}", IM[y as usize]),
+            (1, 7) if y < 6 => {
+                ["LD I,A", "LD R,A", "LD A,I", "LD A,R", "RRD", "RLD"][y as usize].to_string()
+            }
+            (2, 0..=3) if y >= 4 => BLOCK[y as usize - 4][z as usize].to_string(),
+            _ => get_db(&[0xed, op]),
+        }
+    }
+}
+
+// ================================================================================
+//
+//  Test Code
+//
+// ================================================================================
+#[cfg(test)]
+mod test {
+    use crate::z80::{disassemble, disassemble_one, get_labels};
+
+    fn text(data: &[u8]) -> String {
+        disassemble_one(data, 0xc000).text
+    }
+
+    /// Each Case is a whole Instruction (Helper function)
+    fn check(cases: &[(&[u8], &str)]) {
+        for (data, expected) in cases {
+            let instruction = disassemble_one(data, 0xc000);
+            assert_eq!(instruction.text, *expected, "{:02X?}", data);
+            assert_eq!(instruction.bytes, *data, "{:02X?}", data);
+        }
+    }
+
+    #[test]
+    fn test_disassemble() {
+        assert_eq!(text(&[0x31, 0x00, 0xc0]), "LD SP,$C000");
+        assert_eq!(text(&[0xdd, 0x36, 0xfe, 0x12]), "LD (IX-$02),$12");
+        assert_eq!(text(&[0xdd, 0x66, 0x03]), "LD H,(IX+$03)");
+        assert_eq!(text(&[0xfd, 0x7c]), "LD A,IYH");
+        assert_eq!(text(&[0xdd, 0xcb, 0x05, 0x06]), "RLC (IX+$05)");
+        assert_eq!(text(&[0xfd, 0xcb, 0x01, 0xc0]), "LD B,SET 0,(IY+$01)");
+        assert_eq!(text(&[0xdd, 0xcb, 0x01, 0x47]), "BIT 0,(IX+$01)");
+        assert_eq!(text(&[0xcb, 0x30]), "SLL B");
+        assert_eq!(text(&[0xed, 0x70]), "IN F,(C)");
+        assert_eq!(text(&[0xed, 0x71]), "OUT (C),0");
+        assert_eq!(text(&[0xed, 0x4e]), "IM 0");
+        assert_eq!(text(&[0xed, 0xb0]), "LDIR");
+        assert_eq!(text(&[0xed, 0x00]), "DB $ED,$00");
+        assert_eq!(text(&[0xdd, 0x00]), "DB $DD");
+        assert_eq!(text(&[0xdd, 0xeb]), "DB $DD");
+        assert_eq!(text(&[0xc3, 0x34]), "DB $C3,$34");
+
+        // C000 DI / C001 JR C000 / C003 CALL C001 / C006 RST 38
+        let instructions = disassemble(&[0xf3, 0x18, 0xfd, 0xcd, 0x01, 0xc0, 0xff], 0xc000);
+        let texts: Vec<&str> = instructions.iter().map(|inst| inst.text.as_str()).collect();
+        assert_eq!(texts, ["DI", "JR $C000", "CALL $C001", "RST $38"]);
+        let labels = get_labels(&instructions);
+        assert_eq!(labels.len(), 2);
+        assert_eq!(instructions[2].get_text_with_label(&labels), "CALL L_C001");
+    }
+
+    #[test]
+    fn test_disassemble_cb() {
+        check(&[
+            (&[0xcb, 0x00], "RLC B"),
+            (&[0xcb, 0x0e], "RRC (HL)"),
+            (&[0xcb, 0x16], "RL (HL)"),
+            (&[0xcb, 0x1f], "RR A"),
+            (&[0xcb, 0x21], "SLA C"),
+            (&[0xcb, 0x2a], "SRA D"),
+            (&[0xcb, 0x37], "SLL A"),
+            (&[0xcb, 0x3b], "SRL E"),
+            (&[0xcb, 0x7e], "BIT 7,(HL)"),
+            (&[0xcb, 0x87], "RES 0,A"),
+            (&[0xcb, 0xfc], "SET 7,H"),
+        ]);
+    }
+
+    #[test]
+    fn test_disassemble_dd_fd() {
+        check(&[
+            (&[0xdd, 0x21, 0x34, 0x12], "LD IX,$1234"),
+            (&[0xdd, 0x09], "ADD IX,BC"),
+            (&[0xdd, 0x29], "ADD IX,IX"),
+            (&[0xdd, 0x22, 0x00, 0xc0], "LD ($C000),IX"),
+            (&[0xdd, 0x2a, 0x00, 0xc0], "LD IX,($C000)"),
+            (&[0xdd, 0x23], "INC IX"),
+            (&[0xdd, 0x34, 0x10], "INC (IX+$10)"),
+            (&[0xdd, 0x36, 0x05, 0xaa], "LD (IX+$05),$AA"),
+            (&[0xdd, 0x7e, 0x80], "LD A,(IX-$80)"),
+            (&[0xdd, 0x75, 0x7f], "LD (IX+$7F),L"),
+            (&[0xdd, 0xe1], "POP IX"),
+            (&[0xdd, 0xe3], "EX (SP),IX"),
+            (&[0xdd, 0xe9], "JP (IX)"),
+            (&[0xdd, 0xf9], "LD SP,IX"),
+            (&[0xfd, 0xe5], "PUSH IY"),
+            (&[0xfd, 0x46, 0x00], "LD B,(IY+$00)"),
+            (&[0xfd, 0x96, 0xff], "SUB (IY-$01)"),
+            // 非公式: IXH, IXL, IYH, IYL
+            (&[0xdd, 0x26, 0x05], "LD IXH,$05"),
+            (&[0xdd, 0x2c], "INC IXL"),
+            (&[0xdd, 0x65], "LD IXH,IXL"),
+            (&[0xdd, 0x84], "ADD A,IXH"),
+            (&[0xfd, 0x6f], "LD IYL,A"),
+            (&[0xfd, 0xbd], "CP IYL"),
+            // 意味を持たないプレフィックス
+            (&[0xfd], "DB $FD"),
+        ]);
+        for (data, expected) in [
+            (&[0xdd, 0x3e, 0x01][..], ["DB $DD", "LD A,$01"]),
+            (&[0xdd, 0xfd, 0xe9][..], ["DB $DD", "JP (IY)"]),
+            (&[0xfd, 0xed, 0x44][..], ["DB $FD", "NEG"]),
+        ] {
+            let instructions = disassemble(data, 0xc000);
+            let texts: Vec<&str> = instructions.iter().map(|inst| inst.text.as_str()).collect();
+            assert_eq!(texts, expected);
+        }
+    }
+
+    #[test]
+    fn test_disassemble_ed() {
+        check(&[
+            (&[0xed, 0x40], "IN B,(C)"),
+            (&[0xed, 0x70], "IN F,(C)"),
+            (&[0xed, 0x79], "OUT (C),A"),
+            (&[0xed, 0x71], "OUT (C),0"),
+            (&[0xed, 0x42], "SBC HL,BC"),
+            (&[0xed, 0x7a], "ADC HL,SP"),
+            (&[0xed, 0x43, 0x00, 0xc0], "LD ($C000),BC"),
+            (&[0xed, 0x7b, 0x00, 0xc0], "LD SP,($C000)"),
+            (&[0xed, 0x44], "NEG"),
+            (&[0xed, 0x4c], "NEG"),
+            (&[0xed, 0x4d], "RETI"),
+            (&[0xed, 0x45], "RETN"),
+            (&[0xed, 0x46], "IM 0"),
+            (&[0xed, 0x56], "IM 1"),
+            (&[0xed, 0x5e], "IM 2"),
+            (&[0xed, 0x76], "IM 1"),
+            (&[0xed, 0x47], "LD I,A"),
+            (&[0xed, 0x5f], "LD A,R"),
+            (&[0xed, 0x67], "RRD"),
+            (&[0xed, 0x6f], "RLD"),
+            (&[0xed, 0xa3], "OUTI"),
+            (&[0xed, 0xb1], "CPIR"),
+            (&[0xed, 0xb8], "LDDR"),
+            (&[0xed, 0xbb], "OTDR"),
+            (&[0xed, 0x77], "DB $ED,$77"),
+            (&[0xed, 0xa4], "DB $ED,$A4"),
+            (&[0xed, 0xff], "DB $ED,$FF"),
+        ]);
+    }
+
+    #[test]
+    fn test_disassemble_ddcb_fdcb() {
+        check(&[
+            // DD CB d op: 変位が命令コードより前
+            (&[0xdd, 0xcb, 0x01, 0x06], "RLC (IX+$01)"),
+            (&[0xdd, 0xcb, 0x06, 0x01], "LD C,RLC (IX+$06)"),
+            (&[0xdd, 0xcb, 0xfe, 0x46], "BIT 0,(IX-$02)"),
+            (&[0xdd, 0xcb, 0x7f, 0x4f], "BIT 1,(IX+$7F)"),
+            (&[0xdd, 0xcb, 0x10, 0x36], "SLL (IX+$10)"),
+            (&[0xdd, 0xcb, 0x10, 0x30], "LD B,SLL (IX+$10)"),
+            (&[0xdd, 0xcb, 0x00, 0x87], "LD A,RES 0,(IX+$00)"),
+            (&[0xfd, 0xcb, 0x80, 0xfe], "SET 7,(IY-$80)"),
+            (&[0xfd, 0xcb, 0x02, 0xbe], "RES 7,(IY+$02)"),
+            (&[0xfd, 0xcb, 0x03, 0x1d], "LD L,RR (IY+$03)"),
+        ]);
+    }
+
+    #[test]
+    fn test_disassemble_relative_wrap() {
+        for (data, addr, expected, target) in [
+            (&[0x18, 0x10], 0xfffe, "JR $0010", 0x0010),
+            (&[0x20, 0x7f], 0xfff0, "JR NZ,$0071", 0x0071),
+            (&[0x10, 0xfe], 0x0000, "DJNZ $0000", 0x0000),
+            (&[0x38, 0x80], 0x0010, "JR C,$FF92", 0xff92),
+        ] {
+            let instruction = disassemble_one(data, addr);
+            assert_eq!(instruction.text, expected);
+            assert_eq!(instruction.target, Some(target));
+        }
+
+        let instructions = disassemble(&[0x00, 0x00, 0x00], 0xffff);
+        let addrs: Vec<u16> = instructions.iter().map(|inst| inst.addr).collect();
+        assert_eq!(addrs, [0xffff, 0x0000, 0x0001]);
+    }
+
+    #[test]
+    fn test_disassemble_truncated() {
+        check(&[
+            (&[0x3e], "DB $3E"),
+            (&[0x18], "DB $18"),
+            (&[0xcd, 0x00], "DB $CD,$00"),
+            (&[0xcb], "DB $CB"),
+            (&[0xdd], "DB $DD"),
+            (&[0xdd, 0x21, 0x34], "DB $DD,$21,$34"),
+            (&[0xdd, 0x7e], "DB $DD,$7E"),
+            (&[0xdd, 0xcb], "DB $DD,$CB"),
+            (&[0xdd, 0xcb, 0x05], "DB $DD,$CB,$05"),
+            (&[0xed], "DB $ED"),
+            (&[0xed, 0x43, 0x00], "DB $ED,$43,$00"),
+        ]);
+        assert_eq!(disassemble_one(&[0x18], 0xc000).target, None);
+
+        let instructions = disassemble(&[0x00, 0xc3, 0x00], 0xc000);
+        let texts: Vec<&str> = instructions.iter().map(|inst| inst.text.as_str()).collect();
+        assert_eq!(texts, ["NOP", "DB $C3,$00"]);
+    }
+}
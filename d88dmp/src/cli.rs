@@ -1,6 +1,10 @@
 // Commad Line Analysis
 use clap::{Arg, ArgMatches};
 
+use D88FileIO::charset::Charset;
+use D88FileIO::report::layout::{HexLayout, OffsetBase};
+use D88FileIO::select::Selection;
+
 use crate::utility::ERROR;
 use crate::version::*;
 
@@ -19,20 +23,578 @@ pub fn get_cmdline_param() -> ArgMatches {
         .version(TOOL_VERSION)
         .author(AUTHOR)
         .about("D88 Disk Image Dump.")
+        .args_conflicts_with_subcommands(true)
+        .subcommand_negates_reqs(true)
+        .subcommand(
+            // "info"
+            clap::Command::new("info")
+                .about("Print disk header, track offset table and filesystem")
+                .arg(get_d88_arg())
+                .arg(
+                    // "--no-color"
+                    Arg::new("no-color").help("No color").long("no-color"),
+                )
+                .arg(get_charset_arg())
+                .arg(get_format_arg()),
+        )
+        .subcommand(
+            // "dump"
+            clap::Command::new("dump")
+                .about("Dump sector headers and data (default command)")
+                .arg(get_d88_arg())
+                .args(get_selection_args())
+                .arg(
+                    // "-v, --verbose"
+                    Arg::new("verbose")
+                        .help("Print info and sector summary before the data")
+                        .short('v')
+                        .long("verbose"),
+                )
+                .arg(get_sort_arg())
+                .arg(
+                    // "--no-color"
+                    Arg::new("no-color").help("No color").long("no-color"),
+                )
+                .arg(get_charset_arg())
+                .args(get_layout_args())
+                .args(get_sector_layout_args())
+                .arg(get_format_arg())
+                .arg(get_data_arg()),
+        )
+        .subcommand(
+            // "hexdump"
+            clap::Command::new("hexdump")
+                .about("Hex dump of the whole file")
+                .arg(get_d88_arg())
+                .arg(
+                    // "--no-color"
+                    Arg::new("no-color").help("No color").long("no-color"),
+                )
+                .arg(get_charset_arg())
+                .args(get_layout_args()),
+        )
+        .subcommand(
+            // "sectors"
+            clap::Command::new("sectors")
+                .about("Print sector header summary")
+                .arg(get_d88_arg())
+                .args(get_selection_args())
+                .arg(get_sort_arg())
+                .arg(
+                    // "--no-color"
+                    Arg::new("no-color").help("No color").long("no-color"),
+                )
+                .arg(get_format_arg()),
+        )
+        .subcommand(
+            // "ls"
+            clap::Command::new("ls")
+                .about("List files (Hu-BASIC, N88-BASIC, CP/M)")
+                .arg(get_d88_arg())
+                .arg(get_fs_arg())
+                .arg(get_format_arg()),
+        )
+        .subcommand(
+            // "get"
+            clap::Command::new("get")
+                .about("Copy a file out of the disk image")
+                .arg(get_d88_arg())
+                .arg(
+                    Arg::new("NAME")
+                        .help("File name on the disk")
+                        .required(true)
+                        .index(2),
+                )
+                .arg(
+                    // "-o, --output"
+                    Arg::new("OUTPUT")
+                        .help("Output file (default: NAME)")
+                        .takes_value(true)
+                        .short('o')
+                        .long("output"),
+                )
+                .arg(get_fs_arg()),
+        )
+        .subcommand(
+            // "list"
+            clap::Command::new("list")
+                .about("Print a BASIC program (N88-BASIC, Hu-BASIC, MSX-BASIC)")
+                .arg(get_d88_arg())
+                .arg(
+                    Arg::new("NAME")
+                        .help("File name on the disk")
+                        .required(true)
+                        .index(2),
+                )
+                .arg(get_dialect_arg())
+                .arg(get_charset_arg().default_value("sjis"))
+                .arg(get_fs_arg()),
+        )
+        .subcommand(
+            // "put"
+            clap::Command::new("put")
+                .about("Copy a file into the disk image")
+                .arg(get_d88_arg())
+                .arg(
+                    Arg::new("FILE")
+                        .help("File to copy")
+                        .required(true)
+                        .index(2),
+                )
+                .arg(
+                    // "--name"
+                    Arg::new("NAME")
+                        .help("File name on the disk (default: FILE name)")
+                        .takes_value(true)
+                        .long("name"),
+                )
+                .arg(
+                    // "--type"
+                    Arg::new("TYPE")
+                        .help("File type")
+                        .takes_value(true)
+                        .possible_values(["bin", "bas", "asc"])
+                        .default_value("bin")
+                        .long("type"),
+                )
+                .arg(
+                    // "--tokenize"
+                    Arg::new("tokenize")
+                        .help("Tokenize a BASIC text and put it as \"bas\"")
+                        .long("tokenize"),
+                )
+                .arg(get_dialect_arg())
+                .arg(
+                    // "-o, --output"
+                    Arg::new("OUTPUT")
+                        .help("Output D88 file (default: overwrite *.D88)")
+                        .takes_value(true)
+                        .short('o')
+                        .long("output"),
+                )
+                .arg(get_fs_arg()),
+        )
+        .subcommand(
+            // "convert"
+            clap::Command::new("convert")
+                .about("Convert between D88/D77 and raw images (*.2d, *.2dd, *.2hd, *.img)")
+                .arg(
+                    Arg::new("INPUT")
+                        .help("Input image")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    Arg::new("OUTPUT")
+                        .help("Output image")
+                        .required(true)
+                        .index(2),
+                )
+                .arg(get_disk_type_arg().help("Disk type of raw input (default: by size)")),
+        )
+        .subcommand(
+            // "check"
+            clap::Command::new("check")
+                .about("Check integrity of disk image")
+                .arg(get_d88_arg())
+                .arg(get_format_arg()),
+        )
+        .subcommand(
+            // "create"
+            clap::Command::new("create")
+                .about("Create a blank disk image")
+                .arg(
+                    Arg::new("OUTPUT")
+                        .help("Output D88 file")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    get_disk_type_arg()
+                        .help("Disk type")
+                        .default_value("2D"),
+                )
+                .arg(get_fs_arg().help("Format with filesystem"))
+                .arg(
+                    // "--name"
+                    Arg::new("NAME")
+                        .help("Disk name (up to 16 characters)")
+                        .takes_value(true)
+                        .long("name"),
+                ),
+        )
+        .subcommand(
+            // "hash"
+            clap::Command::new("hash")
+                .about("Print CRC32/MD5/SHA-1 of file, sector data, tracks and sectors")
+                .arg(
+                    Arg::new("*.D88")
+                        .help("D88 Disk Image")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    // "-t, --track"
+                    Arg::new("track")
+                        .help("Hash of each track")
+                        .short('t')
+                        .long("track"),
+                )
+                .arg(
+                    // "-s, --sector"
+                    Arg::new("sector")
+                        .help("Hash of each sector")
+                        .short('s')
+                        .long("sector"),
+                )
+                .arg(get_format_arg()),
+        )
+        .subcommand(
+            // "identify"
+            clap::Command::new("identify")
+                .about("Identify disk images with DAT catalog (Logiqx XML)")
+                .arg(
+                    Arg::new("*.D88")
+                        .help("D88 Disk Images")
+                        .required(true)
+                        .multiple_values(true)
+                        .index(1),
+                )
+                .arg(
+                    // "-d, --dat"
+                    Arg::new("DAT")
+                        .help("DAT catalog file")
+                        .takes_value(true)
+                        .required(true)
+                        .short('d')
+                        .long("dat"),
+                )
+                .arg(
+                    // "--by"
+                    Arg::new("HASH")
+                        .help("Hash to match\n  file:    whole file\n  content: sector data only\n  any:     file or content")
+                        .takes_value(true)
+                        .possible_values(["file", "content", "any"])
+                        .default_value("any")
+                        .long("by"),
+                )
+                .arg(get_format_arg()),
+        )
+        .subcommand(
+            // "scan"
+            clap::Command::new("scan")
+                .about("Summarize many disk images in one table (in parallel)")
+                .arg(
+                    Arg::new("PATH")
+                        .help("D88 disk images, directories or glob patterns")
+                        .required(true)
+                        .multiple_values(true)
+                        .index(1),
+                )
+                .arg(
+                    // "-r, --recursive"
+                    Arg::new("recursive")
+                        .help("Scan directories recursively")
+                        .short('r')
+                        .long("recursive"),
+                )
+                .arg(
+                    // "-j, --jobs"
+                    Arg::new("JOBS")
+                        .help("Number of parallel jobs (default: number of CPUs)")
+                        .takes_value(true)
+                        .validator(|s| s.parse::<usize>().map(|_| ()))
+                        .short('j')
+                        .long("jobs"),
+                )
+                .arg(get_format_arg()),
+        )
+        .subcommand(
+            // "diff"
+            clap::Command::new("diff")
+                .about("Compare two disk images by (track, side, sector ID)")
+                .arg(
+                    Arg::new("A.D88")
+                        .help("D88 Disk Image A")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    Arg::new("B.D88")
+                        .help("D88 Disk Image B")
+                        .required(true)
+                        .index(2),
+                )
+                .arg(
+                    // "--no-color"
+                    Arg::new("no-color").help("No color").long("no-color"),
+                )
+                .arg(
+                    // "--summary"
+                    Arg::new("summary").help("Summary only").long("summary"),
+                )
+                .arg(get_format_arg()),
+        )
+        .subcommand(
+            // "merge"
+            clap::Command::new("merge")
+                .about("Reconstruct a disk image from multiple dumps (best copy of each sector)")
+                .arg(
+                    Arg::new("*.D88")
+                        .help("D88 Disk Images (dumps of the same disk)")
+                        .required(true)
+                        .multiple_values(true)
+                        .index(1),
+                )
+                .arg(
+                    // "-o, --output"
+                    Arg::new("OUTPUT")
+                        .help("Output D88 file")
+                        .takes_value(true)
+                        .short('o')
+                        .long("output"),
+                )
+                .arg(
+                    // "-v, --verbose"
+                    Arg::new("verbose")
+                        .help("Report all sectors")
+                        .short('v')
+                        .long("verbose"),
+                )
+                .arg(get_format_arg()),
+        )
+        .subcommand(
+            // "protect"
+            clap::Command::new("protect")
+                .about("Analyze copy protection (duplicate IDs, CRC errors, odd N, etc.)")
+                .arg(
+                    Arg::new("*.D88")
+                        .help("D88 Disk Image")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    // "-a, --all"
+                    Arg::new("all")
+                        .help("Report normal tracks too")
+                        .short('a')
+                        .long("all"),
+                )
+                .arg(
+                    // "--no-color"
+                    Arg::new("no-color").help("No color").long("no-color"),
+                )
+                .arg(get_format_arg()),
+        )
+        .subcommand(
+            // "map"
+            clap::Command::new("map")
+                .about("Print track x sector surface map (OK, CRC error, deleted, missing, empty)")
+                .arg(
+                    Arg::new("*.D88")
+                        .help("D88 Disk Image")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    // "--svg"
+                    Arg::new("SVG")
+                        .help("Export as SVG")
+                        .takes_value(true)
+                        .long("svg"),
+                )
+                .arg(
+                    // "--png"
+                    Arg::new("PNG")
+                        .help("Export as PNG")
+                        .takes_value(true)
+                        .long("png"),
+                )
+                .arg(
+                    // "--no-color"
+                    Arg::new("no-color").help("No color").long("no-color"),
+                )
+                .arg(get_format_arg()),
+        )
+        .subcommand(
+            // "extract"
+            clap::Command::new("extract")
+                .about("Write raw sector data in logical order to a file or stdout")
+                .arg(
+                    Arg::new("*.D88")
+                        .help("D88 Disk Image")
+                        .required(true)
+                        .index(1),
+                )
+                .args(get_selection_args())
+                .arg(
+                    // "-o, --output"
+                    Arg::new("OUTPUT")
+                        .help("Output file (default: stdout)")
+                        .takes_value(true)
+                        .short('o')
+                        .long("output"),
+                )
+                .arg(
+                    // "--header"
+                    Arg::new("header")
+                        .help("Include D88 sector headers (16 byte each)")
+                        .long("header"),
+                )
+                .arg(
+                    // "--pad"
+                    Arg::new("PAD")
+                        .help("Fill missing sectors with a byte (hex, e.g. e5)")
+                        .takes_value(true)
+                        .long("pad"),
+                ),
+        )
+        .subcommand(
+            // "disasm"
+            clap::Command::new("disasm")
+                .about("Disassemble Z80 code of sectors (default: boot sector) or a file")
+                .arg(get_d88_arg())
+                .arg(
+                    Arg::new("NAME")
+                        .help("File name on the disk (default: sectors)")
+                        .index(2),
+                )
+                .args(get_selection_args())
+                .arg(
+                    // "--org"
+                    Arg::new("ORIGIN")
+                        .help("Origin address, hex (default: load address of the file, or 0000)")
+                        .takes_value(true)
+                        .long("org"),
+                )
+                .arg(get_charset_arg())
+                .arg(get_fs_arg()),
+        )
+        .subcommand(
+            // "search"
+            clap::Command::new("search")
+                .about("Search sector data for a hex pattern or string (across sector boundaries)")
+                .arg(
+                    Arg::new("*.D88")
+                        .help("D88 Disk Image")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    // "-x, --hex"
+                    Arg::new("HEX")
+                        .help("Hex pattern, ?? is a wildcard (e.g. \"c3 00 ?? 4d\")")
+                        .takes_value(true)
+                        .short('x')
+                        .long("hex"),
+                )
+                .arg(
+                    // "-a, --ascii"
+                    Arg::new("ASCII")
+                        .help("ASCII string")
+                        .takes_value(true)
+                        .short('a')
+                        .long("ascii"),
+                )
+                .arg(
+                    // "--sjis"
+                    Arg::new("SJIS")
+                        .help("Shift-JIS string")
+                        .takes_value(true)
+                        .long("sjis"),
+                )
+                .arg(
+                    // "--jis"
+                    Arg::new("JIS")
+                        .help("JIS X 0201 string (ASCII and half-width katakana)")
+                        .takes_value(true)
+                        .long("jis"),
+                )
+                .group(
+                    clap::ArgGroup::new("PATTERN")
+                        .args(&["HEX", "ASCII", "SJIS", "JIS"])
+                        .required(true),
+                )
+                .args(get_selection_args())
+                .arg(
+                    // "--no-color"
+                    Arg::new("no-color").help("No color").long("no-color"),
+                )
+                .arg(get_format_arg()),
+        )
+        .subcommand(
+            // "browse"
+            clap::Command::new("browse")
+                .about("Browse tracks and sectors in a full-screen terminal UI")
+                .arg(
+                    Arg::new("*.D88")
+                        .help("D88 Disk Image")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    // "-s, --sort"
+                    Arg::new("sort")
+                        .help("Sort by disk sector order")
+                        .short('s')
+                        .long("sort"),
+                )
+                .arg(get_charset_arg()),
+        )
+        .subcommand(
+            // "edit"
+            clap::Command::new("edit")
+                .about("Edit sector headers and data in a full-screen hex editor")
+                .arg(
+                    Arg::new("*.D88")
+                        .help("D88 Disk Image")
+                        .required(true)
+                        .index(1),
+                ),
+        )
+        .subcommand(
+            // "build"
+            clap::Command::new("build")
+                .about("Build a D88 file from a disk description (TOML)")
+                .arg(
+                    Arg::new("DESC")
+                        .help("Disk description file (TOML)")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    Arg::new("OUTPUT")
+                        .help("Output D88 file")
+                        .required(true)
+                        .index(2),
+                ),
+        )
+        .subcommand(
+            // "decompile"
+            clap::Command::new("decompile")
+                .about("Print a disk description (TOML) of a D88 file")
+                .arg(
+                    Arg::new("*.D88")
+                        .help("D88 Disk Image")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    // "-o, --output"
+                    Arg::new("OUTPUT")
+                        .help("Output description file")
+                        .takes_value(true)
+                        .short('o')
+                        .long("output"),
+                ),
+        )
         .arg(
             Arg::new("*.D88")
                 .help("D88 Disk Image")
                 .required(true)
                 .index(1),
         )
-        .arg(
-            // "-p, --position"
-            Arg::new("TRACK,SIDE,SECTOR")
-                .help("Sector position\n  <TRACK>  0,1,2, ...\n  <SIDE>   0:front or 1:back\n  <SECTOR> 1,2,3, ...")
-                .takes_value(true)
-                .long("position")
-                .short('p'),
-        )
+        .args(get_selection_args())
         .arg(
             // "-n, --noinfo"  No Report D88 Information
             Arg::new("no-info")
@@ -62,11 +624,13 @@ pub fn get_cmdline_param() -> ArgMatches {
         )
         .arg(
             // "-s, --sort"
-            Arg::new("Sort by Disk Sector Order")
-                .help("Sort by disk sector order")
-                .short('s')
-                .long("sort"),
+            get_sort_arg(),
         )
+        .arg(get_charset_arg())
+        .args(get_layout_args())
+        .args(get_sector_layout_args())
+        .arg(get_format_arg())
+        .arg(get_data_arg())
         .get_matches();
 
     match1
@@ -87,7 +651,7 @@ pub fn get_cmdline_param() -> ArgMatches {
     //            .index(1))
     //        .arg(Arg::new("v")
     //            .short('v')
-    //            .multiple_occurrences(true)
+    //            .multiple_values(true)
     //            .takes_value(true)
     //             .about("Sets the level of verbosity"))
     //        .get_matches();
@@ -103,18 +667,246 @@ pub fn get_cmdline_param() -> ArgMatches {
     //      .get_matches();
 }
 
-/// Str to Number(u8)
+/// "*.D88" Argument (Helper function)
+///
+pub fn get_d88_arg() -> Arg<'static> {
+    Arg::new("*.D88")
+        .help("D88 Disk Image")
+        .required(true)
+        .index(1)
+}
+
+/// "-s, --sort" Option (Helper function)
+///
+pub fn get_sort_arg() -> Arg<'static> {
+    Arg::new("sort")
+        .help("Sort by disk sector order")
+        .short('s')
+        .long("sort")
+}
+
+/// "--data" Option (Helper function)
+///
+pub fn get_data_arg() -> Arg<'static> {
+    Arg::new("DATA")
+        .help("Sector data in --format json|yaml|csv")
+        .takes_value(true)
+        .possible_values(["none", "base64", "hex"])
+        .default_value("none")
+        .long("data")
+}
+
+/// "--fs" Option (Helper function)
+///
+/// 指定が無ければ自動判定する。
+///
+pub fn get_fs_arg() -> Arg<'static> {
+    Arg::new("FS")
+        .help("Filesystem (default: detect)")
+        .takes_value(true)
+        .possible_values(["hubasic", "n88", "cpm"])
+        .long("fs")
+}
+
+/// "--dialect" BASIC Dialect Option (Helper function)
+///
+pub fn get_dialect_arg() -> Arg<'static> {
+    Arg::new("DIALECT")
+        .help("BASIC dialect (default: by filesystem)")
+        .takes_value(true)
+        .possible_values(["n88", "hubasic", "msx"])
+        .long("dialect")
+}
+
+/// "--type" Disk Type Option (Helper function)
+///
+pub fn get_disk_type_arg() -> Arg<'static> {
+    Arg::new("DISK TYPE")
+        .takes_value(true)
+        .possible_values(["2D", "2DD", "2HD"])
+        .ignore_case(true)
+        .long("type")
+}
+
+/// "--charset" Option (Helper function)
+///
+/// 16進ダンプの文字欄の文字セット
+///
+pub fn get_charset_arg() -> Arg<'static> {
+    Arg::new("CHARSET")
+        .help("Character set of the dump")
+        .takes_value(true)
+        .possible_values(["ascii", "jisx0201", "sjis", "pc8801", "x1"])
+        .default_value("ascii")
+        .long("charset")
+}
+
+/// Hex Dump Layout Options (Helper function)
+///
+/// "--width", "--group", "--squeeze", "--no-char"
+///
+pub fn get_layout_args() -> Vec<Arg<'static>> {
+    vec![
+        // "--width"
+        Arg::new("WIDTH")
+            .help("Bytes per line")
+            .takes_value(true)
+            .possible_values(["8", "16", "32"])
+            .default_value("16")
+            .long("width"),
+        // "--group"
+        Arg::new("GROUP")
+            .help("Bytes per group")
+            .takes_value(true)
+            .possible_values(["1", "2", "4", "8"])
+            .default_value("1")
+            .long("group"),
+        // "--squeeze"
+        Arg::new("squeeze")
+            .help("Print \"*\" for lines same as the previous line")
+            .long("squeeze"),
+        // "--no-char"
+        Arg::new("no-char")
+            .help("No character column")
+            .long("no-char"),
+    ]
+}
+
+/// Sector Dump Layout Options (Helper function)
+///
+/// "--offset", "--skip-fill"
+///
+pub fn get_sector_layout_args() -> Vec<Arg<'static>> {
+    vec![
+        // "--offset"
+        Arg::new("OFFSET BASE")
+            .help("Offset of sector data\n  file: offset in the file\n  sector: offset in the sector\n  lba: offset in logical order (LBA x sector size)")
+            .takes_value(true)
+            .possible_values(["file", "sector", "lba"])
+            .default_value("file")
+            .long("offset"),
+        // "--skip-fill"
+        Arg::new("skip-fill")
+            .help("Skip sectors filled with one value (e.g. E5)")
+            .long("skip-fill"),
+    ]
+}
+
+/// Character Set from Command Line
+///
+pub fn get_charset(cmdline_info: &ArgMatches) -> Charset {
+    cmdline_info
+        .value_of("CHARSET")
+        .and_then(Charset::from_name)
+        .unwrap_or_default()
+}
+
+/// Hex Dump Layout from Command Line
+///
+/// "--width", "--group", "--squeeze", "--no-char"
+/// (`offset_base`, `skip_fill` はセクタ単位のダンプだけなので `get_sector_layout` で設定する)
+///
+pub fn get_layout(cmdline_info: &ArgMatches) -> HexLayout {
+    let default = HexLayout::default();
+    let value = |id: &str, default: usize| {
+        cmdline_info
+            .value_of(id)
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(default)
+    };
+
+    HexLayout {
+        width: value("WIDTH", default.width),
+        group: value("GROUP", default.group),
+        squeeze: cmdline_info.is_present("squeeze"),
+        char_column: !cmdline_info.is_present("no-char"),
+        ..default
+    }
+}
+
+/// Sector Dump Layout from Command Line
+///
+/// `get_layout` に "--offset", "--skip-fill" を加える。
+///
+pub fn get_sector_layout(cmdline_info: &ArgMatches) -> HexLayout {
+    let offset_base = match cmdline_info.value_of("OFFSET BASE") {
+        Some("sector") => OffsetBase::Sector,
+        Some("lba") => OffsetBase::Lba,
+        _ => OffsetBase::File,
+    };
+
+    HexLayout {
+        offset_base,
+        skip_fill: cmdline_info.is_present("skip-fill"),
+        ..get_layout(cmdline_info)
+    }
+}
+
+/// "--format" Option (Helper function)
 ///
+/// 各サブコマンド共通の出力形式
 ///
-pub fn get_str_to_u8(
-    s: &str,       // string
-    err_mes: &str, // Error Message
-) -> Result<u8, ()> {
-    if let Ok(val) = s.parse() {
-        Ok(val)
-    } else {
-        let mes = format!("{}? {}", s, err_mes);
+pub fn get_format_arg() -> Arg<'static> {
+    Arg::new("FORMAT")
+        .help("Output format")
+        .takes_value(true)
+        .possible_values(["text", "json", "yaml", "csv"])
+        .default_value("text")
+        .long("format")
+}
+
+/// Sector Selection Options (Helper function)
+///
+/// "-p", "--track", "--side", "--lba"
+///
+pub fn get_selection_args() -> Vec<Arg<'static>> {
+    vec![
+        // "-p, --position"
+        Arg::new("TRACK,SIDE,SECTOR")
+            .help("Sector position\n  <TRACK>  0,1,2, ...\n  <SIDE>   0:front or 1:back\n  <SECTOR> 0,1,2, ... (Sector ID R)\n  each is a number, range (0-3, 10..20, 10..=20) or *")
+            .takes_value(true)
+            .long("position")
+            .short('p'),
+        // "--track"
+        Arg::new("TRACK")
+            .help("Track range (e.g. 10..20, 0-3,5)")
+            .takes_value(true)
+            .long("track"),
+        // "--side"
+        Arg::new("SIDE")
+            .help("Side (0:front or 1:back)")
+            .takes_value(true)
+            .long("side"),
+        // "--lba"
+        Arg::new("LBA")
+            .help("Logical sector range, 0 start (e.g. 0..32)")
+            .takes_value(true)
+            .long("lba"),
+    ]
+}
+
+/// Sector Selection from Command Line
+///
+/// 指定が不正ならエラー終了する。
+///
+pub fn get_selection(cmdline_info: &ArgMatches) -> Selection {
+    let mut selection = Selection::default();
+
+    let result = [
+        (
+            "TRACK,SIDE,SECTOR",
+            Selection::parse_position as fn(&mut Selection, &str) -> _,
+        ),
+        ("TRACK", Selection::set_track),
+        ("SIDE", Selection::set_side),
+        ("LBA", Selection::set_lba),
+    ]
+    .iter()
+    .filter_map(|(arg, set)| cmdline_info.value_of(arg).map(|value| (value, set)))
+    .try_for_each(|(value, set)| set(&mut selection, value));
+    if let Err(mes) = result {
         ERROR(mes.as_str());
-        Err(())
     }
+
+    selection
 }
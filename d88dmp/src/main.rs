@@ -1,6 +1,24 @@
 mod cli;
+mod report_browse;
+mod report_build;
+mod report_check;
+mod report_convert;
+mod report_create;
 mod report_d88;
 mod report_d88_noinfo;
+mod report_diff;
+mod report_disasm;
+mod report_edit;
+mod report_extract;
+mod report_fs;
+mod report_hash;
+mod report_identify;
+mod report_map;
+mod report_merge;
+mod report_protect;
+mod report_scan;
+mod report_search;
+mod structured;
 mod utility;
 mod version;
 
@@ -8,7 +26,110 @@ fn main() {
     // Command Line Analysis
     let cmdline_info = cli::get_cmdline_param();
 
-    let mut rpt_d88 = report_d88::ReportD88::new(cmdline_info);
-
-    rpt_d88.report();
+    match cmdline_info.subcommand() {
+        Some(("info", sub_cmdline_info)) => {
+            let mut rpt_d88 = report_d88::ReportD88::new_info(sub_cmdline_info.clone());
+            rpt_d88.report();
+        }
+        Some(("dump", sub_cmdline_info)) => {
+            let mut rpt_d88 = report_d88::ReportD88::new_dump(sub_cmdline_info.clone());
+            rpt_d88.report();
+        }
+        Some(("hexdump", sub_cmdline_info)) => {
+            let mut rpt_d88 = report_d88::ReportD88::new_hexdump(sub_cmdline_info.clone());
+            rpt_d88.report();
+        }
+        Some(("sectors", sub_cmdline_info)) => {
+            let mut rpt_d88 = report_d88::ReportD88::new_sectors(sub_cmdline_info.clone());
+            rpt_d88.report();
+        }
+        Some(("ls", sub_cmdline_info)) => {
+            let rpt_ls = report_fs::ReportLs::new(sub_cmdline_info.clone());
+            rpt_ls.report();
+        }
+        Some(("get", sub_cmdline_info)) => {
+            let rpt_get = report_fs::ReportGet::new(sub_cmdline_info.clone());
+            rpt_get.report();
+        }
+        Some(("list", sub_cmdline_info)) => {
+            let rpt_list = report_fs::ReportList::new(sub_cmdline_info.clone());
+            rpt_list.report();
+        }
+        Some(("put", sub_cmdline_info)) => {
+            let rpt_put = report_fs::ReportPut::new(sub_cmdline_info.clone());
+            rpt_put.report();
+        }
+        Some(("convert", sub_cmdline_info)) => {
+            let rpt_convert = report_convert::ReportConvert::new(sub_cmdline_info.clone());
+            rpt_convert.report();
+        }
+        Some(("check", sub_cmdline_info)) => {
+            let rpt_check = report_check::ReportCheck::new(sub_cmdline_info.clone());
+            rpt_check.report();
+        }
+        Some(("create", sub_cmdline_info)) => {
+            let rpt_create = report_create::ReportCreate::new(sub_cmdline_info.clone());
+            rpt_create.report();
+        }
+        Some(("hash", sub_cmdline_info)) => {
+            let rpt_hash = report_hash::ReportHash::new(sub_cmdline_info.clone());
+            rpt_hash.report();
+        }
+        Some(("diff", sub_cmdline_info)) => {
+            let rpt_diff = report_diff::ReportDiff::new(sub_cmdline_info.clone());
+            rpt_diff.report();
+        }
+        Some(("merge", sub_cmdline_info)) => {
+            let rpt_merge = report_merge::ReportMerge::new(sub_cmdline_info.clone());
+            rpt_merge.report();
+        }
+        Some(("protect", sub_cmdline_info)) => {
+            let rpt_protect = report_protect::ReportProtect::new(sub_cmdline_info.clone());
+            rpt_protect.report();
+        }
+        Some(("map", sub_cmdline_info)) => {
+            let rpt_map = report_map::ReportMap::new(sub_cmdline_info.clone());
+            rpt_map.report();
+        }
+        Some(("extract", sub_cmdline_info)) => {
+            let rpt_extract = report_extract::ReportExtract::new(sub_cmdline_info.clone());
+            rpt_extract.report();
+        }
+        Some(("disasm", sub_cmdline_info)) => {
+            let rpt_disasm = report_disasm::ReportDisasm::new(sub_cmdline_info.clone());
+            rpt_disasm.report();
+        }
+        Some(("search", sub_cmdline_info)) => {
+            let rpt_search = report_search::ReportSearch::new(sub_cmdline_info.clone());
+            rpt_search.report();
+        }
+        Some(("browse", sub_cmdline_info)) => {
+            let rpt_browse = report_browse::ReportBrowse::new(sub_cmdline_info.clone());
+            rpt_browse.report();
+        }
+        Some(("edit", sub_cmdline_info)) => {
+            let rpt_edit = report_edit::ReportEdit::new(sub_cmdline_info.clone());
+            rpt_edit.report();
+        }
+        Some(("build", sub_cmdline_info)) => {
+            let rpt_build = report_build::ReportBuild::new(sub_cmdline_info.clone());
+            rpt_build.report();
+        }
+        Some(("decompile", sub_cmdline_info)) => {
+            let rpt_decompile = report_build::ReportDecompile::new(sub_cmdline_info.clone());
+            rpt_decompile.report();
+        }
+        Some(("identify", sub_cmdline_info)) => {
+            let rpt_identify = report_identify::ReportIdentify::new(sub_cmdline_info.clone());
+            rpt_identify.report();
+        }
+        Some(("scan", sub_cmdline_info)) => {
+            let rpt_scan = report_scan::ReportScan::new(sub_cmdline_info.clone());
+            rpt_scan.report();
+        }
+        _ => {
+            let mut rpt_d88 = report_d88::ReportD88::new(cmdline_info);
+            rpt_d88.report();
+        }
+    }
 }
@@ -0,0 +1,723 @@
+use std::io;
+use std::mem;
+use std::path::Path;
+
+use ratatui::backend::Backend;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+use ratatui::{Frame, Terminal};
+
+use D88FileIO::charset::Charset;
+use D88FileIO::fileio;
+use D88FileIO::format::D88_SectorHdr;
+use D88FileIO::search::{search, Pattern};
+use D88FileIO::sector::Sector;
+use D88FileIO::select::Selection;
+
+use crate::cli::get_charset;
+use crate::utility::{get_16byte_str, ERROR};
+
+const KEY_HELP: &str =
+    "q:Quit  Up/Down:Sector  PgUp/PgDn:Track  [ ]:Scroll  g:Offset  /:Search  n/N:Next/Prev  s:Sort";
+
+/// ReportBrowse
+///
+/// 全画面のターミナルUIで、トラック/セクタを選んでダンプを見る。
+///
+pub struct ReportBrowse {
+    pub path: Option<String>,
+    pub sort_by_sector: bool,
+    pub charset: Charset,
+}
+
+impl ReportBrowse {
+    /// Constructor
+    ///
+    pub fn new(_cmdline_info: clap::ArgMatches) -> Self {
+        Self {
+            path: _cmdline_info.value_of("*.D88").map(|path| path.to_string()),
+            sort_by_sector: _cmdline_info.is_present("sort"),
+            charset: get_charset(&_cmdline_info),
+        }
+    }
+
+    /// Report
+    ///
+    /// # Argument
+    ///   * (none)
+    ///
+    pub fn report(&self) {
+        if let Some(ref d88_path) = self.path {
+            let d88fileio = fileio::D88FileIO::open(Path::new(d88_path));
+            if !d88fileio.is_open() {
+                println!("File Not Found \"{}\"", d88_path);
+                return;
+            }
+
+            let mut browser = Browser::new(d88fileio, self.sort_by_sector, self.charset);
+            let mut terminal = ratatui::init();
+            let result = browser.run(&mut terminal);
+            ratatui::restore();
+
+            if let Err(err) = result {
+                ERROR(format!("{}", err).as_str());
+            }
+        }
+    }
+}
+
+/// Row of Track/Sector Tree
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Row {
+    /// Index to `track_tbl`
+    Track(usize),
+    /// Index to `track_tbl`, Index to `sector_tbl`
+    Sector(usize, usize),
+}
+
+/// Input Prompt
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Prompt {
+    Offset,
+    Search,
+}
+
+/// Disk Browser
+///
+/// 画面の状態とキー操作。描画は `draw()` で、任意の `Backend` に出力できる。
+///
+pub struct Browser {
+    d88fileio: fileio::D88FileIO,
+    rows: Vec<Row>,
+    cursor: usize,
+    scroll: usize,
+    hex_height: usize,
+    sort_by_sector: bool,
+    charset: Charset,
+    mark: Option<(u64, usize)>,
+    input: Option<(Prompt, String)>,
+    hits: Vec<(u64, usize)>,
+    hit_idx: usize,
+    message: String,
+    quit: bool,
+}
+
+impl Browser {
+    /// Constructor
+    ///
+    /// # Argument
+    ///
+    ///   * `d88fileio`      D88FileIO
+    ///   * `sort_by_sector` Sort by Sector ID (false: File Offset Order)
+    ///   * `charset`        Character Set of Hex View
+    ///
+    pub fn new(mut d88fileio: fileio::D88FileIO, sort_by_sector: bool, charset: Charset) -> Self {
+        if sort_by_sector {
+            d88fileio.sector_sort();
+        } else {
+            d88fileio.file_offset_sort();
+        }
+
+        let mut browser = Self {
+            d88fileio,
+            rows: Vec::new(),
+            cursor: 0,
+            scroll: 0,
+            hex_height: 16,
+            sort_by_sector,
+            charset,
+            mark: None,
+            input: None,
+            hits: Vec::new(),
+            hit_idx: 0,
+            message: String::new(),
+            quit: false,
+        };
+        browser.build_rows();
+        browser.cursor = browser.next_sector_row(0).unwrap_or(0);
+        browser
+    }
+
+    /// Run until Quit
+    ///
+    pub fn run<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> io::Result<()> {
+        while !self.quit {
+            terminal.draw(|frame| self.draw(frame))?;
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    self.handle_key(key);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Current Sector
+    ///
+    pub fn current_sector(&self) -> Option<&Sector> {
+        match self.rows.get(self.cursor) {
+            Some(Row::Sector(track_idx, sector_idx)) => {
+                Some(&self.d88fileio.disk.track_tbl[*track_idx].sector_tbl[*sector_idx])
+            }
+            _ => None,
+        }
+    }
+
+    /// Key Input
+    ///
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        self.message.clear();
+
+        if let Some((prompt, mut text)) = self.input.take() {
+            match key.code {
+                KeyCode::Enter => match prompt {
+                    Prompt::Offset => self.jump_to_input(&text),
+                    Prompt::Search => self.search_input(&text),
+                },
+                KeyCode::Esc => {}
+                KeyCode::Backspace => {
+                    text.pop();
+                    self.input = Some((prompt, text));
+                }
+                KeyCode::Char(c) => {
+                    text.push(c);
+                    self.input = Some((prompt, text));
+                }
+                _ => self.input = Some((prompt, text)),
+            }
+            return;
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Down | KeyCode::Char('j') => self.move_sector(true),
+            KeyCode::Up | KeyCode::Char('k') => self.move_sector(false),
+            KeyCode::PageDown => self.move_track(true),
+            KeyCode::PageUp => self.move_track(false),
+            KeyCode::Home => self.select_row(self.next_sector_row(0)),
+            KeyCode::End => self.select_row(self.prev_sector_row(self.rows.len())),
+            KeyCode::Char(']') => self.scroll_hex(8),
+            KeyCode::Char('[') => self.scroll_hex(-8),
+            KeyCode::Char('g') => self.input = Some((Prompt::Offset, String::new())),
+            KeyCode::Char('/') => self.input = Some((Prompt::Search, String::new())),
+            KeyCode::Char('n') => self.next_hit(true),
+            KeyCode::Char('N') => self.next_hit(false),
+            KeyCode::Char('s') => self.toggle_sort(),
+            _ => {}
+        }
+    }
+
+    /// Draw Screen
+    ///
+    pub fn draw(&mut self, frame: &mut Frame) {
+        let [main_area, status_area] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+        let [tree_area, right_area] =
+            Layout::horizontal([Constraint::Length(24), Constraint::Min(0)]).areas(main_area);
+        let [header_area, hex_area] =
+            Layout::vertical([Constraint::Length(6), Constraint::Min(0)]).areas(right_area);
+
+        // Track/Sector Tree
+        //
+        let items: Vec<ListItem> = self
+            .rows
+            .iter()
+            .map(|row| match row {
+                Row::Track(track_idx) => {
+                    let (track_no, side_no) = self.d88fileio.disk.track_position(*track_idx);
+                    ListItem::new(format!("Track {:3} Side {}", track_no, side_no))
+                        .style(Style::default().fg(Color::Cyan))
+                }
+                Row::Sector(track_idx, sector_idx) => {
+                    let sector = &self.d88fileio.disk.track_tbl[*track_idx].sector_tbl[*sector_idx];
+                    let item = ListItem::new(format!(
+                        "  Sector {:3} {:5}",
+                        sector.header.sector,
+                        sector.data.len()
+                    ));
+                    if sector.header.status != 0 || sector.header.deleted_mark != 0 {
+                        item.style(Style::default().fg(Color::Yellow))
+                    } else {
+                        item
+                    }
+                }
+            })
+            .collect();
+        let tree = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title("Sector"))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        let mut state = ListState::default().with_selected(Some(self.cursor));
+        frame.render_stateful_widget(tree, tree_area, &mut state);
+
+        // Header Details
+        //
+        let disk = &self.d88fileio.disk;
+        let mut header_lines = vec![Line::from(format!(
+            "Name({})  Protect{}  {}  {}",
+            disk.get_disk_name_with(self.name_charset()),
+            disk.get_disk_write_protect(),
+            disk.get_disk_type(),
+            disk.get_disk_size()
+        ))];
+        if let Some(sector) = self.current_sector() {
+            header_lines.push(Line::from(format!(
+                "{}, {}, {}, {}, {}",
+                sector.get_track(),
+                sector.get_side(),
+                sector.get_sector(),
+                sector.get_sector_size(),
+                sector.get_num_of_sector()
+            )));
+            header_lines.push(Line::from(format!(
+                "{}, {}, {}, {}",
+                sector.get_status(),
+                sector.get_density(),
+                sector.get_mark(),
+                sector.get_data_size()
+            )));
+        }
+        header_lines.push(Line::from(format!(
+            "Sort by {}",
+            if self.sort_by_sector {
+                "Sector ID"
+            } else {
+                "File Offset"
+            }
+        )));
+        let header = Paragraph::new(header_lines)
+            .block(Block::default().borders(Borders::ALL).title("Header"));
+        frame.render_widget(header, header_area);
+
+        // Hex View
+        //
+        self.hex_height = hex_area.height.saturating_sub(2) as usize;
+        let hex_lines = self.hex_lines();
+        let hex = Paragraph::new(hex_lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Offset  +0 +1 +2 +3 +4 +5 +6 +7 +8 +9 +a +b +c +d +e +f"),
+            )
+            .scroll((self.scroll as u16, 0));
+        frame.render_widget(hex, hex_area);
+
+        // Status Line
+        //
+        let status = match self.input {
+            Some((Prompt::Offset, ref text)) => format!("File Offset (hex): {}_", text),
+            Some((Prompt::Search, ref text)) => {
+                format!(
+                    "Search (ASCII, x:hex, s:Shift-JIS, j:JIS X 0201): {}_",
+                    text
+                )
+            }
+            None if !self.message.is_empty() => self.message.clone(),
+            None => KEY_HELP.to_string(),
+        };
+        frame.render_widget(Paragraph::new(status), status_area);
+    }
+
+    /// Hex View Lines (Helper function)
+    ///
+    /// セクタヘッダ(16byte)とデータを `print_16byte` と同じ形で並べる。
+    ///
+    fn hex_lines(&self) -> Vec<Line<'static>> {
+        let sector = if let Some(sector) = self.current_sector() {
+            sector
+        } else {
+            return Vec::new();
+        };
+
+        let byte_img;
+        unsafe {
+            byte_img = mem::transmute::<D88_SectorHdr, [u8; mem::size_of::<D88_SectorHdr>()]>(
+                sector.header,
+            );
+        }
+
+        let header_offset = sector.offset - mem::size_of::<D88_SectorHdr>() as u64;
+        let header_cells = self.charset.render(&byte_img);
+        let mut lines = vec![self.hex_line(&byte_img, &header_cells, header_offset, Color::Green)];
+        let cells = self.charset.render(&sector.data);
+        for (idx, buf16) in sector.data.chunks(16).enumerate() {
+            lines.push(self.hex_line(
+                buf16,
+                &cells[idx * 16..idx * 16 + buf16.len()],
+                sector.offset + (idx * 16) as u64,
+                Color::White,
+            ));
+        }
+        lines
+    }
+
+    /// Character Set of Disk Name (Helper function)
+    ///
+    /// `ascii` なら Shift-JIS で表示する。
+    ///
+    fn name_charset(&self) -> Charset {
+        match self.charset {
+            Charset::Ascii => Charset::Sjis,
+            charset => charset,
+        }
+    }
+
+    /// Hex View Line (Helper function)
+    ///
+    fn hex_line(&self, buf16: &[u8], cells: &[String], offset: u64, color: Color) -> Line<'static> {
+        let (byte16_str, _) = get_16byte_str(buf16, cells, buf16.len());
+        let marked = |idx: usize| match self.mark {
+            Some((start, len)) => (start..start + len as u64).contains(&(offset + idx as u64)),
+            None => false,
+        };
+        let style = |idx: usize| {
+            if marked(idx) {
+                Style::default().fg(color).add_modifier(Modifier::REVERSED)
+            } else {
+                Style::default().fg(color)
+            }
+        };
+
+        let mut spans = vec![Span::styled(
+            format!("{:06x}  ", offset),
+            Style::default().fg(Color::Cyan),
+        )];
+        for idx in 0..buf16.len() {
+            spans.push(Span::styled(
+                byte16_str[idx * 3..idx * 3 + 2].to_string(),
+                style(idx),
+            ));
+            spans.push(Span::raw(" "));
+        }
+        spans.push(Span::raw("   ".repeat(16 - buf16.len())));
+        for (idx, cell) in cells.iter().enumerate() {
+            let cell = if cell.is_empty() && idx == 0 {
+                " "
+            } else {
+                cell
+            };
+            spans.push(Span::styled(cell.to_string(), style(idx)));
+        }
+        Line::from(spans)
+    }
+
+    /// Build Track/Sector Tree (Helper function)
+    ///
+    /// セクタの無いトラックは表示しない。
+    ///
+    fn build_rows(&mut self) {
+        self.rows.clear();
+        for (track_idx, track) in self.d88fileio.disk.track_tbl.iter().enumerate() {
+            if track.sector_tbl.is_empty() {
+                continue;
+            }
+            self.rows.push(Row::Track(track_idx));
+            for sector_idx in 0..track.sector_tbl.len() {
+                self.rows.push(Row::Sector(track_idx, sector_idx));
+            }
+        }
+    }
+
+    /// First Sector Row at or after `from` (Helper function)
+    ///
+    fn next_sector_row(&self, from: usize) -> Option<usize> {
+        (from..self.rows.len()).find(|idx| matches!(self.rows[*idx], Row::Sector(..)))
+    }
+
+    /// Last Sector Row before `to` (Helper function)
+    ///
+    fn prev_sector_row(&self, to: usize) -> Option<usize> {
+        (0..to.min(self.rows.len()))
+            .rev()
+            .find(|idx| matches!(self.rows[*idx], Row::Sector(..)))
+    }
+
+    /// Select a Row (Helper function)
+    ///
+    fn select_row(&mut self, row: Option<usize>) {
+        if let Some(row) = row {
+            self.cursor = row;
+            self.scroll = 0;
+        }
+    }
+
+    /// Next/Previous Sector (Helper function)
+    ///
+    fn move_sector(&mut self, forward: bool) {
+        let row = if forward {
+            self.next_sector_row(self.cursor + 1)
+        } else {
+            self.prev_sector_row(self.cursor)
+        };
+        self.select_row(row);
+    }
+
+    /// First Sector of Next/Previous Track (Helper function)
+    ///
+    fn move_track(&mut self, forward: bool) {
+        let is_track = |idx: &usize| matches!(self.rows[*idx], Row::Track(_));
+        let current = (0..=self.cursor).rev().find(is_track).unwrap_or(0);
+        let track_row = if forward {
+            (current + 1..self.rows.len()).find(is_track)
+        } else {
+            (0..current).rev().find(is_track)
+        };
+        if let Some(track_row) = track_row {
+            self.select_row(self.next_sector_row(track_row));
+        }
+    }
+
+    /// Scroll Hex View (Helper function)
+    ///
+    fn scroll_hex(&mut self, lines: isize) {
+        let max = self.hex_lines().len().saturating_sub(self.hex_height);
+        self.scroll = self.scroll.saturating_add_signed(lines).min(max);
+    }
+
+    /// Jump to File Offset (Helper function)
+    ///
+    /// セクタヘッダまたはデータに `offset` を含むセクタを選び、そのバイトに印を付ける。
+    ///
+    fn jump_to_offset(&mut self, offset: u64, len: usize) -> bool {
+        let hdr_size = mem::size_of::<D88_SectorHdr>() as u64;
+        let row = self.rows.iter().position(|row| match row {
+            Row::Sector(track_idx, sector_idx) => {
+                let sector = &self.d88fileio.disk.track_tbl[*track_idx].sector_tbl[*sector_idx];
+                (sector.offset - hdr_size..sector.offset + sector.data.len() as u64)
+                    .contains(&offset)
+            }
+            Row::Track(_) => false,
+        });
+
+        if let Some(row) = row {
+            self.select_row(Some(row));
+            self.mark = Some((offset, len));
+
+            let sector_start = self.current_sector().map_or(0, |sector| sector.offset) - hdr_size;
+            let line = ((offset - sector_start) / 16) as usize;
+            if line >= self.hex_height {
+                self.scroll = line - self.hex_height / 2;
+            }
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Jump to File Offset from Input (Helper function)
+    ///
+    fn jump_to_input(&mut self, text: &str) {
+        let hex = text.trim().trim_start_matches("0x");
+        match u64::from_str_radix(hex, 16) {
+            Ok(offset) => {
+                if !self.jump_to_offset(offset, 1) {
+                    self.message = format!("Offset 0x{:x} is not in any sector", offset);
+                }
+            }
+            Err(_) => self.message = format!("\"{}\"? Not Hex Number", text),
+        }
+    }
+
+    /// Search from Input (Helper function)
+    ///
+    fn search_input(&mut self, text: &str) {
+        let pattern = if let Some(hex) = text.strip_prefix("x:") {
+            Pattern::from_hex(hex)
+        } else if let Some(sjis) = text.strip_prefix("s:") {
+            Pattern::from_sjis(sjis)
+        } else if let Some(jis) = text.strip_prefix("j:") {
+            Pattern::from_jisx0201(jis)
+        } else {
+            Pattern::from_ascii(text)
+        };
+
+        match pattern {
+            Ok(pattern) => {
+                self.hits = search(&self.d88fileio.disk, &Selection::default(), &pattern)
+                    .iter()
+                    .map(|hit| (hit.file_offset, pattern.len()))
+                    .collect();
+                self.hit_idx = 0;
+                self.show_hit();
+            }
+            Err(mes) => self.message = mes,
+        }
+    }
+
+    /// Next/Previous Search Hit (Helper function)
+    ///
+    fn next_hit(&mut self, forward: bool) {
+        if self.hits.is_empty() {
+            self.message = "No search hits".to_string();
+            return;
+        }
+
+        self.hit_idx = if forward {
+            (self.hit_idx + 1) % self.hits.len()
+        } else {
+            (self.hit_idx + self.hits.len() - 1) % self.hits.len()
+        };
+        self.show_hit();
+    }
+
+    /// Show Search Hit (Helper function)
+    ///
+    fn show_hit(&mut self) {
+        if let Some((offset, len)) = self.hits.get(self.hit_idx).copied() {
+            self.jump_to_offset(offset, len);
+            self.message = format!(
+                "Hit {}/{}  File Offset 0x{:06x}",
+                self.hit_idx + 1,
+                self.hits.len(),
+                offset
+            );
+        } else {
+            self.message = "Not Found".to_string();
+        }
+    }
+
+    /// Toggle Sector ID / File Offset Order (Helper function)
+    ///
+    fn toggle_sort(&mut self) {
+        let offset = self.current_sector().map(|sector| sector.offset);
+
+        self.sort_by_sector = !self.sort_by_sector;
+        if self.sort_by_sector {
+            self.d88fileio.sector_sort();
+        } else {
+            self.d88fileio.file_offset_sort();
+        }
+        self.build_rows();
+
+        let row = self.rows.iter().position(|row| match row {
+            Row::Sector(track_idx, sector_idx) => {
+                Some(self.d88fileio.disk.track_tbl[*track_idx].sector_tbl[*sector_idx].offset)
+                    == offset
+            }
+            Row::Track(_) => false,
+        });
+        self.cursor = row.or(self.next_sector_row(0)).unwrap_or(0);
+    }
+}
+
+// ================================================================================
+//
+//  Test Code
+//
+// ================================================================================
+#[cfg(test)]
+mod test {
+    use ratatui::backend::TestBackend;
+    use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use ratatui::Terminal;
+
+    use crate::report_browse::Browser;
+    use D88FileIO::charset::Charset;
+    use D88FileIO::fileio;
+
+    const HUBASIC: &str = "../sample/HuBASIC_Format_2D.d88";
+
+    fn key(browser: &mut Browser, code: KeyCode) {
+        browser.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    fn keys(browser: &mut Browser, text: &str) {
+        for c in text.chars() {
+            key(browser, KeyCode::Char(c));
+        }
+    }
+
+    fn screen(browser: &mut Browser) -> Vec<String> {
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        terminal.draw(|frame| browser.draw(frame)).unwrap();
+
+        let buffer = terminal.backend().buffer();
+        buffer
+            .content()
+            .chunks(buffer.area.width as usize)
+            .map(|row| row.iter().map(|cell| cell.symbol()).collect::<String>())
+            .collect()
+    }
+
+    #[test]
+    fn test_draw() {
+        let mut browser = Browser::new(fileio::D88FileIO::open(HUBASIC), false, Charset::Ascii);
+        let lines = screen(&mut browser);
+
+        assert!(lines[1].contains("Track   0 Side 0"));
+        assert!(lines[2].contains("  Sector   1   256"));
+        assert!(lines
+            .iter()
+            .any(|line| line.contains("Sort by File Offset")));
+        assert!(lines
+            .iter()
+            .any(|line| line.contains("0002b0  00 00 01 01 10 00")));
+        assert!(lines.iter().any(|line| line.starts_with("│  Sector")));
+        assert!(lines[29].starts_with("q:Quit"));
+    }
+
+    #[test]
+    fn test_navigation() {
+        let mut browser = Browser::new(fileio::D88FileIO::open(HUBASIC), true, Charset::Ascii);
+
+        key(&mut browser, KeyCode::Down);
+        assert_eq!(browser.current_sector().unwrap().header.sector, 2);
+        key(&mut browser, KeyCode::Up);
+        key(&mut browser, KeyCode::Up);
+        assert_eq!(browser.current_sector().unwrap().header.sector, 1);
+
+        key(&mut browser, KeyCode::PageDown);
+        let header = browser.current_sector().unwrap().header;
+        assert_eq!((header.track, header.side, header.sector), (0, 1, 1));
+        key(&mut browser, KeyCode::PageUp);
+        assert_eq!(browser.current_sector().unwrap().header.side, 0);
+
+        key(&mut browser, KeyCode::End);
+        assert_eq!(browser.current_sector().unwrap().header.track, 39);
+
+        // Sort toggle keeps the current sector
+        let offset = browser.current_sector().unwrap().offset;
+        key(&mut browser, KeyCode::Char('s'));
+        assert_eq!(browser.current_sector().unwrap().offset, offset);
+        assert!(screen(&mut browser)
+            .iter()
+            .any(|line| line.contains("Sort by File Offset")));
+
+        key(&mut browser, KeyCode::Char('q'));
+        assert!(browser.quit);
+    }
+
+    #[test]
+    fn test_jump_and_search() {
+        let mut browser = Browser::new(fileio::D88FileIO::open(HUBASIC), true, Charset::Ascii);
+
+        // Data of Track 0 Side 1 Sector 1 (16 sectors x (16 + 256) byte after header)
+        let offset = 0x2b0 + 16 * (16 + 256) + 16 + 0x20;
+        key(&mut browser, KeyCode::Char('g'));
+        keys(&mut browser, &format!("{:x}", offset));
+        key(&mut browser, KeyCode::Enter);
+        let sector = browser.current_sector().unwrap();
+        assert_eq!((sector.header.side, sector.header.sector), (1, 1));
+
+        key(&mut browser, KeyCode::Char('g'));
+        keys(&mut browser, "zz");
+        key(&mut browser, KeyCode::Enter);
+        assert!(screen(&mut browser)
+            .iter()
+            .any(|line| line.contains("\"zz\"? Not Hex Number")));
+
+        // Search pattern at the head of Track 1 Side 0 Sector 1
+        let data = browser.d88fileio.disk.track_tbl[2].sector_tbl[0].data[..4].to_vec();
+        let hex: Vec<String> = data.iter().map(|byte| format!("{:02x}", byte)).collect();
+        key(&mut browser, KeyCode::Char('/'));
+        keys(&mut browser, &format!("x:{}", hex.join(" ")));
+        key(&mut browser, KeyCode::Enter);
+        assert!(screen(&mut browser)
+            .iter()
+            .any(|line| line.starts_with("Hit 1/")));
+    }
+}
@@ -0,0 +1,105 @@
+use std::fs;
+use std::path::Path;
+
+use D88FileIO::desc::{build, DiskDesc};
+use D88FileIO::fileio;
+
+use crate::utility::ERROR;
+
+/// ReportBuild
+///
+/// ディスク記述ファイル(TOML)からD88ファイルを作る。
+///
+pub struct ReportBuild {
+    pub desc_path: Option<String>,
+    pub output_path: Option<String>,
+}
+
+impl ReportBuild {
+    /// Constructor
+    ///
+    pub fn new(_cmdline_info: clap::ArgMatches) -> Self {
+        Self {
+            desc_path: _cmdline_info.value_of("DESC").map(|path| path.to_string()),
+            output_path: _cmdline_info
+                .value_of("OUTPUT")
+                .map(|path| path.to_string()),
+        }
+    }
+
+    /// Report
+    ///
+    /// # Argument
+    ///   * (none)
+    ///
+    pub fn report(&self) {
+        if let (Some(ref desc_path), Some(ref output_path)) = (&self.desc_path, &self.output_path) {
+            let disk = match build(Path::new(desc_path)) {
+                Ok(disk) => disk,
+                Err(mes) => {
+                    ERROR(mes.as_str());
+                    return;
+                }
+            };
+
+            if let Ok(disk_size) = disk.save(Path::new(output_path)) {
+                println!("Output  {} ({} byte)", output_path, disk_size);
+            } else {
+                ERROR(format!("Can't write \"{}\"", output_path).as_str());
+            }
+        }
+    }
+}
+
+/// ReportDecompile
+///
+/// D88ファイルからディスク記述(TOML)を作る。
+///
+pub struct ReportDecompile {
+    pub path: Option<String>,
+    pub output_path: Option<String>,
+}
+
+impl ReportDecompile {
+    /// Constructor
+    ///
+    pub fn new(_cmdline_info: clap::ArgMatches) -> Self {
+        Self {
+            path: _cmdline_info.value_of("*.D88").map(|path| path.to_string()),
+            output_path: _cmdline_info
+                .value_of("OUTPUT")
+                .map(|path| path.to_string()),
+        }
+    }
+
+    /// Report
+    ///
+    /// # Argument
+    ///   * (none)
+    ///
+    pub fn report(&self) {
+        if let Some(ref d88_path) = self.path {
+            let d88fileio = fileio::D88FileIO::open(Path::new(d88_path));
+            if !d88fileio.is_open() {
+                println!("File Not Found \"{}\"", d88_path);
+                return;
+            }
+
+            let text = match DiskDesc::from_disk(&d88fileio.disk).to_toml() {
+                Ok(text) => text,
+                Err(mes) => {
+                    ERROR(mes.as_str());
+                    return;
+                }
+            };
+
+            if let Some(ref output_path) = self.output_path {
+                if fs::write(Path::new(output_path), text).is_err() {
+                    ERROR(format!("Can't write \"{}\"", output_path).as_str());
+                }
+            } else {
+                print!("{}", text);
+            }
+        }
+    }
+}
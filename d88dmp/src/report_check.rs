@@ -0,0 +1,131 @@
+use serde::Serialize;
+use std::io::{Seek, SeekFrom};
+use std::path::Path;
+use std::process;
+
+use D88FileIO::check::{check, Problem, Severity};
+use D88FileIO::fileio;
+
+use crate::structured::{print_structured, OutputFormat};
+
+/// ReportCheck
+///
+/// ディスクイメージの整合性を調べる。
+/// エラーがあれば終了コード 1 で終わる。
+///
+pub struct ReportCheck {
+    pub path: Option<String>,
+    pub format: OutputFormat,
+}
+
+impl ReportCheck {
+    /// Constructor
+    ///
+    pub fn new(_cmdline_info: clap::ArgMatches) -> Self {
+        Self {
+            path: _cmdline_info.value_of("*.D88").map(|path| path.to_string()),
+            format: OutputFormat::from_cmdline(&_cmdline_info),
+        }
+    }
+
+    /// Report
+    ///
+    /// # Argument
+    ///   * (none)
+    ///
+    pub fn report(&self) {
+        if let Some(ref d88_path) = self.path {
+            let mut d88fileio = fileio::D88FileIO::open(Path::new(d88_path));
+            if !d88fileio.is_open() {
+                println!("File Not Found \"{}\"", d88_path);
+                return;
+            }
+
+            // Size of the image (also in an archive)
+            let file_size = d88fileio
+                .reader
+                .as_mut()
+                .and_then(|reader| reader.seek(SeekFrom::End(0)).ok());
+            let problems = check(&d88fileio.disk, file_size);
+
+            if !self.format.is_text() {
+                let rows: Vec<CheckRow> = problems.iter().map(CheckRow::new).collect();
+                let doc = CheckDoc {
+                    path: d88_path.to_string(),
+                    problems: rows,
+                };
+                print_structured(self.format, &doc, &doc.problems);
+            } else {
+                self.report_problems(d88_path, &problems);
+            }
+
+            if problems
+                .iter()
+                .any(|problem| problem.severity == Severity::Error)
+            {
+                process::exit(1);
+            }
+        }
+    }
+
+    /// Report Problems (Helper function)
+    ///
+    fn report_problems(&self, d88_path: &str, problems: &[Problem]) {
+        println!("Check  {}", d88_path);
+        println!();
+        for problem in problems.iter() {
+            let position = match problem.track_idx {
+                Some(idx) => format!("Track Index {:3}", idx),
+                None => "Disk Header    ".to_string(),
+            };
+            println!(
+                "{:7}  {}  {}",
+                problem.severity.get_name(),
+                position,
+                problem.message
+            );
+        }
+
+        let count = |severity| {
+            problems
+                .iter()
+                .filter(|problem| problem.severity == severity)
+                .count()
+        };
+        if !problems.is_empty() {
+            println!();
+        }
+        println!(
+            "{} error(s), {} warning(s)",
+            count(Severity::Error),
+            count(Severity::Warning)
+        );
+    }
+}
+
+/// "check" Document (Structured Output)
+///
+#[derive(Debug, Serialize)]
+struct CheckDoc {
+    path: String,
+    problems: Vec<CheckRow>,
+}
+
+/// "check" Problem (Structured Output)
+///
+#[derive(Debug, Serialize)]
+struct CheckRow {
+    severity: String,
+    track_idx: Option<usize>,
+    message: String,
+}
+
+impl CheckRow {
+    fn new(problem: &Problem) -> Self {
+        Self {
+            severity: problem.severity.get_name().to_string(),
+            track_idx: problem.track_idx,
+            message: problem.message.clone(),
+        }
+    }
+}
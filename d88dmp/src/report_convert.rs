@@ -0,0 +1,98 @@
+use std::fs;
+use std::path::Path;
+
+use D88FileIO::archive::is_archive_source;
+use D88FileIO::disk::Disk;
+use D88FileIO::fileio;
+use D88FileIO::raw::{from_raw, to_raw, Geometry};
+
+use crate::utility::ERROR;
+
+/// ReportConvert
+///
+/// D88/D77 とベタイメージ(*.2d, *.2dd, *.2hd, *.img 等)を相互に変換する。
+/// 形式は拡張子で決める(*.d88, *.d77, *.88d 以外はベタイメージ)。
+///
+pub struct ReportConvert {
+    pub input_path: Option<String>,
+    pub output_path: Option<String>,
+    pub disk_type: Option<String>,
+}
+
+/// Fill Byte of missing Sectors at Raw Image
+const RAW_FILL: u8 = 0xe5;
+
+impl ReportConvert {
+    /// Constructor
+    ///
+    pub fn new(_cmdline_info: clap::ArgMatches) -> Self {
+        Self {
+            input_path: _cmdline_info.value_of("INPUT").map(|path| path.to_string()),
+            output_path: _cmdline_info
+                .value_of("OUTPUT")
+                .map(|path| path.to_string()),
+            disk_type: _cmdline_info
+                .value_of("DISK TYPE")
+                .map(|disk_type| disk_type.to_string()),
+        }
+    }
+
+    /// Report
+    ///
+    /// # Argument
+    ///   * (none)
+    ///
+    pub fn report(&self) {
+        if let (Some(ref input_path), Some(ref output_path)) = (&self.input_path, &self.output_path)
+        {
+            let mut disk = if let Some(disk) = self.read(input_path) {
+                disk
+            } else {
+                return;
+            };
+
+            let result =
+                if let Some(format) = fileio::D88FileIO::image_format_from_path(output_path) {
+                    disk.format = format;
+                    disk.update_offset();
+                    disk.save(Path::new(output_path)).map_err(|_| ())
+                } else {
+                    let img = to_raw(&disk, RAW_FILL);
+                    fs::write(Path::new(output_path), &img)
+                        .map(|_| img.len())
+                        .map_err(|_| ())
+                };
+
+            if let Ok(size) = result {
+                println!("Output  {} ({} byte)", output_path, size);
+            } else {
+                ERROR(format!("Can't write \"{}\"", output_path).as_str());
+            }
+        }
+    }
+
+    /// Read D88 or Raw Image (Helper function)
+    ///
+    fn read(&self, input_path: &str) -> Option<Disk> {
+        if fileio::D88FileIO::image_format_from_path(input_path).is_some()
+            || is_archive_source(input_path)
+        {
+            let d88fileio = fileio::D88FileIO::open(Path::new(input_path));
+            if d88fileio.is_open() {
+                return Some(d88fileio.disk);
+            }
+        } else if let Ok(img) = fs::read(Path::new(input_path)) {
+            let geometry = self.disk_type.as_deref().and_then(Geometry::from_name);
+            match from_raw(&img, geometry) {
+                Ok(disk) => return Some(disk),
+                Err(mes) => {
+                    ERROR(mes.as_str());
+                    return None;
+                }
+            }
+        }
+
+        println!("File Not Found \"{}\"", input_path);
+        None
+    }
+}
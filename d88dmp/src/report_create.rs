@@ -0,0 +1,77 @@
+use std::path::Path;
+
+use D88FileIO::filesystem::from_name;
+use D88FileIO::raw::{blank, Geometry};
+
+use crate::utility::ERROR;
+
+/// ReportCreate
+///
+/// 空のディスクイメージを作る。`--fs` 指定時はファイルシステムを作る。
+///
+pub struct ReportCreate {
+    pub output_path: Option<String>,
+    pub disk_type: Option<String>,
+    pub fs_name: Option<String>,
+    pub disk_name: Option<String>,
+}
+
+/// Fill Byte of Blank Sectors
+const BLANK_FILL: u8 = 0xe5;
+
+impl ReportCreate {
+    /// Constructor
+    ///
+    pub fn new(_cmdline_info: clap::ArgMatches) -> Self {
+        Self {
+            output_path: _cmdline_info
+                .value_of("OUTPUT")
+                .map(|path| path.to_string()),
+            disk_type: _cmdline_info
+                .value_of("DISK TYPE")
+                .map(|disk_type| disk_type.to_string()),
+            fs_name: _cmdline_info.value_of("FS").map(|name| name.to_string()),
+            disk_name: _cmdline_info.value_of("NAME").map(|name| name.to_string()),
+        }
+    }
+
+    /// Report
+    ///
+    /// # Argument
+    ///   * (none)
+    ///
+    pub fn report(&self) {
+        if let Some(ref output_path) = self.output_path {
+            let geometry = match self.disk_type.as_deref().and_then(Geometry::from_name) {
+                Some(geometry) => geometry,
+                None => {
+                    ERROR("Unknown disk type");
+                    return;
+                }
+            };
+            let mut disk = blank(&geometry, BLANK_FILL);
+
+            if let Some(ref disk_name) = self.disk_name {
+                let name = disk_name.as_bytes();
+                if name.len() > 16 {
+                    ERROR(format!("\"{}\"? Disk name is up to 16 byte", disk_name).as_str());
+                    return;
+                }
+                disk.header.disk_name[..name.len()].copy_from_slice(name);
+            }
+
+            if let Some(fs) = self.fs_name.as_deref().and_then(from_name) {
+                if let Err(mes) = fs.format(&mut disk) {
+                    ERROR(mes.as_str());
+                    return;
+                }
+            }
+
+            if let Ok(disk_size) = disk.save(Path::new(output_path)) {
+                println!("Output  {} ({} byte)", output_path, disk_size);
+            } else {
+                ERROR(format!("Can't write \"{}\"", output_path).as_str());
+            }
+        }
+    }
+}
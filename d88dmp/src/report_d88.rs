@@ -1,85 +1,165 @@
-use ansi_term::Color;
-use std::mem;
+use serde::Serialize;
+use std::io::{self, Write};
 use std::path::Path;
 
-use ::D88FileIO::format::{D88_Header, D88_SectorHdr};
-use ::D88FileIO::sector::Sector;
-use ::D88FileIO::track::Track;
-use D88FileIO::fileio::D88FileIO;
-
-use crate::cli::get_str_to_u8;
+use D88FileIO::archive::read_source;
+use D88FileIO::boot::Boot;
+use D88FileIO::charset::Charset;
+use D88FileIO::fileio;
+use D88FileIO::filesystem::detect;
+use D88FileIO::report::layout::HexLayout;
+use D88FileIO::report::style::{Ansi, Plain, Style};
+use D88FileIO::report::{HexDump, Report};
+use D88FileIO::sector::Sector;
+use D88FileIO::select::Selection;
+
+use crate::cli::{get_charset, get_layout, get_sector_layout, get_selection};
+use crate::structured::{
+    print_structured, BootDoc, DataEncoding, DiskDoc, OutputFormat, SectorDoc,
+};
+
+/// "info" Row (CSV)
+///
+#[derive(Debug, Serialize)]
+struct InfoRow {
+    path: String,
+    disk_name: String,
+    write_protect: u8,
+    disk_type: u8,
+    disk_size: u32,
+    image_format: String,
+    filesystem: Option<String>,
+    boot: String,
+}
 
 /// ReportD88
 ///
 /// D88ファイル情報を表示。
 ///
-pub struct Position {
-    track: u8,
-    side: u8,
-    sector: u8,
-}
-
+/// 表示する内容
+///
+///   * `info_flg`    ヘッダのサマリとトラックオフセットテーブル
+///   * `sectors_flg` セクタのサマリ
+///   * `data_flg`    ヘッダとセクタのバイトイメージ
+///   * `noinfo_flg`  ファイル全体の16進ダンプ(他の指定より優先)
+///
 pub struct ReportD88 {
     pub path: Option<String>,
     pub noinfo_flg: bool,
     pub nocolor_flg: bool,
-    pub summary_only_flg: bool,
+    pub info_flg: bool,
+    pub sectors_flg: bool,
+    pub data_flg: bool,
     pub sort_by_sector: bool,
-    pub verbose_flg: bool,
+    pub format: OutputFormat,
+    pub data_encoding: DataEncoding,
+    pub charset: Charset,
+    pub layout: HexLayout,
 
-    pub position: Option<Position>,
-    pub d88fileio: D88FileIO,
+    pub selection: Selection,
+    pub d88fileio: fileio::D88FileIO,
 }
 
 impl ReportD88 {
-    /// Constructor
+    /// Constructor (Top Level Options)
+    ///
+    /// サブコマンド無しの互換動作。
+    ///
+    ///   * (none)      Data
+    ///   * `-v`        Info, Sectors and Data
+    ///   * `--summary` Info and Sectors
+    ///   * `-n`        Hex Dump of the whole File
     ///
     pub fn new(_cmdline_info: clap::ArgMatches) -> Self {
-        // Get Command Line Option
-        //
-        let _path = if let Some(path) = _cmdline_info.value_of("*.D88") {
-            Some(path.to_string())
-        } else {
-            None
-        };
-
-        let _noinfo_flg: bool = _cmdline_info.is_present("no-info");
-        let _nocolor_flg: bool = _cmdline_info.is_present("no-color");
         let _summary_only_flg: bool = _cmdline_info.is_present("summary");
         let _verbose_flg: bool = _cmdline_info.is_present("verbose");
-        let mut _sort_by_sector: bool = _cmdline_info.is_present("Sort by Disk Sector Order");
 
-        let _position = if let Some(pos) = _cmdline_info.value_of("TRACK,SIDE,SECTOR") {
-            let pos_str: Vec<&str> = pos.split(',').collect();
+        Self {
+            noinfo_flg: _cmdline_info.is_present("no-info"),
+            info_flg: _summary_only_flg || _verbose_flg,
+            sectors_flg: _summary_only_flg || _verbose_flg,
+            data_flg: !_summary_only_flg,
+            ..ReportD88::new_dump(_cmdline_info)
+        }
+    }
 
-            let track: Result<u8, ()> = get_str_to_u8(pos_str[0], "Not Track Number");
-            let side: Result<u8, ()> = get_str_to_u8(pos_str[1], "Not Side Number");
-            let sector: Result<u8, ()> = get_str_to_u8(pos_str[2], "Not Sector Number");
+    /// Constructor ("dump")
+    ///
+    pub fn new_dump(_cmdline_info: clap::ArgMatches) -> Self {
+        let _verbose_flg: bool = _cmdline_info.is_present("verbose");
 
-            // Sort by Sector Order
-            _sort_by_sector = true;
+        Self {
+            info_flg: _verbose_flg,
+            sectors_flg: _verbose_flg,
+            data_flg: true,
+            sort_by_sector: _cmdline_info.is_present("sort"),
+            data_encoding: DataEncoding::from_cmdline(&_cmdline_info),
+            layout: get_sector_layout(&_cmdline_info),
+            selection: get_selection(&_cmdline_info),
+            ..ReportD88::new_info(_cmdline_info)
+        }
+    }
 
-            Some(Position {
-                track: track.unwrap(),
-                side: side.unwrap(),
-                sector: (sector.unwrap() - 1),
-            })
-        } else {
-            None
-        };
+    /// Constructor ("info")
+    ///
+    pub fn new_info(_cmdline_info: clap::ArgMatches) -> Self {
+        Self {
+            charset: get_charset(&_cmdline_info),
+            ..ReportD88::new_common(_cmdline_info)
+        }
+    }
 
-        //
-        //
+    /// Constructor (Common Options of "info", "sectors")
+    ///
+    fn new_common(_cmdline_info: clap::ArgMatches) -> Self {
         Self {
-            path: _path,
-            noinfo_flg: _noinfo_flg,
-            nocolor_flg: _nocolor_flg,
-            sort_by_sector: _sort_by_sector,
-            summary_only_flg: _summary_only_flg,
-            verbose_flg: _verbose_flg,
-            position: _position,
+            path: _cmdline_info.value_of("*.D88").map(|path| path.to_string()),
+            noinfo_flg: false,
+            nocolor_flg: _cmdline_info.is_present("no-color"),
+            info_flg: true,
+            sectors_flg: false,
+            data_flg: false,
+            sort_by_sector: false,
+            format: OutputFormat::from_cmdline(&_cmdline_info),
+            data_encoding: DataEncoding::None,
+            charset: Charset::Ascii,
+            layout: HexLayout::default(),
+            selection: Selection::default(),
+
+            d88fileio: fileio::D88FileIO::default(),
+        }
+    }
 
-            d88fileio: D88FileIO::default(),
+    /// Constructor ("sectors")
+    ///
+    pub fn new_sectors(_cmdline_info: clap::ArgMatches) -> Self {
+        Self {
+            info_flg: false,
+            sectors_flg: true,
+            sort_by_sector: _cmdline_info.is_present("sort"),
+            selection: get_selection(&_cmdline_info),
+            ..ReportD88::new_common(_cmdline_info)
+        }
+    }
+
+    /// Constructor ("hexdump")
+    ///
+    pub fn new_hexdump(_cmdline_info: clap::ArgMatches) -> Self {
+        Self {
+            path: _cmdline_info.value_of("*.D88").map(|path| path.to_string()),
+            noinfo_flg: true,
+            nocolor_flg: _cmdline_info.is_present("no-color"),
+            info_flg: false,
+            sectors_flg: false,
+            data_flg: false,
+            sort_by_sector: false,
+            format: OutputFormat::Text,
+            data_encoding: DataEncoding::None,
+            charset: get_charset(&_cmdline_info),
+            layout: get_layout(&_cmdline_info),
+            selection: Selection::default(),
+
+            d88fileio: fileio::D88FileIO::default(),
         }
     }
 
@@ -91,16 +171,23 @@ impl ReportD88 {
     pub fn report(&mut self) {
         if let Some(ref d88_path) = self.path {
             if self.noinfo_flg {
-                if let Ok(fh) = std::fs::File::open(Path::new(d88_path)) {
-                    self.report_d88_noinfo(fh);
+                // "-", "archive.zip", "archive.zip:disk2.d88"
+                match read_source(d88_path) {
+                    Ok(mut entries) => self.report_d88_noinfo(&entries.remove(0).data),
+                    Err(mes) => println!("{}", mes),
+                }
+            } else if !self.format.is_text() {
+                self.d88fileio = fileio::D88FileIO::open(Path::new(d88_path));
+                if self.d88fileio.is_open() {
+                    self.report_structured(d88_path);
                 } else {
                     println!("File Not Found \"{}\"", d88_path);
                 }
             } else {
-                self.d88fileio = D88FileIO::open(Path::new(d88_path));
+                self.d88fileio = fileio::D88FileIO::open(Path::new(d88_path));
                 if self.d88fileio.is_open() {
                     //
-                    if self.sort_by_sector || self.position.is_some() {
+                    if self.sort_by_sector {
                         self.d88fileio.sector_sort();
                     }
 
@@ -125,294 +212,135 @@ impl ReportD88 {
     ///  * (none)
     ///
     pub fn report_d88(&self) {
-        if let Some(ref position) = self.position {
-            // Report One Sector
-            let sector = &self.d88fileio.disk.track_tbl
-                [((position.track * 2) + position.side) as usize]
-                .sector_tbl[position.sector as usize];
+        let report = Report::new(&self.d88fileio.disk, self.get_hex_dump());
+        let _ = self.write_d88(&report, &mut io::stdout());
+    }
 
-            self.print_offset_bar();
-            self.report_sector(sector);
+    /// Write D88 File (Helper function)
+    ///
+    /// `report_d88` から呼び出される内部関数。
+    ///
+    fn write_d88<W: Write>(&self, report: &Report, w: &mut W) -> io::Result<()> {
+        // Summary
+        if self.info_flg {
+            report.write_info(w)?;
+        }
+        if self.sectors_flg {
+            if self.info_flg {
+                writeln!(w)?;
+                writeln!(w)?;
+            }
+            report.write_sector_summary(w, &self.get_summary_sectors())?;
+        }
+
+        // Byte Image
+        if !self.data_flg {
+            return Ok(());
+        }
+
+        if self.selection.is_all() {
+            writeln!(w)?;
+            writeln!(w)?;
+            report.write_dump(w)
         } else {
-            // Summary
-            if self.summary_only_flg || self.verbose_flg {
-                let _ = self.report_d88_summary();
+            let selected = self.selection.select(&self.d88fileio.disk);
+            if selected.is_empty() {
+                return writeln!(w, "Sector Not Found");
             }
 
-            // Byte Image
-            if self.summary_only_flg {
-                return;
+            if self.info_flg || self.sectors_flg {
+                writeln!(w)?;
+                writeln!(w)?;
             }
-            println!();
-            println!();
-            let _ = self.report_d88_header();
-            for track in self.d88fileio.disk.track_tbl.iter() {
-                self.report_track(track);
+            report.dump.write_offset_bar(w)?;
+            for (_, sector) in selected {
+                report.write_sector(w, sector)?;
             }
+            Ok(())
         }
     }
 
-    pub fn report_track(&self, track: &Track) {
-        for sector in track.sector_tbl.iter() {
-            self.report_sector(sector);
-        }
-    }
-
-    pub fn report_sector(&self, sector: &Sector) {
-        self.print_sector(sector);
-    }
-
-    /// Report D88 Summary (Helper function)
+    /// Sectors of the Sector Summary (Helper function)
     ///
-    /// `report_d88` から呼び出される内部関数。  
-    /// D88ファイルのサマリ情報を表示する
+    /// `-p` 等の指定が無ければ全セクタ(トラック順)。
     ///
-    /// # Argument
-    ///
-    ///   * `reader` BufferReader instance at D88 File
-    ///
-    /// # Return
-    ///
-    ///   * if Success, return D88 Header Size.  
-    ///   * if Error, return 0
-    ///
-    #[allow(clippy::format_in_format_args)]
-    pub fn report_d88_summary(&self) -> usize {
-        // Get File Header
-        //
-        let header = &(self.d88fileio.disk.header);
-
-        // ----------------------------------------
-        // Report File Header Summary
-        // ----------------------------------------
-        self.print_d88_file_header_title_bar();
-        println!("  {}", self.d88fileio.disk.get_disk_name());
-        println!("  Protect{}", self.d88fileio.disk.get_disk_write_protect());
-        println!("  {}", self.d88fileio.disk.get_disk_type());
-        println!("  {}", self.d88fileio.disk.get_disk_size());
-        println!();
-        println!();
-
-        // ----------------------------------------
-        // Report Track Offset Table
-        // ----------------------------------------
-        self.print_track_offset_table_bar();
-        for n in 0..164 {
-            // format
-            let track_num_formated = format!("{0:2x}h {0:3}d", n);
-            let track_offset_formated = if header.track_offset_tbl[n] == 0 {
-                "------".to_string()
-            } else {
-                format!("{:06x}", header.track_offset_tbl[n])
-            };
-
-            //
-            if (n % 8) == 0 {
-                println!();
-
-                if self.nocolor_flg {
-                    print!("{}  {} ", track_num_formated, track_offset_formated);
-                } else {
-                    print!(
-                        "{}  {} ",
-                        Color::Cyan.paint(track_num_formated),
-                        track_offset_formated
-                    );
-                }
-            } else {
-                print!("{} ", track_offset_formated);
-            }
+    fn get_summary_sectors(&self) -> Vec<(usize, &Sector)> {
+        if self.selection.is_all() {
+            self.d88fileio
+                .disk
+                .track_tbl
+                .iter()
+                .enumerate()
+                .flat_map(|(idx, track)| track.sector_tbl.iter().map(move |sector| (idx, sector)))
+                .collect()
+        } else {
+            self.selection.select(&self.d88fileio.disk)
         }
-        println!();
-
-        // ----------------------------------------
-        // Report All Sector Summary
-        // ----------------------------------------
-        println!();
-        println!();
-
-        self.print_sector_summary_bar();
-
-        for track in self.d88fileio.disk.track_tbl.iter() {
-            for (sector_ct, sector) in track.sector_tbl.iter().enumerate() {
-                //
-                let tso_formated = if sector_ct == 0 {
-                    format!(
-                        "{0:02x}h {0:3}d {1:3}  {2:3} {3:3} ",
-                        sector.header.track,
-                        sector.header.side,
-                        sector.header.sector,
-                        track.number_of_sector
-                    )
-                } else {
-                    format!("... .... ...  {:3} ... ", sector.header.sector)
-                };
-
-                if !self.nocolor_flg {
-                    print!("{} ", Color::Cyan.paint(&tso_formated));
-                } else {
-                    print!("{} ", tso_formated);
-                }
-
-                //
-                let offset_formated = format!("{:06x}h ", sector.offset);
-                if !self.nocolor_flg {
-                    print!("{} ", Color::Cyan.paint(offset_formated));
-                } else {
-                    print!("{} ", offset_formated);
-                }
-
-                println!(
-                    "{}, {}, {}, {}, {}, {}",
-                    sector.get_sector_size(),
-                    sector.get_num_of_sector(),
-                    sector.get_status(),
-                    sector.get_density(),
-                    sector.get_mark(),
-                    sector.get_data_size(),
-                );
-            } // for sector in track.sector_tbl.iter() {
-        } // for track in self.d88fileio.disk.track_tbl.iter() {
-
-        // ----------------------------------------
-        mem::size_of::<D88_Header>()
     }
 
-    /// Report D88 Summary (Helper function)
+    /// Report D88 File as JSON/YAML/CSV
     ///
-    /// `report_d88` から呼び出される内部関数。  
-    /// D88ファイルのヘッダ情報を表示する
+    /// セクタデータはバイトイメージを表示する時だけ出力する。
+    /// `-p` 等の指定時は選んだセクタだけを論理順で出力する。
+    /// "info" のCSVはヘッダを1行で出力する。セクタ一覧のCSVはセクタだけで、ヘッダとトラックオフセットテーブルは含まない。
     ///
     /// # Argument
     ///
-    ///   * `reader` BufferReader instance at D88 File
+    ///  * `d88_path` D88 File Path
     ///
-    /// # Return
-    ///
-    ///   * if Success, return D88 Header Size.  
-    ///   * if Error, return 0
-    ///
-    #[allow(clippy::format_in_format_args)]
-    pub fn report_d88_header(&self) -> usize {
-        // Get File Header
-        //
-        let header = &(self.d88fileio.disk.header);
-
-        // ----------------------------------------
-        // [ByteImage] D88 File Header
-        //   16byte
-        // ----------------------------------------
-        let byte_img;
-        #[allow(clippy::clone_on_copy)]
-        unsafe {
-            byte_img =
-                mem::transmute::<D88_Header, [u8; mem::size_of::<D88_Header>()]>((*header).clone());
+    pub fn report_structured(&self, d88_path: &str) {
+        let disk = &self.d88fileio.disk;
+        let encoding = if self.data_flg {
+            self.data_encoding
+        } else {
+            DataEncoding::None
+        };
+        let mut doc = DiskDoc::new(d88_path, disk, encoding);
+        doc.filesystem = detect(disk).map(|fs| fs.get_name().to_string());
+        let boot = Boot::identify(disk);
+        doc.boot = Some(BootDoc::new(&boot));
+
+        if !self.sectors_flg && !self.data_flg {
+            doc.sectors.clear();
+            let row = InfoRow {
+                path: d88_path.to_string(),
+                disk_name: doc.header.disk_name.clone(),
+                write_protect: doc.header.write_protect,
+                disk_type: doc.header.disk_type,
+                disk_size: doc.header.disk_size,
+                image_format: doc.header.image_format.clone(),
+                filesystem: doc.filesystem.clone(),
+                boot: boot.get_name().to_string(),
+            };
+            print_structured(self.format, &doc, &[row]);
+            return;
         }
-        self.print_offset_bar();
-
-        self.print_16byte(&byte_img, 0x00000_u64, ansi_term::Color::Green); /////////   0 - 16 byte
-        print!("{}", self.d88fileio.disk.get_disk_name());
-        println!();
-
-        self.print_16byte(&byte_img[16..], 0x00010_u64, ansi_term::Color::Green); //   16 - 31 byte
-        print!(
-            "{}, {}, {}",
-            self.d88fileio.disk.get_disk_write_protect(),
-            self.d88fileio.disk.get_disk_type(),
-            self.d88fileio.disk.get_disk_size(),
-        );
-        println!();
-
-        // ----------------------------------------
-        // [ByteImage] Offset to Track
-        //   32bit(4byte) x 164
-        // ----------------------------------------
-        let mut offset = 0x00020_u64;
-        let mut buf32x4 = [0u32; 4];
-        for (ct, track_offset) in header.track_offset_tbl.iter().enumerate() {
-            buf32x4[ct % 4] = *track_offset;
-            if (ct % 4) == 3 {
-                unsafe {
-                    let buf8x16 = mem::transmute::<[u32; 4], [u8; 16]>(buf32x4); // 32bit x 4 --> 8bit x 16
-                    self.print_16byte(&buf8x16, offset, ansi_term::Color::Yellow);
-                }
 
-                print!("TrackOffset ");
-                for ofst in buf32x4.iter() {
-                    let formated = if *ofst == 0 {
-                        "------".to_string()
-                    } else {
-                        format!("{:06x}", ofst)
-                    };
-                    print!("{} ", formated);
-                }
-                println!();
-                offset += 16;
-            }
+        if !self.selection.is_all() {
+            doc.sectors = self
+                .selection
+                .select(disk)
+                .into_iter()
+                .map(|(_, sector)| SectorDoc::new(sector, encoding))
+                .collect();
         }
 
-        // ----------------------------------------
-        mem::size_of::<D88_Header>()
+        print_structured(self.format, &doc, &doc.sectors);
     }
 
-    /// Report Sector (Helper function)
-    ///
-    /// `report_d88` から呼び出される内部関数。  
-    /// セクタを表示する。  
-    ///
-    /// # Arguments
-    ///   * `reader` BufferReader instance at D88 File
-    ///   * `offset_` Offset to a Sector at D88 Disk File
-    ///
-    /// # Return
-    ///   * if Success, return next offset at D88 File
-    ///   * if Error, retrun 0
+    /// Output Style (Helper function)
     ///
-    pub fn print_sector(&self, sector: &Sector) {
-        let byte_img;
-
-        // Report Sector Header (Byte Image)
-        //
-        #[allow(clippy::clone_on_copy)]
-        unsafe {
-            byte_img = mem::transmute::<D88_SectorHdr, [u8; mem::size_of::<D88_SectorHdr>()]>(
-                sector.header.clone(),
-            );
+    pub fn get_style(&self) -> &'static dyn Style {
+        if self.nocolor_flg {
+            &Plain
+        } else {
+            &Ansi
         }
-        self.print_16byte(
-            &byte_img,
-            sector.offset - mem::size_of::<D88_SectorHdr>() as u64,
-            ansi_term::Color::Green,
-        );
-
-        // Print Sector Header
-        //
-        print!(
-            "{}, {}, {}, {}, {}, {}, {}, {}, {}",
-            sector.get_track(),
-            sector.get_side(),
-            sector.get_sector(),
-            sector.get_sector_size(),
-            sector.get_num_of_sector(),
-            sector.get_status(),
-            sector.get_density(),
-            sector.get_mark(),
-            sector.get_data_size(),
-        );
-        println!();
+    }
 
-        // Print Sector Raw Data
-        //
-        let mut ct = sector.header.size_of_data;
-        let mut offset = sector.offset;
-        let mut pt = 0;
-        while ct > 0 {
-            let aa = &sector.data[pt..pt + 16];
-            self.print_16byte(aa, offset, ansi_term::Color::White);
-            println!();
-            offset += 16;
-            pt += 16;
-            ct -= 16;
-        }
+    /// Hex Dump (Helper function)
+    ///
+    pub fn get_hex_dump(&self) -> HexDump<'static> {
+        HexDump::new(self.charset, self.layout, self.get_style())
     }
 }
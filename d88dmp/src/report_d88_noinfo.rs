@@ -1,28 +1,75 @@
-use std::io::Read;
+use std::io::{self, Write};
+
+use D88FileIO::report::style::Color;
 
 use crate::report_d88::ReportD88;
 
 impl ReportD88 {
-    pub fn report_d88_noinfo(&mut self, mut fh: std::fs::File) {
-        let mut buffer = Vec::<u8>::new();
+    /// Hex Dump of the whole File
+    ///
+    /// # Argument
+    ///
+    ///  * `data` File Image ("-" and archives are already read by `read_source()`)
+    ///
+    pub fn report_d88_noinfo(&self, data: &[u8]) {
+        let _ = self.write_d88_noinfo(&mut io::stdout(), data);
+    }
+
+    /// Write Hex Dump of the whole File
+    ///
+    pub fn write_d88_noinfo<W: Write>(&self, w: &mut W, data: &[u8]) -> io::Result<()> {
+        let dump = self.get_hex_dump();
+        dump.write_offset_bar(w)?;
+        dump.write_data(w, data, 0, Color::White)
+    }
+}
+
+// ================================================================================
+//
+//  Test Code
+//
+// ================================================================================
+#[cfg(test)]
+mod test {
+    use clap::Arg;
+    use D88FileIO::archive::read_source;
 
-        if let Ok(size) = fh.read_to_end(&mut buffer) {
-            let mut ofst: usize = 0;
-            let mut buf16: [u8; 16] = [0; 16];
+    use crate::cli::{get_charset_arg, get_d88_arg, get_layout_args};
+    use crate::report_d88::ReportD88;
 
-            self.print_offset_bar();
+    const HUBASIC: &str = "../sample/HuBASIC_Format_2D.d88";
+    const HUBASIC_LZH: &str = "../sample/HuBASIC_Format_2D.lzh";
 
-            while ofst < size {
-                //for idx in 0..16 {
-                //    buf16[idx] = buffer[ofst + idx];
-                //}
-                buf16[..16].clone_from_slice(&buffer[ofst..(16 + ofst)]);
+    fn hexdump(source: &str) -> Result<String, String> {
+        let matches = clap::Command::new("hexdump")
+            .arg(get_d88_arg())
+            .arg(Arg::new("no-color").long("no-color"))
+            .arg(get_charset_arg())
+            .args(get_layout_args())
+            .get_matches_from(["hexdump", "--no-color", source]);
+        let report = ReportD88::new_hexdump(matches);
+
+        let mut entries = read_source(source)?;
+        let mut out = Vec::new();
+        report
+            .write_d88_noinfo(&mut out, &entries.remove(0).data)
+            .unwrap();
+        Ok(String::from_utf8(out).unwrap())
+    }
 
-                self.print_16byte(&buf16, ofst as u64, ansi_term::Color::White);
-                println!();
+    #[test]
+    fn test_hexdump_source() {
+        let plain = hexdump(HUBASIC).unwrap();
+        assert_eq!(plain.lines().count(), 2 + (348848 / 16)); // Offset Bar (2 lines)
 
-                ofst += 16;
-            }
-        }
+        assert_eq!(hexdump(HUBASIC_LZH).unwrap(), plain);
+        assert_eq!(
+            hexdump(&format!("{}:HUBASIC.D88", HUBASIC_LZH)).unwrap(),
+            plain
+        );
+        assert_eq!(
+            hexdump("../sample/none.d88"),
+            Err("File Not Found \"../sample/none.d88\"".to_string())
+        );
     }
 }
@@ -0,0 +1,361 @@
+use ansi_term::Color;
+use serde::Serialize;
+use std::path::Path;
+
+use D88FileIO::diff::{diff, DataDiff, DiskDiff, FieldDiff, SectorDiff, SectorKey};
+use D88FileIO::fileio;
+use D88FileIO::sector::Sector;
+
+use crate::structured::{print_structured, OutputFormat};
+
+/// ReportDiff
+///
+/// 2つのD88ファイルを(トラック, サイド, セクタID)で対応付けて比較する。
+///
+pub struct ReportDiff {
+    pub path_a: Option<String>,
+    pub path_b: Option<String>,
+    pub nocolor_flg: bool,
+    pub summary_only_flg: bool,
+    pub format: OutputFormat,
+}
+
+impl ReportDiff {
+    /// Constructor
+    ///
+    pub fn new(_cmdline_info: clap::ArgMatches) -> Self {
+        Self {
+            path_a: _cmdline_info.value_of("A.D88").map(|path| path.to_string()),
+            path_b: _cmdline_info.value_of("B.D88").map(|path| path.to_string()),
+            nocolor_flg: _cmdline_info.is_present("no-color"),
+            summary_only_flg: _cmdline_info.is_present("summary"),
+            format: OutputFormat::from_cmdline(&_cmdline_info),
+        }
+    }
+
+    /// Report
+    ///
+    /// # Argument
+    ///   * (none)
+    ///
+    pub fn report(&self) {
+        if let (Some(ref path_a), Some(ref path_b)) = (&self.path_a, &self.path_b) {
+            let mut d88fileio_a = fileio::D88FileIO::open(Path::new(path_a));
+            let mut d88fileio_b = fileio::D88FileIO::open(Path::new(path_b));
+
+            if !d88fileio_a.is_open() {
+                println!("File Not Found \"{}\"", path_a);
+                return;
+            }
+            if !d88fileio_b.is_open() {
+                println!("File Not Found \"{}\"", path_b);
+                return;
+            }
+            d88fileio_a.sector_sort();
+            d88fileio_b.sector_sort();
+
+            if !self.format.is_text() {
+                let result = diff(&d88fileio_a.disk, &d88fileio_b.disk);
+                self.report_structured(path_a, path_b, &result);
+                return;
+            }
+
+            println!("A: {}", path_a);
+            println!("B: {}", path_b);
+            println!();
+
+            let result = diff(&d88fileio_a.disk, &d88fileio_b.disk);
+            if !self.summary_only_flg {
+                self.report_diff(&result);
+            }
+            self.report_diff_summary(&result);
+        }
+    }
+
+    /// Report as JSON/YAML/CSV (Helper function)
+    ///
+    /// CSVは差分のあるセクタ1つにつき1行。
+    ///
+    fn report_structured(&self, path_a: &str, path_b: &str, result: &DiskDiff) {
+        let sectors: Vec<DiffSector> = if self.summary_only_flg {
+            Vec::new()
+        } else {
+            result.sectors.iter().map(DiffSector::new).collect()
+        };
+
+        let rows: Vec<DiffRow> = sectors
+            .iter()
+            .map(|sector| DiffRow {
+                track: sector.track,
+                side: sector.side,
+                sector: sector.sector,
+                nth: sector.nth,
+                kind: sector.kind,
+                offset_a: sector.offset_a,
+                offset_b: sector.offset_b,
+                fields: sector
+                    .fields
+                    .iter()
+                    .map(|f| f.name)
+                    .collect::<Vec<&str>>()
+                    .join(" "),
+                data_bytes: sector.data.iter().map(|d| d.len).sum(),
+            })
+            .collect();
+
+        let doc = DiffDoc {
+            a: path_a.to_string(),
+            b: path_b.to_string(),
+            same: result.same_count,
+            changed: result.changed_count,
+            only_a: result.only_a_count,
+            only_b: result.only_b_count,
+            header: result.header.iter().map(DiffField::new).collect(),
+            sectors,
+        };
+        print_structured(self.format, &doc, &rows);
+    }
+
+    /// Report Differences (Helper function)
+    ///
+    fn report_diff(&self, result: &DiskDiff) {
+        if !result.header.is_empty() {
+            println!("D88 File Header");
+            for field in result.header.iter() {
+                println!("  A {}", field.a);
+                println!("  B {}", field.b);
+            }
+            println!();
+        }
+
+        for sector_diff in result.sectors.iter() {
+            match sector_diff {
+                SectorDiff::OnlyA { key, a } => {
+                    println!(
+                        "{}  Only in A (A {:06x}h)",
+                        self.get_position(key),
+                        a.offset
+                    );
+                }
+                SectorDiff::OnlyB { key, b } => {
+                    println!(
+                        "{}  Only in B (B {:06x}h)",
+                        self.get_position(key),
+                        b.offset
+                    );
+                }
+                SectorDiff::Changed {
+                    key,
+                    a,
+                    b,
+                    fields,
+                    data,
+                } => {
+                    println!(
+                        "{}  Changed (A {:06x}h, B {:06x}h)",
+                        self.get_position(key),
+                        a.offset,
+                        b.offset
+                    );
+                    for field in fields.iter() {
+                        println!("  A {}", field.a);
+                        println!("  B {}", field.b);
+                    }
+                    self.report_data_diff(a, b, data);
+                }
+            }
+        }
+    }
+
+    /// Report Hex Diff of Sector Data (Helper function)
+    ///
+    /// 差分を含む16byte行だけを、A/Bの順に表示する。
+    ///
+    fn report_data_diff(&self, a: &Sector, b: &Sector, data: &[DataDiff]) {
+        let mut rows: Vec<usize> = Vec::new();
+        for range in data.iter() {
+            for pos in (range.start / 16)..=((range.start + range.len - 1) / 16) {
+                if !rows.contains(&pos) {
+                    rows.push(pos);
+                }
+            }
+        }
+
+        for row in rows {
+            let start = row * 16;
+            self.print_diff_row("A", &a.data, b.data.as_slice(), a.offset, start);
+            self.print_diff_row("B", &b.data, a.data.as_slice(), b.offset, start);
+        }
+    }
+
+    /// Print 16byte Row (Helper function)
+    ///
+    /// `other` と異なるバイトを赤で表示する。
+    ///
+    fn print_diff_row(&self, name: &str, buf: &[u8], other: &[u8], offset: u64, start: usize) {
+        let offset_formated = format!("{:06x}", offset + start as u64);
+        if self.nocolor_flg {
+            print!("  {} {}  ", name, offset_formated);
+        } else {
+            print!("  {} {}  ", name, Color::Cyan.paint(offset_formated));
+        }
+
+        let mut char_pat = String::new();
+        for pos in start..(start + 16) {
+            if let Some(byte) = buf.get(pos) {
+                let byte_formated = format!("{:02x}", byte);
+                if self.nocolor_flg || (other.get(pos) == Some(byte)) {
+                    print!("{} ", byte_formated);
+                } else {
+                    print!("{} ", Color::Red.paint(byte_formated));
+                }
+
+                unsafe {
+                    if libc::isprint(*byte as libc::c_int) != 0 {
+                        char_pat.push(*byte as char);
+                    } else {
+                        char_pat.push('.');
+                    }
+                }
+            } else {
+                print!("   ");
+                char_pat.push(' ');
+            }
+        }
+        println!("{}", char_pat);
+    }
+
+    /// Report Summary (Helper function)
+    ///
+    fn report_diff_summary(&self, result: &DiskDiff) {
+        println!();
+        println!(
+            "Summary  Same({}), Changed({}), OnlyA({}), OnlyB({}), Header({})",
+            result.same_count,
+            result.changed_count,
+            result.only_a_count,
+            result.only_b_count,
+            result.header.len()
+        );
+    }
+
+    fn get_position(&self, key: &SectorKey) -> String {
+        let position = if key.nth == 0 {
+            format!(
+                "Track({}), Side({}), Sector({})",
+                key.track, key.side, key.sector
+            )
+        } else {
+            format!(
+                "Track({}), Side({}), Sector({}#{})",
+                key.track,
+                key.side,
+                key.sector,
+                key.nth + 1
+            )
+        };
+
+        if self.nocolor_flg {
+            position
+        } else {
+            Color::Yellow.paint(position).to_string()
+        }
+    }
+}
+
+/// Disk Difference (Structured Output)
+///
+#[derive(Serialize)]
+struct DiffDoc {
+    a: String,
+    b: String,
+    same: usize,
+    changed: usize,
+    only_a: usize,
+    only_b: usize,
+    header: Vec<DiffField>,
+    sectors: Vec<DiffSector>,
+}
+
+#[derive(Serialize)]
+struct DiffField {
+    name: &'static str,
+    a: String,
+    b: String,
+}
+
+impl DiffField {
+    fn new(field: &FieldDiff) -> Self {
+        Self {
+            name: field.name,
+            a: field.a.clone(),
+            b: field.b.clone(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct DiffRange {
+    start: usize,
+    len: usize,
+}
+
+#[derive(Serialize)]
+struct DiffSector {
+    track: usize,
+    side: usize,
+    sector: u8,
+    nth: usize,
+    kind: &'static str,
+    offset_a: Option<u64>,
+    offset_b: Option<u64>,
+    fields: Vec<DiffField>,
+    data: Vec<DiffRange>,
+}
+
+impl DiffSector {
+    fn new(sector_diff: &SectorDiff) -> Self {
+        let key = sector_diff.key();
+        let (kind, offset_a, offset_b, fields, data): (_, _, _, &[FieldDiff], &[DataDiff]) =
+            match sector_diff {
+                SectorDiff::OnlyA { a, .. } => ("OnlyA", Some(a.offset), None, &[], &[]),
+                SectorDiff::OnlyB { b, .. } => ("OnlyB", None, Some(b.offset), &[], &[]),
+                SectorDiff::Changed {
+                    a, b, fields, data, ..
+                } => ("Changed", Some(a.offset), Some(b.offset), fields, data),
+            };
+
+        Self {
+            track: key.track,
+            side: key.side,
+            sector: key.sector,
+            nth: key.nth,
+            kind,
+            offset_a,
+            offset_b,
+            fields: fields.iter().map(DiffField::new).collect(),
+            data: data
+                .iter()
+                .map(|d| DiffRange {
+                    start: d.start,
+                    len: d.len,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct DiffRow {
+    track: usize,
+    side: usize,
+    sector: u8,
+    nth: usize,
+    kind: &'static str,
+    offset_a: Option<u64>,
+    offset_b: Option<u64>,
+    /// Names of differing header fields (space separated)
+    fields: String,
+    /// Number of differing data bytes
+    data_bytes: usize,
+}
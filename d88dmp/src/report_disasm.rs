@@ -0,0 +1,166 @@
+use std::path::Path;
+
+use D88FileIO::charset::Charset;
+use D88FileIO::fileio;
+use D88FileIO::filesystem::{detect, find_entry, from_name};
+use D88FileIO::select::Selection;
+use D88FileIO::z80::{disassemble, get_labels};
+
+use crate::cli::{get_charset, get_selection};
+use crate::utility::ERROR;
+
+/// ReportDisasm
+///
+/// セクタ(指定が無ければ トラック0 面0 セクタ1 のブートセクタ)かファイルを
+/// Z80 のコードとして逆アセンブルする。
+///
+pub struct ReportDisasm {
+    pub path: Option<String>,
+    pub name: Option<String>,
+    pub fs_name: Option<String>,
+    pub origin: Option<u16>,
+    pub selection: Selection,
+    pub charset: Charset,
+}
+
+impl ReportDisasm {
+    /// Constructor
+    ///
+    pub fn new(_cmdline_info: clap::ArgMatches) -> Self {
+        let _origin = _cmdline_info.value_of("ORIGIN").map(|origin| {
+            parse_addr(origin).unwrap_or_else(|| {
+                ERROR(format!("{}? Not Address (0000-ffff)", origin).as_str());
+                0
+            })
+        });
+
+        let mut _selection = get_selection(&_cmdline_info);
+        if _selection.is_all() {
+            let _ = _selection.parse_position("0,0,1");
+        }
+
+        Self {
+            path: _cmdline_info.value_of("*.D88").map(|path| path.to_string()),
+            name: _cmdline_info.value_of("NAME").map(|name| name.to_string()),
+            fs_name: _cmdline_info.value_of("FS").map(|name| name.to_string()),
+            origin: _origin,
+            selection: _selection,
+            charset: get_charset(&_cmdline_info),
+        }
+    }
+
+    /// Report
+    ///
+    /// # Argument
+    ///   * (none)
+    ///
+    pub fn report(&self) {
+        if let Some(ref d88_path) = self.path {
+            let d88fileio = fileio::D88FileIO::open(Path::new(d88_path));
+            if !d88fileio.is_open() {
+                println!("File Not Found \"{}\"", d88_path);
+                return;
+            }
+
+            let (data, origin) = match self.name {
+                Some(ref name) => match self.read_file(&d88fileio, name) {
+                    Ok(file) => file,
+                    Err(mes) => {
+                        ERROR(mes.as_str());
+                        return;
+                    }
+                },
+                None => {
+                    let data: Vec<u8> = self
+                        .selection
+                        .select(&d88fileio.disk)
+                        .iter()
+                        .flat_map(|(_, sector)| sector.data.iter().copied())
+                        .collect();
+                    (data, self.origin.unwrap_or(0))
+                }
+            };
+            if data.is_empty() {
+                println!("Sector Not Found");
+                return;
+            }
+
+            self.print(&data, origin);
+        }
+    }
+
+    /// Read File and its Load Address (Helper function)
+    ///
+    fn read_file(
+        &self,
+        d88fileio: &fileio::D88FileIO,
+        name: &str,
+    ) -> Result<(Vec<u8>, u16), String> {
+        let fs = match self.fs_name {
+            Some(ref fs_name) => from_name(fs_name),
+            None => detect(&d88fileio.disk),
+        }
+        .ok_or("Unknown filesystem, specify --fs")?;
+
+        let entry = find_entry(&fs.list(&d88fileio.disk)?, name)?;
+        let data = fs.read(&d88fileio.disk, name)?;
+        Ok((data, self.origin.or(entry.load_addr).unwrap_or(0)))
+    }
+
+    /// Print Disassembly (Helper function)
+    ///
+    fn print(&self, data: &[u8], origin: u16) {
+        let instructions = disassemble(data, origin);
+        let labels = get_labels(&instructions);
+
+        println!("Origin  {:04x}  ({} byte)", origin, data.len());
+        println!();
+        println!("Addr  Code         Char  Label    Instruction");
+        println!("----  -----------  ----  -------  -----------");
+        for inst in instructions.iter() {
+            let hex: Vec<String> = inst.bytes.iter().map(|b| format!("{:02x}", b)).collect();
+            let chars: String = self.charset.render(&inst.bytes).concat();
+            let label = labels
+                .get(&inst.addr)
+                .map_or(String::new(), |label| format!("{}:", label));
+            println!(
+                "{:04x}  {:11}  {:4}  {:7}  {}",
+                inst.addr,
+                hex.join(" "),
+                chars,
+                label,
+                inst.get_text_with_label(&labels)
+            );
+        }
+    }
+}
+
+/// Parse Address ("c000", "0xc000", "$c000", "c000h") (Helper function)
+///
+fn parse_addr(s: &str) -> Option<u16> {
+    let hex = s
+        .trim_start_matches("0x")
+        .trim_start_matches("0X")
+        .trim_start_matches('$')
+        .trim_end_matches(['h', 'H']);
+    u16::from_str_radix(hex, 16).ok()
+}
+
+// ================================================================================
+//
+//  Test Code
+//
+// ================================================================================
+#[cfg(test)]
+mod test {
+    use crate::report_disasm::parse_addr;
+
+    #[test]
+    fn test_parse_addr() {
+        assert_eq!(parse_addr("c000"), Some(0xc000));
+        assert_eq!(parse_addr("0xC000"), Some(0xc000));
+        assert_eq!(parse_addr("$8000"), Some(0x8000));
+        assert_eq!(parse_addr("100h"), Some(0x100));
+        assert_eq!(parse_addr("10000"), None);
+    }
+}
@@ -0,0 +1,733 @@
+use std::fs;
+use std::io;
+use std::mem;
+use std::path::Path;
+
+use ratatui::backend::Backend;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use ratatui::{Frame, Terminal};
+
+use D88FileIO::archive::is_archive_source;
+use D88FileIO::edit::{BytePos, Edit, Editor};
+use D88FileIO::fileio;
+use D88FileIO::format::D88_SectorHdr;
+
+use crate::utility::ERROR;
+
+const KEY_HELP: &str =
+    "0-f:Edit  Arrows:Move  PgUp/PgDn:Sector  u/r:Undo/Redo  g:Offset  w:Save  W:Save as  q:Quit";
+
+const HDR_SIZE: usize = mem::size_of::<D88_SectorHdr>();
+
+/// ReportEdit
+///
+/// 全画面のターミナルUIで、セクタヘッダとデータを書き換えてD88ファイルに保存する。
+///
+pub struct ReportEdit {
+    pub path: Option<String>,
+}
+
+impl ReportEdit {
+    /// Constructor
+    ///
+    pub fn new(_cmdline_info: clap::ArgMatches) -> Self {
+        Self {
+            path: _cmdline_info.value_of("*.D88").map(|path| path.to_string()),
+        }
+    }
+
+    /// Report
+    ///
+    /// # Argument
+    ///   * (none)
+    ///
+    pub fn report(&self) {
+        if let Some(ref d88_path) = self.path {
+            let d88fileio = fileio::D88FileIO::open(Path::new(d88_path));
+            if !d88fileio.is_open() {
+                println!("File Not Found \"{}\"", d88_path);
+                return;
+            }
+
+            let mut hex_editor = match HexEditor::new(d88fileio, d88_path) {
+                Ok(hex_editor) => hex_editor,
+                Err(mes) => {
+                    println!("{}", mes);
+                    return;
+                }
+            };
+            let mut terminal = ratatui::init();
+            let result = hex_editor.run(&mut terminal);
+            ratatui::restore();
+
+            if let Err(err) = result {
+                ERROR(format!("{}", err).as_str());
+            }
+        }
+    }
+}
+
+/// Input Mode
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Mode {
+    Normal,
+    /// File Offset Input
+    Offset(String),
+    /// Path Input for "Save as"
+    SaveAs(String),
+    /// Confirm to Save (Path)
+    Confirm(String),
+}
+
+/// Sector Hex Editor
+///
+/// 画面の状態とキー操作。描画は `draw()` で、任意の `Backend` に出力できる。
+///
+pub struct HexEditor {
+    editor: Editor,
+    path: String,
+    sectors: Vec<(usize, usize)>,
+    current: usize,
+    pos: usize,
+    high_nibble: Option<u8>,
+    scroll: usize,
+    mode: Mode,
+    message: String,
+    quit_requested: bool,
+    quit: bool,
+}
+
+impl HexEditor {
+    /// Constructor
+    ///
+    /// # Argument
+    ///
+    ///   * `d88fileio` D88FileIO
+    ///   * `path`      D88 File Path (to Save)
+    ///
+    /// # Return
+    ///
+    ///   * Ok(HexEditor)
+    ///   * Err(String)  Error Message (no sector to edit)
+    ///
+    pub fn new(d88fileio: fileio::D88FileIO, path: &str) -> Result<Self, String> {
+        let disk = d88fileio.disk;
+        let sectors = disk
+            .track_tbl
+            .iter()
+            .enumerate()
+            .flat_map(|(track_idx, track)| {
+                (0..track.sector_tbl.len()).map(move |sector_idx| (track_idx, sector_idx))
+            })
+            .collect::<Vec<(usize, usize)>>();
+        if sectors.is_empty() {
+            return Err(format!("Sector Not Found \"{}\"", path));
+        }
+
+        Ok(Self {
+            editor: Editor::new(disk),
+            path: path.to_string(),
+            sectors,
+            current: 0,
+            pos: HDR_SIZE,
+            high_nibble: None,
+            scroll: 0,
+            mode: Mode::Normal,
+            message: String::new(),
+            quit_requested: false,
+            quit: false,
+        })
+    }
+
+    /// Run until Quit
+    ///
+    pub fn run<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> io::Result<()> {
+        while !self.quit {
+            terminal.draw(|frame| self.draw(frame))?;
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    self.handle_key(key);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Key Input
+    ///
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        self.message.clear();
+
+        match self.mode.clone() {
+            Mode::Normal => self.handle_normal_key(key),
+            Mode::Offset(text) => {
+                if let Some(text) = self.input_key(key, text) {
+                    self.jump_to_input(&text);
+                }
+            }
+            Mode::SaveAs(text) => {
+                if let Some(text) = self.input_key(key, text) {
+                    if !text.is_empty() {
+                        self.mode = Mode::Confirm(text);
+                    }
+                }
+            }
+            Mode::Confirm(path) => {
+                self.mode = Mode::Normal;
+                if key.code == KeyCode::Char('y') {
+                    self.save(&path);
+                } else {
+                    self.message = "Not saved".to_string();
+                }
+            }
+        }
+    }
+
+    /// Key Input in Normal Mode (Helper function)
+    ///
+    fn handle_normal_key(&mut self, key: KeyEvent) {
+        let quit_requested = self.quit_requested;
+        self.quit_requested = false;
+
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char(c) if !ctrl && c.is_ascii_hexdigit() => {
+                self.input_nibble(c.to_digit(16).unwrap_or(0) as u8)
+            }
+            KeyCode::Esc => self.high_nibble = None,
+            KeyCode::Left => self.move_cursor(-1),
+            KeyCode::Right => self.move_cursor(1),
+            KeyCode::Up => self.move_cursor(-16),
+            KeyCode::Down => self.move_cursor(16),
+            KeyCode::Home => self.move_cursor(-(self.view_len() as isize)),
+            KeyCode::End => self.move_cursor(self.view_len() as isize),
+            KeyCode::PageDown => self.move_sector(true),
+            KeyCode::PageUp => self.move_sector(false),
+            KeyCode::Char('u') => self.undo(),
+            KeyCode::Char('z') if ctrl => self.undo(),
+            KeyCode::Char('r') => self.redo(),
+            KeyCode::Char('y') if ctrl => self.redo(),
+            KeyCode::Char('g') => self.mode = Mode::Offset(String::new()),
+            KeyCode::Char('w') => {
+                if self.editor.changes().is_empty() {
+                    self.message = "No changes".to_string();
+                } else {
+                    self.mode = Mode::Confirm(self.path.clone());
+                }
+            }
+            KeyCode::Char('W') => self.mode = Mode::SaveAs(self.path.clone()),
+            KeyCode::Char('q') => {
+                if quit_requested || self.editor.changes().is_empty() {
+                    self.quit = true;
+                } else {
+                    self.quit_requested = true;
+                    self.message = "Unsaved changes. Press q again to quit".to_string();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Line Input (Helper function)
+    ///
+    /// # Return
+    ///
+    ///   * Some(String)  Entered Text
+    ///   * None          Editing (or Canceled)
+    ///
+    fn input_key(&mut self, key: KeyEvent, mut text: String) -> Option<String> {
+        let mode = |text: String| match self.mode {
+            Mode::Offset(_) => Mode::Offset(text),
+            _ => Mode::SaveAs(text),
+        };
+
+        match key.code {
+            KeyCode::Enter => {
+                self.mode = Mode::Normal;
+                return Some(text);
+            }
+            KeyCode::Esc => self.mode = Mode::Normal,
+            KeyCode::Backspace => {
+                text.pop();
+                self.mode = mode(text);
+            }
+            KeyCode::Char(c) => {
+                text.push(c);
+                self.mode = mode(text);
+            }
+            _ => {}
+        }
+        None
+    }
+
+    /// Draw Screen
+    ///
+    pub fn draw(&mut self, frame: &mut Frame) {
+        let [header_area, hex_area, status_area] = Layout::vertical([
+            Constraint::Length(6),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        // Header Details
+        //
+        let (track_idx, sector_idx) = self.sectors[self.current];
+        let disk = &self.editor.disk;
+        let sector = &disk.track_tbl[track_idx].sector_tbl[sector_idx];
+        let (track_no, side_no) = disk.track_position(track_idx);
+        let header_lines = vec![
+            Line::from(format!(
+                "{}  Track {} Side {}  [{}/{}]  {} changed sector(s)",
+                self.path,
+                track_no,
+                side_no,
+                self.current + 1,
+                self.sectors.len(),
+                self.editor.changes().len()
+            )),
+            Line::from(format!(
+                "{}, {}, {}, {}, {}",
+                sector.get_track(),
+                sector.get_side(),
+                sector.get_sector(),
+                sector.get_sector_size(),
+                sector.get_num_of_sector()
+            )),
+            Line::from(format!(
+                "{}, {}, {}, {}",
+                sector.get_status(),
+                sector.get_density(),
+                sector.get_mark(),
+                sector.get_data_size()
+            )),
+            Line::from(format!(
+                "Cursor 0x{:06x}  {}",
+                self.file_offset(self.pos),
+                match self.byte_pos(self.pos) {
+                    BytePos::Header(idx) => format!("Header +{:x}", idx),
+                    BytePos::Data(idx) => format!("Data +{:x}", idx),
+                }
+            )),
+        ];
+        let header = Paragraph::new(header_lines)
+            .block(Block::default().borders(Borders::ALL).title("Sector"));
+        frame.render_widget(header, header_area);
+
+        // Hex View
+        //
+        let hex_height = hex_area.height.saturating_sub(2) as usize;
+        let line = self.pos / 16;
+        if line < self.scroll {
+            self.scroll = line;
+        } else if hex_height > 0 && line >= self.scroll + hex_height {
+            self.scroll = line + 1 - hex_height;
+        }
+
+        let hex_lines: Vec<Line> = (0..self.view_len().div_ceil(16))
+            .map(|line| self.hex_line(line))
+            .collect();
+        let hex = Paragraph::new(hex_lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Offset  +0 +1 +2 +3 +4 +5 +6 +7 +8 +9 +a +b +c +d +e +f"),
+            )
+            .scroll((self.scroll as u16, 0));
+        frame.render_widget(hex, hex_area);
+
+        // Confirmation
+        //
+        if let Mode::Confirm(ref path) = self.mode {
+            let mut lines = vec![Line::from(format!("Save to \"{}\"", path)), Line::from("")];
+            for change in self.editor.changes().iter() {
+                let sector =
+                    &self.editor.disk.track_tbl[change.track_idx].sector_tbl[change.sector_idx];
+                let (track_no, side_no) = self.editor.disk.track_position(change.track_idx);
+                lines.push(Line::from(format!(
+                    "Track {:3} Side {} Sector {:3}  Header {:2} byte, Data {:4} byte  (0x{:06x})",
+                    track_no,
+                    side_no,
+                    sector.header.sector,
+                    change.header_bytes,
+                    change.data_bytes,
+                    sector.offset - HDR_SIZE as u64
+                )));
+            }
+            let popup = Paragraph::new(lines).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Changed Sectors"),
+            );
+            frame.render_widget(Clear, hex_area);
+            frame.render_widget(popup, hex_area);
+        }
+
+        // Status Line
+        //
+        let status = match self.mode {
+            Mode::Offset(ref text) => format!("File Offset (hex): {}_", text),
+            Mode::SaveAs(ref text) => format!("Save as: {}_", text),
+            Mode::Confirm(_) => "Save? (y/n)".to_string(),
+            Mode::Normal if !self.message.is_empty() => self.message.clone(),
+            Mode::Normal => KEY_HELP.to_string(),
+        };
+        frame.render_widget(Paragraph::new(status), status_area);
+    }
+
+    /// Hex View Line (Helper function)
+    ///
+    /// 1行目がセクタヘッダ、2行目以降がデータ。書き換えたバイトは赤、カーソルは反転で表示する。
+    ///
+    fn hex_line(&self, line: usize) -> Line<'static> {
+        let (track_idx, sector_idx) = self.sectors[self.current];
+        let start = line * 16;
+        let end = (start + 16).min(self.view_len());
+
+        let style = |pos: usize| {
+            let bytepos = self.byte_pos(pos);
+            let mut style = if self.editor.is_modified(track_idx, sector_idx, bytepos) {
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+            } else if line == 0 {
+                Style::default().fg(Color::Green)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            if pos == self.pos {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+            style
+        };
+
+        let mut spans = vec![Span::styled(
+            format!("{:06x}  ", self.file_offset(start)),
+            Style::default().fg(Color::Cyan),
+        )];
+        let mut chars = Vec::new();
+        for pos in start..end {
+            let byte = self
+                .editor
+                .get_byte(track_idx, sector_idx, self.byte_pos(pos))
+                .unwrap_or(0);
+            let text = match self.high_nibble {
+                Some(high) if pos == self.pos => format!("{:x}_", high),
+                _ => format!("{:02x}", byte),
+            };
+            spans.push(Span::styled(text, style(pos)));
+            spans.push(Span::raw(" "));
+
+            let c = if byte.is_ascii_graphic() || byte == b' ' {
+                byte as char
+            } else {
+                '.'
+            };
+            chars.push(Span::styled(c.to_string(), style(pos)));
+        }
+        spans.push(Span::raw("   ".repeat(16 - (end - start))));
+        spans.extend(chars);
+        Line::from(spans)
+    }
+
+    /// Number of Bytes in View (Header + Data) (Helper function)
+    ///
+    fn view_len(&self) -> usize {
+        let (track_idx, sector_idx) = self.sectors[self.current];
+        HDR_SIZE
+            + self.editor.disk.track_tbl[track_idx].sector_tbl[sector_idx]
+                .data
+                .len()
+    }
+
+    /// View Position to Byte Position (Helper function)
+    ///
+    fn byte_pos(&self, pos: usize) -> BytePos {
+        if pos < HDR_SIZE {
+            BytePos::Header(pos)
+        } else {
+            BytePos::Data(pos - HDR_SIZE)
+        }
+    }
+
+    /// View Position to File Offset (Helper function)
+    ///
+    fn file_offset(&self, pos: usize) -> u64 {
+        let (track_idx, sector_idx) = self.sectors[self.current];
+        let sector = &self.editor.disk.track_tbl[track_idx].sector_tbl[sector_idx];
+        sector.offset - HDR_SIZE as u64 + pos as u64
+    }
+
+    /// Input a Hex Digit (Helper function)
+    ///
+    /// 上位、下位の順に入力し、2桁そろったら書き換えて次のバイトへ進む。
+    ///
+    fn input_nibble(&mut self, digit: u8) {
+        let high = if let Some(high) = self.high_nibble.take() {
+            high
+        } else {
+            self.high_nibble = Some(digit);
+            return;
+        };
+
+        let (track_idx, sector_idx) = self.sectors[self.current];
+        let bytepos = self.byte_pos(self.pos);
+        match self
+            .editor
+            .set_byte(track_idx, sector_idx, bytepos, (high << 4) | digit)
+        {
+            Ok(()) => self.move_cursor(1),
+            Err(mes) => self.message = mes,
+        }
+    }
+
+    /// Move Cursor in the Sector (Helper function)
+    ///
+    fn move_cursor(&mut self, delta: isize) {
+        self.high_nibble = None;
+        self.pos = self
+            .pos
+            .saturating_add_signed(delta)
+            .min(self.view_len() - 1);
+    }
+
+    /// Next/Previous Sector (Helper function)
+    ///
+    fn move_sector(&mut self, forward: bool) {
+        let current = if forward {
+            (self.current + 1).min(self.sectors.len() - 1)
+        } else {
+            self.current.saturating_sub(1)
+        };
+        self.select_sector(current, HDR_SIZE);
+    }
+
+    /// Select Sector and Position (Helper function)
+    ///
+    fn select_sector(&mut self, current: usize, pos: usize) {
+        self.high_nibble = None;
+        self.current = current;
+        self.pos = pos.min(self.view_len() - 1);
+    }
+
+    /// Undo (Helper function)
+    ///
+    fn undo(&mut self) {
+        let edit = self.editor.undo();
+        self.show_edit(edit, "Undo");
+    }
+
+    /// Redo (Helper function)
+    ///
+    fn redo(&mut self) {
+        let edit = self.editor.redo();
+        self.show_edit(edit, "Redo");
+    }
+
+    /// Show Undone/Redone Edit (Helper function)
+    ///
+    fn show_edit(&mut self, edit: Option<Edit>, name: &str) {
+        if let Some(edit) = edit {
+            if let Some(current) = self
+                .sectors
+                .iter()
+                .position(|entry| *entry == (edit.track_idx, edit.sector_idx))
+            {
+                let pos = match edit.pos {
+                    BytePos::Header(idx) => idx,
+                    BytePos::Data(idx) => HDR_SIZE + idx,
+                };
+                self.select_sector(current, pos);
+            }
+            self.message = format!("{} {:02x} -> {:02x}", name, edit.old, edit.new);
+        } else {
+            self.message = format!("Nothing to {}", name.to_lowercase());
+        }
+    }
+
+    /// Jump to File Offset from Input (Helper function)
+    ///
+    fn jump_to_input(&mut self, text: &str) {
+        let hex = text.trim().trim_start_matches("0x");
+        let offset = if let Ok(offset) = u64::from_str_radix(hex, 16) {
+            offset
+        } else {
+            self.message = format!("\"{}\"? Not Hex Number", text);
+            return;
+        };
+
+        let disk = &self.editor.disk;
+        let found = self.sectors.iter().enumerate().find_map(|(idx, entry)| {
+            let sector = &disk.track_tbl[entry.0].sector_tbl[entry.1];
+            let start = sector.offset - HDR_SIZE as u64;
+            (start..sector.offset + sector.data.len() as u64)
+                .contains(&offset)
+                .then(|| (idx, (offset - start) as usize))
+        });
+        match found {
+            Some((current, pos)) => self.select_sector(current, pos),
+            None => self.message = format!("Offset 0x{:x} is not in any sector", offset),
+        }
+    }
+
+    /// Save Changes (Helper function)
+    ///
+    /// 別名の場合は元のファイルをコピーしてから、変更したセクタを書き込む。
+    /// 以後はそのファイルを編集する。
+    ///
+    fn save(&mut self, path: &str) {
+        if is_archive_source(&self.path) || is_archive_source(path) {
+            self.message = "Can't save into an archive or stdin".to_string();
+            return;
+        }
+        if (path != self.path) && fs::copy(&self.path, path).is_err() {
+            self.message = format!("Can't write \"{}\"", path);
+            return;
+        }
+
+        let result = fs::OpenOptions::new()
+            .write(true)
+            .open(path)
+            .map_err(|_| ())
+            .and_then(|mut fh| self.editor.write_changes(&mut fh));
+        match result {
+            Ok(count) => {
+                self.path = path.to_string();
+                self.message = format!("Saved {} sector(s) to \"{}\"", count, path);
+            }
+            Err(()) => self.message = format!("Can't write \"{}\"", path),
+        }
+    }
+}
+
+// ================================================================================
+//
+//  Test Code
+//
+// ================================================================================
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use ratatui::backend::TestBackend;
+    use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use ratatui::style::Color;
+    use ratatui::Terminal;
+
+    use crate::report_edit::HexEditor;
+    use D88FileIO::fileio;
+
+    const HUBASIC: &str = "../sample/HuBASIC_Format_2D.d88";
+
+    fn keys(hex_editor: &mut HexEditor, text: &str) {
+        for c in text.chars() {
+            hex_editor.handle_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+    }
+
+    fn key(hex_editor: &mut HexEditor, code: KeyCode) {
+        hex_editor.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    fn draw(hex_editor: &mut HexEditor) -> Terminal<TestBackend> {
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        terminal.draw(|frame| hex_editor.draw(frame)).unwrap();
+        terminal
+    }
+
+    fn screen(terminal: &Terminal<TestBackend>) -> Vec<String> {
+        let buffer = terminal.backend().buffer();
+        buffer
+            .content()
+            .chunks(buffer.area.width as usize)
+            .map(|row| row.iter().map(|cell| cell.symbol()).collect::<String>())
+            .collect()
+    }
+
+    #[test]
+    fn test_edit_undo_redo() {
+        let mut hex_editor = HexEditor::new(fileio::D88FileIO::open(HUBASIC), HUBASIC).unwrap();
+
+        keys(&mut hex_editor, "a5");
+        keys(&mut hex_editor, "5");
+        let terminal = draw(&mut hex_editor);
+        let lines = screen(&terminal);
+        let row = lines
+            .iter()
+            .position(|line| line.contains("0002c0  a5 5_"))
+            .unwrap();
+        assert_eq!(terminal.backend().buffer()[(9, row as u16)].fg, Color::Red);
+        assert!(lines[1].contains("1 changed sector(s)"));
+
+        // Header: Sector ID
+        key(&mut hex_editor, KeyCode::Esc);
+        key(&mut hex_editor, KeyCode::Home);
+        key(&mut hex_editor, KeyCode::Right);
+        key(&mut hex_editor, KeyCode::Right);
+        keys(&mut hex_editor, "09");
+        assert!(screen(&draw(&mut hex_editor))[2].contains("Sector(9)"));
+
+        // size_of_data can't be edited
+        keys(&mut hex_editor, "g2be");
+        key(&mut hex_editor, KeyCode::Enter);
+        keys(&mut hex_editor, "00");
+        assert!(screen(&draw(&mut hex_editor))[29].starts_with("Data size"));
+
+        keys(&mut hex_editor, "uu");
+        let lines = screen(&draw(&mut hex_editor));
+        assert!(lines[29].starts_with("Undo") && lines[29].contains("-> a5"));
+        assert!(lines[1].contains("0 changed sector(s)"));
+        keys(&mut hex_editor, "r");
+        assert!(screen(&draw(&mut hex_editor))[1].contains("1 changed sector(s)"));
+
+        // Quit needs confirmation with unsaved changes
+        keys(&mut hex_editor, "q");
+        assert!(!hex_editor.quit);
+        keys(&mut hex_editor, "q");
+        assert!(hex_editor.quit);
+    }
+
+    #[test]
+    fn test_save_as() {
+        let save_path = std::env::temp_dir().join("d88dmp_test_edit_save_as.d88");
+        let save_path = save_path.to_str().unwrap().to_string();
+        let mut hex_editor = HexEditor::new(fileio::D88FileIO::open(HUBASIC), HUBASIC).unwrap();
+
+        keys(&mut hex_editor, "g2d0");
+        key(&mut hex_editor, KeyCode::Enter);
+        keys(&mut hex_editor, "ff");
+
+        keys(&mut hex_editor, "W");
+        for _ in 0..HUBASIC.len() {
+            key(&mut hex_editor, KeyCode::Backspace);
+        }
+        keys(&mut hex_editor, &save_path);
+        key(&mut hex_editor, KeyCode::Enter);
+        let lines = screen(&draw(&mut hex_editor));
+        assert!(lines.iter().any(
+            |line| line.contains("Track   0 Side 0 Sector   1  Header  0 byte, Data    1 byte")
+        ));
+
+        keys(&mut hex_editor, "y");
+        let img = fs::read(HUBASIC).unwrap();
+        let saved = fs::read(&save_path).unwrap();
+        fs::remove_file(&save_path).unwrap();
+
+        let diff: Vec<usize> = (0..img.len()).filter(|i| img[*i] != saved[*i]).collect();
+        assert_eq!(diff, vec![0x2d0]);
+        assert_eq!(saved[0x2d0], 0xff);
+        assert!(screen(&draw(&mut hex_editor))[1].contains("0 changed sector(s)"));
+    }
+
+    #[test]
+    fn test_no_sector() {
+        let mut d88fileio = fileio::D88FileIO::open(HUBASIC);
+        for track in d88fileio.disk.track_tbl.iter_mut() {
+            track.sector_tbl.clear();
+        }
+        assert!(HexEditor::new(d88fileio, HUBASIC).is_err());
+    }
+}
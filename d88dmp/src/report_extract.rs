@@ -0,0 +1,86 @@
+use std::fs;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use D88FileIO::extract::{extract, ExtractOption};
+use D88FileIO::fileio;
+use D88FileIO::select::Selection;
+
+use crate::cli::get_selection;
+use crate::utility::ERROR;
+
+/// ReportExtract
+///
+/// 選んだセクタのデータを論理順にバイナリで書き出す。
+///
+pub struct ReportExtract {
+    pub path: Option<String>,
+    pub output_path: Option<String>,
+    pub selection: Selection,
+    pub option: ExtractOption,
+}
+
+impl ReportExtract {
+    /// Constructor
+    ///
+    pub fn new(_cmdline_info: clap::ArgMatches) -> Self {
+        let _pad = _cmdline_info.value_of("PAD").map(|pad| {
+            let hex = pad.trim_start_matches("0x").trim_start_matches("0X");
+            u8::from_str_radix(hex, 16).unwrap_or_else(|_| {
+                ERROR(format!("{}? Not Fill Byte (00-ff)", pad).as_str());
+                0
+            })
+        });
+
+        Self {
+            path: _cmdline_info.value_of("*.D88").map(|path| path.to_string()),
+            output_path: _cmdline_info
+                .value_of("OUTPUT")
+                .map(|path| path.to_string()),
+            selection: get_selection(&_cmdline_info),
+            option: ExtractOption {
+                with_header: _cmdline_info.is_present("header"),
+                pad: _pad,
+            },
+        }
+    }
+
+    /// Report
+    ///
+    /// # Argument
+    ///   * (none)
+    ///
+    pub fn report(&self) {
+        if let Some(ref d88_path) = self.path {
+            let d88fileio = fileio::D88FileIO::open(Path::new(d88_path));
+            if !d88fileio.is_open() {
+                println!("File Not Found \"{}\"", d88_path);
+                return;
+            }
+
+            if let Some(ref output_path) = self.output_path {
+                let result = fs::File::create(Path::new(output_path))
+                    .map_err(|_| ())
+                    .and_then(|fh| self.write(&d88fileio, &mut BufWriter::new(fh)));
+                if result.is_err() {
+                    ERROR(format!("Can't write \"{}\"", output_path).as_str());
+                }
+            } else {
+                let stdout = io::stdout();
+                let _ = self.write(&d88fileio, &mut stdout.lock());
+            }
+        }
+    }
+
+    /// Write Sector Data (Helper function)
+    ///
+    fn write<W: Write>(&self, d88fileio: &fileio::D88FileIO, writer: &mut W) -> Result<(), ()> {
+        let size = extract(&d88fileio.disk, &self.selection, &self.option, writer)?;
+        writer.flush().map_err(|_| ())?;
+
+        if size == 0 {
+            eprintln!("Sector Not Found");
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,426 @@
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+use D88FileIO::archive::is_archive_source;
+use D88FileIO::basic::{detokenize, tokenize, Dialect};
+use D88FileIO::charset::Charset;
+use D88FileIO::disk::Disk;
+use D88FileIO::fileio;
+use D88FileIO::filesystem::{detect, find_entry, from_name, DirEntry, FileSystem, FileType};
+
+use crate::cli::get_charset;
+use crate::structured::{print_structured, OutputFormat};
+use crate::utility::ERROR;
+
+/// Filesystem of "--fs" or detected (Helper function)
+///
+/// 判定できなければエラー終了する。
+///
+fn get_fs(disk: &Disk, fs_name: &Option<String>) -> Option<Box<dyn FileSystem>> {
+    let fs = match fs_name {
+        Some(name) => from_name(name),
+        None => detect(disk),
+    };
+    if fs.is_none() {
+        ERROR("Unknown filesystem, specify --fs");
+    }
+    fs
+}
+
+/// BASIC Dialect of "--dialect" or Filesystem (Helper function)
+///
+/// 決まらなければエラー終了する。
+///
+fn get_dialect(fs: &dyn FileSystem, dialect_name: &Option<String>) -> Option<Dialect> {
+    let dialect = match dialect_name {
+        Some(name) => Dialect::from_name(name),
+        None => Dialect::from_filesystem(fs.get_name()),
+    };
+    if dialect.is_none() {
+        ERROR("Unknown BASIC dialect, specify --dialect");
+    }
+    dialect
+}
+
+/// ReportLs
+///
+/// ディスク上のファイル一覧を表示。
+///
+pub struct ReportLs {
+    pub path: Option<String>,
+    pub fs_name: Option<String>,
+    pub format: OutputFormat,
+}
+
+impl ReportLs {
+    /// Constructor
+    ///
+    pub fn new(_cmdline_info: clap::ArgMatches) -> Self {
+        Self {
+            path: _cmdline_info.value_of("*.D88").map(|path| path.to_string()),
+            fs_name: _cmdline_info.value_of("FS").map(|name| name.to_string()),
+            format: OutputFormat::from_cmdline(&_cmdline_info),
+        }
+    }
+
+    /// Report
+    ///
+    /// # Argument
+    ///   * (none)
+    ///
+    pub fn report(&self) {
+        if let Some(ref d88_path) = self.path {
+            let d88fileio = fileio::D88FileIO::open(Path::new(d88_path));
+            if !d88fileio.is_open() {
+                println!("File Not Found \"{}\"", d88_path);
+                return;
+            }
+
+            let fs = match get_fs(&d88fileio.disk, &self.fs_name) {
+                Some(fs) => fs,
+                None => return,
+            };
+            let entries = match fs.list(&d88fileio.disk) {
+                Ok(entries) => entries,
+                Err(mes) => {
+                    ERROR(mes.as_str());
+                    return;
+                }
+            };
+
+            if !self.format.is_text() {
+                let files: Vec<LsFile> = entries.iter().map(LsFile::new).collect();
+                let doc = LsDoc {
+                    path: d88_path.to_string(),
+                    filesystem: fs.get_name().to_string(),
+                    files,
+                };
+                print_structured(self.format, &doc, &doc.files);
+                return;
+            }
+
+            let addr =
+                |addr: Option<u16>| addr.map_or("----".to_string(), |a| format!("{:04x}", a));
+            println!("Filesystem  {}", fs.get_name());
+            println!();
+            println!("Name              Type  Attr  Size    Start  Load  Exec");
+            println!("----------------  ----  ----  ------  -----  ----  ----");
+            for entry in entries.iter() {
+                println!(
+                    "{:16}  {:4}  {:02x}h   {:6}  {:5}  {}  {}",
+                    entry.name,
+                    entry.file_type.get_name(),
+                    entry.attr,
+                    entry.size,
+                    entry.start,
+                    addr(entry.load_addr),
+                    addr(entry.exec_addr),
+                );
+            }
+            println!();
+            println!("{} file(s)", entries.len());
+        }
+    }
+}
+
+/// "ls" Document (Structured Output)
+///
+#[derive(Debug, Serialize)]
+struct LsDoc {
+    path: String,
+    filesystem: String,
+    files: Vec<LsFile>,
+}
+
+/// "ls" File (Structured Output)
+///
+#[derive(Debug, Serialize)]
+struct LsFile {
+    name: String,
+    file_type: String,
+    attr: u8,
+    size: usize,
+    start: usize,
+    load_addr: Option<u16>,
+    exec_addr: Option<u16>,
+}
+
+impl LsFile {
+    fn new(entry: &DirEntry) -> Self {
+        Self {
+            name: entry.name.clone(),
+            file_type: entry.file_type.get_name().to_string(),
+            attr: entry.attr,
+            size: entry.size,
+            start: entry.start,
+            load_addr: entry.load_addr,
+            exec_addr: entry.exec_addr,
+        }
+    }
+}
+
+/// ReportGet
+///
+/// ディスク上のファイルを取り出す。
+///
+pub struct ReportGet {
+    pub path: Option<String>,
+    pub name: Option<String>,
+    pub output_path: Option<String>,
+    pub fs_name: Option<String>,
+}
+
+impl ReportGet {
+    /// Constructor
+    ///
+    pub fn new(_cmdline_info: clap::ArgMatches) -> Self {
+        Self {
+            path: _cmdline_info.value_of("*.D88").map(|path| path.to_string()),
+            name: _cmdline_info.value_of("NAME").map(|name| name.to_string()),
+            output_path: _cmdline_info
+                .value_of("OUTPUT")
+                .map(|path| path.to_string()),
+            fs_name: _cmdline_info.value_of("FS").map(|name| name.to_string()),
+        }
+    }
+
+    /// Report
+    ///
+    /// # Argument
+    ///   * (none)
+    ///
+    pub fn report(&self) {
+        if let (Some(ref d88_path), Some(ref name)) = (&self.path, &self.name) {
+            let d88fileio = fileio::D88FileIO::open(Path::new(d88_path));
+            if !d88fileio.is_open() {
+                println!("File Not Found \"{}\"", d88_path);
+                return;
+            }
+
+            let fs = match get_fs(&d88fileio.disk, &self.fs_name) {
+                Some(fs) => fs,
+                None => return,
+            };
+            let data = match fs.read(&d88fileio.disk, name) {
+                Ok(data) => data,
+                Err(mes) => {
+                    ERROR(mes.as_str());
+                    return;
+                }
+            };
+
+            let output_path = self.output_path.as_ref().unwrap_or(name);
+            if fs::write(Path::new(output_path), &data).is_ok() {
+                println!("Output  {} ({} byte)", output_path, data.len());
+            } else {
+                ERROR(format!("Can't write \"{}\"", output_path).as_str());
+            }
+        }
+    }
+}
+
+/// ReportList
+///
+/// ディスク上のBASICプログラムを表示する。
+/// 中間コード("BAS")はテキストに戻し、テキスト("ASC")はそのまま表示する。
+///
+pub struct ReportList {
+    pub path: Option<String>,
+    pub name: Option<String>,
+    pub fs_name: Option<String>,
+    pub dialect_name: Option<String>,
+    pub charset: Charset,
+}
+
+impl ReportList {
+    /// Constructor
+    ///
+    pub fn new(_cmdline_info: clap::ArgMatches) -> Self {
+        Self {
+            path: _cmdline_info.value_of("*.D88").map(|path| path.to_string()),
+            name: _cmdline_info.value_of("NAME").map(|name| name.to_string()),
+            fs_name: _cmdline_info.value_of("FS").map(|name| name.to_string()),
+            dialect_name: _cmdline_info
+                .value_of("DIALECT")
+                .map(|name| name.to_string()),
+            charset: get_charset(&_cmdline_info),
+        }
+    }
+
+    /// Report
+    ///
+    /// # Argument
+    ///   * (none)
+    ///
+    pub fn report(&self) {
+        if let (Some(ref d88_path), Some(ref name)) = (&self.path, &self.name) {
+            let d88fileio = fileio::D88FileIO::open(Path::new(d88_path));
+            if !d88fileio.is_open() {
+                println!("File Not Found \"{}\"", d88_path);
+                return;
+            }
+
+            let fs = match get_fs(&d88fileio.disk, &self.fs_name) {
+                Some(fs) => fs,
+                None => return,
+            };
+            let entry = fs
+                .list(&d88fileio.disk)
+                .and_then(|entries| find_entry(&entries, name));
+            let data = entry.and_then(|entry| {
+                fs.read(&d88fileio.disk, name)
+                    .map(|data| (entry.file_type, data))
+            });
+            let (file_type, data) = match data {
+                Ok(data) => data,
+                Err(mes) => {
+                    ERROR(mes.as_str());
+                    return;
+                }
+            };
+
+            match file_type {
+                FileType::Basic => {
+                    let dialect = match get_dialect(fs.as_ref(), &self.dialect_name) {
+                        Some(dialect) => dialect,
+                        None => return,
+                    };
+                    match detokenize(dialect, &data, self.charset) {
+                        Ok(lines) => {
+                            for line in lines.iter() {
+                                println!("{} {}", line.number, line.text);
+                            }
+                        }
+                        Err(mes) => ERROR(mes.as_str()),
+                    }
+                }
+                FileType::Ascii => {
+                    // 0x1a (EOF) まで、改行は CR, CR LF, LF
+                    let end = data.iter().position(|byte| *byte == 0x1a);
+                    let text = &data[..end.unwrap_or(data.len())];
+                    for line in text.split(|byte| *byte == b'\r' || *byte == b'\n') {
+                        if !line.is_empty() {
+                            println!("{}", self.charset.decode(line));
+                        }
+                    }
+                }
+                _ => ERROR(format!("\"{}\" is not a BASIC program", name).as_str()),
+            }
+        }
+    }
+}
+
+/// ReportPut
+///
+/// ファイルをディスクに書き込む。
+///
+pub struct ReportPut {
+    pub path: Option<String>,
+    pub file_path: Option<String>,
+    pub name: Option<String>,
+    pub file_type: FileType,
+    pub tokenize: bool,
+    pub dialect_name: Option<String>,
+    pub output_path: Option<String>,
+    pub fs_name: Option<String>,
+}
+
+impl ReportPut {
+    /// Constructor
+    ///
+    pub fn new(_cmdline_info: clap::ArgMatches) -> Self {
+        Self {
+            path: _cmdline_info.value_of("*.D88").map(|path| path.to_string()),
+            file_path: _cmdline_info.value_of("FILE").map(|path| path.to_string()),
+            name: _cmdline_info.value_of("NAME").map(|name| name.to_string()),
+            file_type: _cmdline_info
+                .value_of("TYPE")
+                .and_then(FileType::from_name)
+                .unwrap_or(FileType::Binary),
+            tokenize: _cmdline_info.is_present("tokenize"),
+            dialect_name: _cmdline_info
+                .value_of("DIALECT")
+                .map(|name| name.to_string()),
+            output_path: _cmdline_info
+                .value_of("OUTPUT")
+                .map(|path| path.to_string()),
+            fs_name: _cmdline_info.value_of("FS").map(|name| name.to_string()),
+        }
+    }
+
+    /// Report
+    ///
+    /// # Argument
+    ///   * (none)
+    ///
+    pub fn report(&self) {
+        if let (Some(ref d88_path), Some(ref file_path)) = (&self.path, &self.file_path) {
+            let mut d88fileio = fileio::D88FileIO::open(Path::new(d88_path));
+            if !d88fileio.is_open() {
+                println!("File Not Found \"{}\"", d88_path);
+                return;
+            }
+            let data = if let Ok(data) = fs::read(Path::new(file_path)) {
+                data
+            } else {
+                println!("File Not Found \"{}\"", file_path);
+                return;
+            };
+
+            let name = self.name.clone().unwrap_or_else(|| {
+                Path::new(file_path)
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default()
+            });
+            let fs = match get_fs(&d88fileio.disk, &self.fs_name) {
+                Some(fs) => fs,
+                None => return,
+            };
+            let (data, file_type) = if self.tokenize {
+                let dialect = match get_dialect(fs.as_ref(), &self.dialect_name) {
+                    Some(dialect) => dialect,
+                    None => return,
+                };
+                // UTF-8 でなければ Shift-JIS のテキストとして読む
+                let text = String::from_utf8(data.clone()).unwrap_or_else(|_| {
+                    data.split(|byte| *byte == b'\r' || *byte == b'\n')
+                        .map(|line| Charset::Sjis.decode(line) + "\n")
+                        .collect()
+                });
+                match tokenize(dialect, &text) {
+                    Ok(data) => (data, FileType::Basic),
+                    Err(mes) => {
+                        ERROR(mes.as_str());
+                        return;
+                    }
+                }
+            } else {
+                (data, self.file_type)
+            };
+            if let Err(mes) = fs.write(&mut d88fileio.disk, &name, &data, file_type) {
+                ERROR(mes.as_str());
+                return;
+            }
+
+            let output_path = self.output_path.as_ref().unwrap_or(d88_path);
+            if is_archive_source(output_path) {
+                ERROR("Can't write into an archive or stdin, specify -o");
+                return;
+            }
+            d88fileio.disk.update_offset();
+            if d88fileio.disk.save(Path::new(output_path)).is_ok() {
+                println!(
+                    "Put  {} ({} byte, {}) to {}",
+                    name,
+                    data.len(),
+                    file_type.get_name(),
+                    output_path
+                );
+            } else {
+                ERROR(format!("Can't write \"{}\"", output_path).as_str());
+            }
+        }
+    }
+}
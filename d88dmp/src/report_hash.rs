@@ -0,0 +1,170 @@
+use serde::Serialize;
+
+use D88FileIO::archive::read_source;
+use D88FileIO::fileio;
+use D88FileIO::hash::Digest;
+
+use crate::structured::{print_structured, OutputFormat};
+
+/// ReportHash
+///
+/// D88ファイルのハッシュ値を表示。
+/// 1行1レコードのタブ区切り(kind, track, side, sector, crc32, md5, sha1)。
+///
+pub struct ReportHash {
+    pub path: Option<String>,
+    pub track_flg: bool,
+    pub sector_flg: bool,
+    pub format: OutputFormat,
+}
+
+impl ReportHash {
+    /// Constructor
+    ///
+    pub fn new(_cmdline_info: clap::ArgMatches) -> Self {
+        Self {
+            path: _cmdline_info.value_of("*.D88").map(|path| path.to_string()),
+            track_flg: _cmdline_info.is_present("track"),
+            sector_flg: _cmdline_info.is_present("sector"),
+            format: OutputFormat::from_cmdline(&_cmdline_info),
+        }
+    }
+
+    /// Report
+    ///
+    /// # Argument
+    ///   * (none)
+    ///
+    pub fn report(&self) {
+        if let Some(ref d88_path) = self.path {
+            // "archive.zip:disk2.d88" is hashed as the entry, not the archive
+            let entry = match read_source(d88_path) {
+                Ok(mut entries) => entries.remove(0),
+                Err(mes) => {
+                    println!("{}", mes);
+                    return;
+                }
+            };
+            let file_digest = Digest::from_bytes(&entry.data);
+            let format = fileio::D88FileIO::image_format_from_path(&entry.name);
+            let d88fileio = fileio::D88FileIO::from_bytes(entry.data, format);
+
+            if d88fileio.is_open() {
+                self.report_hash(&entry.name, &d88fileio, &file_digest);
+            } else {
+                println!("File Not Found \"{}\"", d88_path);
+            }
+        }
+    }
+
+    /// Report Hash (Helper function)
+    ///
+    fn report_hash(&self, d88_path: &str, d88fileio: &fileio::D88FileIO, file_digest: &Digest) {
+        let disk = &d88fileio.disk;
+
+        let mut rows = vec![
+            HashRow::new("file", None, None, None, file_digest),
+            HashRow::new("content", None, None, None, &disk.content_digest()),
+        ];
+
+        if self.track_flg {
+            for (idx, track) in disk.track_tbl.iter().enumerate() {
+                if track.sector_tbl.is_empty() {
+                    continue;
+                }
+                if let Some(digest) = disk.track_digest(idx) {
+                    let (track_no, side_no) = disk.track_position(idx);
+                    rows.push(HashRow::new(
+                        "track",
+                        Some(track_no),
+                        Some(side_no),
+                        None,
+                        &digest,
+                    ));
+                }
+            }
+        }
+
+        if self.sector_flg {
+            for (idx, sector) in disk.logical_sectors() {
+                let (track_no, side_no) = disk.track_position(idx);
+                rows.push(HashRow::new(
+                    "sector",
+                    Some(track_no),
+                    Some(side_no),
+                    Some(sector.header.sector),
+                    &sector.digest(),
+                ));
+            }
+        }
+
+        if !self.format.is_text() {
+            let doc = HashDoc {
+                path: d88_path.to_string(),
+                hashes: rows,
+            };
+            print_structured(self.format, &doc, &doc.hashes);
+            return;
+        }
+
+        println!("#kind\ttrack\tside\tsector\tcrc32\tmd5\tsha1");
+        for row in rows.iter() {
+            self.print_digest(row);
+        }
+    }
+
+    /// Print One Record (Helper function)
+    ///
+    fn print_digest(&self, row: &HashRow) {
+        let or_dash = |n: Option<String>| n.unwrap_or_else(|| "-".to_string());
+        println!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            row.kind,
+            or_dash(row.track.map(|n| n.to_string())),
+            or_dash(row.side.map(|n| n.to_string())),
+            or_dash(row.sector.map(|n| n.to_string())),
+            row.crc32,
+            row.md5,
+            row.sha1
+        );
+    }
+}
+
+/// Hash Record (Structured Output)
+///
+#[derive(Serialize)]
+struct HashRow {
+    kind: &'static str,
+    track: Option<usize>,
+    side: Option<usize>,
+    sector: Option<u8>,
+    crc32: String,
+    md5: String,
+    sha1: String,
+}
+
+impl HashRow {
+    fn new(
+        kind: &'static str,
+        track: Option<usize>,
+        side: Option<usize>,
+        sector: Option<u8>,
+        digest: &Digest,
+    ) -> Self {
+        Self {
+            kind,
+            track,
+            side,
+            sector,
+            crc32: digest.get_crc32(),
+            md5: digest.get_md5(),
+            sha1: digest.get_sha1(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct HashDoc {
+    path: String,
+    hashes: Vec<HashRow>,
+}
@@ -0,0 +1,252 @@
+use serde::Serialize;
+use std::path::Path;
+
+use D88FileIO::archive::read_source;
+use D88FileIO::dat::{DatFile, DatMatch, MatchBy};
+use D88FileIO::fileio;
+use D88FileIO::hash::Digest;
+
+use crate::structured::{print_structured, OutputFormat};
+use crate::utility::ERROR;
+
+/// ReportIdentify
+///
+/// DATカタログ(Logiqx XML)とハッシュ値を照合して、タイトルを表示。
+///
+pub struct ReportIdentify {
+    pub paths: Vec<String>,
+    pub dat_path: Option<String>,
+    pub match_by: Vec<MatchBy>,
+    pub format: OutputFormat,
+}
+
+impl ReportIdentify {
+    /// Constructor
+    ///
+    pub fn new(_cmdline_info: clap::ArgMatches) -> Self {
+        let _paths = if let Some(paths) = _cmdline_info.values_of("*.D88") {
+            paths.map(|path| path.to_string()).collect()
+        } else {
+            Vec::new()
+        };
+
+        let _match_by = match _cmdline_info.value_of("HASH") {
+            Some("file") => vec![MatchBy::File],
+            Some("content") => vec![MatchBy::Content],
+            _ => vec![MatchBy::File, MatchBy::Content],
+        };
+
+        Self {
+            paths: _paths,
+            dat_path: _cmdline_info.value_of("DAT").map(|path| path.to_string()),
+            match_by: _match_by,
+            format: OutputFormat::from_cmdline(&_cmdline_info),
+        }
+    }
+
+    /// Report
+    ///
+    /// # Argument
+    ///   * (none)
+    ///
+    pub fn report(&self) {
+        let dat = if let Some(ref dat_path) = self.dat_path {
+            match DatFile::open(Path::new(dat_path)) {
+                Ok(dat) => dat,
+                Err(mes) => {
+                    ERROR(mes.as_str());
+                    return;
+                }
+            }
+        } else {
+            return;
+        };
+
+        if self.format.is_text() {
+            println!("DAT({}) Games({})", dat.name, dat.games.len());
+            println!();
+        }
+
+        let mut results: Vec<IdentifyResult> = Vec::new();
+        let mut unmatched: Vec<String> = Vec::new();
+        for source in self.paths.iter() {
+            let entries = match read_source(source) {
+                Ok(entries) => entries,
+                Err(mes) => {
+                    println!("{}", mes);
+                    continue;
+                }
+            };
+
+            for entry in entries {
+                let d88_path = entry.name.as_str();
+                let file_digest = Digest::from_bytes(&entry.data);
+                let size = Some(entry.data.len() as u64);
+                let format = fileio::D88FileIO::image_format_from_path(d88_path);
+                let d88fileio = fileio::D88FileIO::from_bytes(entry.data, format);
+                if !d88fileio.is_open() {
+                    println!("File Not Found \"{}\"", d88_path);
+                    continue;
+                }
+                let content_digest = d88fileio.disk.content_digest();
+
+                let mut found: Vec<DatMatch> = Vec::new();
+                for by in self.match_by.iter() {
+                    let digest = match by {
+                        MatchBy::File => &file_digest,
+                        MatchBy::Content => &content_digest,
+                    };
+                    for m in dat.find(digest, size, *by) {
+                        if !found.iter().any(|f| f.game.name == m.game.name) {
+                            found.push(m);
+                        }
+                    }
+                }
+
+                if !self.format.is_text() {
+                    results.push(IdentifyResult::new(&dat, d88_path, &found));
+                } else if found.is_empty() {
+                    unmatched.push(d88_path.to_string());
+                } else {
+                    self.report_match(&dat, d88_path, &found);
+                }
+            }
+        }
+
+        if !self.format.is_text() {
+            let rows: Vec<IdentifyRow> = results.iter().flat_map(IdentifyRow::rows).collect();
+            let doc = IdentifyDoc {
+                dat: dat.name.clone(),
+                games: dat.games.len(),
+                results,
+            };
+            print_structured(self.format, &doc, &rows);
+            return;
+        }
+
+        if !unmatched.is_empty() {
+            println!("Unmatched({})", unmatched.len());
+            for d88_path in unmatched {
+                println!("  {}", d88_path);
+            }
+        }
+    }
+
+    /// Report Matched Entries (Helper function)
+    ///
+    fn report_match(&self, dat: &DatFile, d88_path: &str, found: &[DatMatch]) {
+        println!("{}", d88_path);
+
+        for m in found.iter() {
+            println!(
+                "  Title({}), Match({}), Status({}){}",
+                m.game.name,
+                match m.by {
+                    MatchBy::File => "File",
+                    MatchBy::Content => "Content",
+                },
+                m.game.get_dump_status(m.rom),
+                if m.game.is_bad_or_modified(m.rom) {
+                    ", !! Bad or Modified Dump !!"
+                } else {
+                    ""
+                }
+            );
+            if !m.game.description.is_empty() && (m.game.description != m.game.name) {
+                println!("    Description({})", m.game.description);
+            }
+            for alt in dat.find_alternates(m.game) {
+                println!("    Alternate({})", alt.name);
+            }
+        }
+        println!();
+    }
+}
+
+/// Identification (Structured Output)
+///
+#[derive(Serialize)]
+struct IdentifyDoc {
+    dat: String,
+    games: usize,
+    results: Vec<IdentifyResult>,
+}
+
+#[derive(Serialize)]
+struct IdentifyResult {
+    path: String,
+    matches: Vec<IdentifyMatch>,
+}
+
+#[derive(Serialize)]
+struct IdentifyMatch {
+    title: String,
+    description: String,
+    by: &'static str,
+    status: String,
+    bad_or_modified: bool,
+    alternates: Vec<String>,
+}
+
+impl IdentifyResult {
+    fn new(dat: &DatFile, d88_path: &str, found: &[DatMatch]) -> Self {
+        Self {
+            path: d88_path.to_string(),
+            matches: found
+                .iter()
+                .map(|m| IdentifyMatch {
+                    title: m.game.name.clone(),
+                    description: m.game.description.clone(),
+                    by: match m.by {
+                        MatchBy::File => "File",
+                        MatchBy::Content => "Content",
+                    },
+                    status: m.game.get_dump_status(m.rom),
+                    bad_or_modified: m.game.is_bad_or_modified(m.rom),
+                    alternates: dat
+                        .find_alternates(m.game)
+                        .iter()
+                        .map(|alt| alt.name.clone())
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// One Row of CSV (Unmatched file has empty title)
+///
+#[derive(Serialize)]
+struct IdentifyRow {
+    path: String,
+    title: String,
+    by: &'static str,
+    status: String,
+    bad_or_modified: bool,
+}
+
+impl IdentifyRow {
+    fn rows(result: &IdentifyResult) -> Vec<Self> {
+        if result.matches.is_empty() {
+            return vec![Self {
+                path: result.path.clone(),
+                title: String::new(),
+                by: "",
+                status: String::new(),
+                bad_or_modified: false,
+            }];
+        }
+
+        result
+            .matches
+            .iter()
+            .map(|m| Self {
+                path: result.path.clone(),
+                title: m.title.clone(),
+                by: m.by,
+                status: m.status.clone(),
+                bad_or_modified: m.bad_or_modified,
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,433 @@
+use ansi_term::Color;
+use serde::Serialize;
+use std::fs;
+use std::io::BufWriter;
+use std::path::Path;
+
+use D88FileIO::fileio;
+use D88FileIO::map::{MapRow, SectorState, SurfaceMap};
+
+use crate::structured::{print_structured, OutputFormat};
+use crate::utility::ERROR;
+
+const STATES: [SectorState; 5] = [
+    SectorState::Ok,
+    SectorState::Empty,
+    SectorState::Deleted,
+    SectorState::Error,
+    SectorState::Missing,
+];
+
+/// Cell Size of SVG/PNG (pixel)
+const CELL: usize = 10;
+/// Label Width of SVG (pixel)
+const LABEL: usize = 60;
+
+/// ReportMap
+///
+/// トラック×セクタの状態マップを表示する。(SVG/PNG出力あり)
+///
+pub struct ReportMap {
+    pub path: Option<String>,
+    pub nocolor_flg: bool,
+    pub svg_path: Option<String>,
+    pub png_path: Option<String>,
+    pub format: OutputFormat,
+}
+
+impl ReportMap {
+    /// Constructor
+    ///
+    pub fn new(_cmdline_info: clap::ArgMatches) -> Self {
+        Self {
+            path: _cmdline_info.value_of("*.D88").map(|path| path.to_string()),
+            nocolor_flg: _cmdline_info.is_present("no-color"),
+            svg_path: _cmdline_info.value_of("SVG").map(|path| path.to_string()),
+            png_path: _cmdline_info.value_of("PNG").map(|path| path.to_string()),
+            format: OutputFormat::from_cmdline(&_cmdline_info),
+        }
+    }
+
+    /// Report
+    ///
+    /// # Argument
+    ///   * (none)
+    ///
+    pub fn report(&self) {
+        if let Some(ref d88_path) = self.path {
+            let d88fileio = fileio::D88FileIO::open(Path::new(d88_path));
+            if !d88fileio.is_open() {
+                println!("File Not Found \"{}\"", d88_path);
+                return;
+            }
+
+            let map = SurfaceMap::new(&d88fileio.disk);
+            if self.format.is_text() {
+                self.report_map(&map);
+            } else {
+                self.report_structured(d88_path, &map);
+            }
+
+            if let Some(ref svg_path) = self.svg_path {
+                if fs::write(Path::new(svg_path), self.get_svg(&map)).is_err() {
+                    ERROR(format!("Can't write \"{}\"", svg_path).as_str());
+                }
+            }
+            if let Some(ref png_path) = self.png_path {
+                if self.write_png(&map, Path::new(png_path)).is_err() {
+                    ERROR(format!("Can't write \"{}\"", png_path).as_str());
+                }
+            }
+        }
+    }
+
+    /// Report Map on Terminal (Helper function)
+    ///
+    fn report_map(&self, map: &SurfaceMap) {
+        print!("Surface Map  ");
+        for state in STATES.iter() {
+            print!("{}:{} ", self.get_cell(Some(*state)), state.get_name());
+        }
+        println!();
+        println!();
+
+        println!("Track    Side  Sector");
+        let digits = get_id_digits(&map.ids);
+        for (n, line) in digits.iter().enumerate() {
+            if n == digits.len() - 1 {
+                println!("hex dec  No.  {}", line);
+            } else {
+                println!("              {}", line);
+            }
+        }
+        println!("--- ---- ---  {}", "-".repeat(map.ids.len()));
+
+        for row in map.rows.iter() {
+            self.report_row(row);
+        }
+
+        println!();
+        print!("Summary  ");
+        for state in STATES.iter() {
+            print!("{}({}) ", state.get_name(), map.count(*state));
+        }
+        println!();
+    }
+
+    /// Report as JSON/YAML/CSV (Helper function)
+    ///
+    /// CSVは1セクタ1行。
+    ///
+    fn report_structured(&self, d88_path: &str, map: &SurfaceMap) {
+        let get_name = |cell: &Option<SectorState>| cell.map(|state| state.get_name());
+
+        let doc = MapDoc {
+            path: d88_path.to_string(),
+            ids: map.ids.clone(),
+            rows: map
+                .rows
+                .iter()
+                .map(|row| MapDocRow {
+                    track: row.track,
+                    side: row.side,
+                    cells: row.cells.iter().map(get_name).collect(),
+                })
+                .collect(),
+        };
+
+        let mut cells: Vec<MapCell> = Vec::new();
+        for row in map.rows.iter() {
+            for (n, cell) in row.cells.iter().enumerate() {
+                if let Some(state) = get_name(cell) {
+                    cells.push(MapCell {
+                        track: row.track,
+                        side: row.side,
+                        sector: map.ids[n],
+                        state,
+                    });
+                }
+            }
+        }
+
+        print_structured(self.format, &doc, &cells);
+    }
+
+    /// Report a Row (Helper function)
+    ///
+    fn report_row(&self, row: &MapRow) {
+        let ts_formated = format!("{0:02x}h {0:3}d {1:3}", row.track, row.side);
+        if self.nocolor_flg {
+            print!("{}  ", ts_formated);
+        } else {
+            print!("{}  ", Color::Cyan.paint(ts_formated));
+        }
+
+        for cell in row.cells.iter() {
+            print!("{}", self.get_cell(*cell));
+        }
+        println!();
+    }
+
+    /// Cell Character (Helper function)
+    ///
+    fn get_cell(&self, cell: Option<SectorState>) -> String {
+        let (ch, color) = match cell {
+            Some(SectorState::Ok) => ("o", Color::Green),
+            Some(SectorState::Empty) => (".", Color::Blue),
+            Some(SectorState::Deleted) => ("D", Color::Yellow),
+            Some(SectorState::Error) => ("C", Color::Red),
+            Some(SectorState::Missing) => ("-", Color::Purple),
+            None => (" ", Color::White),
+        };
+
+        if self.nocolor_flg {
+            ch.to_string()
+        } else {
+            color.paint(ch).to_string()
+        }
+    }
+
+    /// SVG Image (Helper function)
+    ///
+    fn get_svg(&self, map: &SurfaceMap) -> String {
+        let width = LABEL + (map.ids.len() * CELL);
+        let height = (map.rows.len() + 1) * CELL;
+
+        let mut svg = String::new();
+        svg.push_str(&format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" font-family=\"monospace\" font-size=\"{}\">\n",
+            width, height, CELL - 1
+        ));
+        svg.push_str(&format!(
+            "<rect width=\"{}\" height=\"{}\" fill=\"#ffffff\"/>\n",
+            width, height
+        ));
+
+        // Sector Number (先頭、5の倍数、IDが飛んだ所)
+        for (n, id) in map.ids.iter().enumerate() {
+            if (n == 0) || (id % 5) == 0 || (map.ids[n - 1] + 1) != *id {
+                svg.push_str(&format!(
+                    "<text x=\"{}\" y=\"{}\">{}</text>\n",
+                    LABEL + (n * CELL),
+                    CELL - 1,
+                    id
+                ));
+            }
+        }
+
+        for (y, row) in map.rows.iter().enumerate() {
+            let top = (y + 1) * CELL;
+            svg.push_str(&format!(
+                "<text x=\"0\" y=\"{}\">{:02}:{}</text>\n",
+                top + CELL - 1,
+                row.track,
+                row.side
+            ));
+
+            for (x, cell) in row.cells.iter().enumerate() {
+                if let Some(state) = cell {
+                    let (r, g, b) = self.get_rgb(*state);
+                    svg.push_str(&format!(
+                        "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"#{:02x}{:02x}{:02x}\"><title>Track {} Side {} Sector {}: {}</title></rect>\n",
+                        LABEL + (x * CELL),
+                        top,
+                        CELL - 1,
+                        CELL - 1,
+                        r,
+                        g,
+                        b,
+                        row.track,
+                        row.side,
+                        map.ids[x],
+                        state.get_name()
+                    ));
+                }
+            }
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Write PNG Image (Helper function)
+    ///
+    /// 1行が1トラック(1面)、1マスが1セクタ。
+    ///
+    fn write_png(&self, map: &SurfaceMap, path: &Path) -> Result<(), ()> {
+        let width = (map.ids.len() * CELL).max(1);
+        let height = (map.rows.len() * CELL).max(1);
+
+        let mut img = vec![0xffu8; width * height * 3];
+        for (y, row) in map.rows.iter().enumerate() {
+            for (x, cell) in row.cells.iter().enumerate() {
+                if let Some(state) = cell {
+                    let (r, g, b) = self.get_rgb(*state);
+                    for py in (y * CELL)..((y + 1) * CELL - 1) {
+                        for px in (x * CELL)..((x + 1) * CELL - 1) {
+                            let pos = ((py * width) + px) * 3;
+                            img[pos..pos + 3].copy_from_slice(&[r, g, b]);
+                        }
+                    }
+                }
+            }
+        }
+
+        let fh = fs::File::create(path).map_err(|_| ())?;
+        let mut encoder = png::Encoder::new(BufWriter::new(fh), width as u32, height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder.write_header().map_err(|_| ())?;
+        writer.write_image_data(&img).map_err(|_| ())
+    }
+
+    fn get_rgb(&self, state: SectorState) -> (u8, u8, u8) {
+        match state {
+            SectorState::Ok => (0x33, 0xcc, 0x33),
+            SectorState::Empty => (0xcc, 0xcc, 0xcc),
+            SectorState::Deleted => (0xff, 0xcc, 0x33),
+            SectorState::Error => (0xee, 0x33, 0x33),
+            SectorState::Missing => (0x40, 0x00, 0x60),
+        }
+    }
+}
+
+/// Surface Map (Structured Output)
+///
+#[derive(Serialize)]
+struct MapDoc {
+    path: String,
+    /// Sector ID of each column
+    ids: Vec<u8>,
+    rows: Vec<MapDocRow>,
+}
+
+#[derive(Serialize)]
+struct MapDocRow {
+    track: usize,
+    side: usize,
+    /// `cells[n]` is Sector ID `ids[n]`
+    cells: Vec<Option<&'static str>>,
+}
+
+#[derive(Serialize)]
+struct MapCell {
+    track: usize,
+    side: usize,
+    sector: u8,
+    state: &'static str,
+}
+
+/// Sector ID Header (Helper function)
+///
+/// 百の位、十の位、一の位の順に1行ずつ。上の桁が無い列は空白。
+///
+/// # Argument
+///
+///   * `ids` Sector ID of each column
+///
+/// # Return
+///
+///   * Lines (the last line is ones)
+///
+fn get_id_digits(ids: &[u8]) -> Vec<String> {
+    let max_id = ids.iter().max().copied().unwrap_or(0);
+    let places: Vec<u8> = [100u8, 10, 1]
+        .iter()
+        .copied()
+        .filter(|place| (*place == 1) || (max_id >= *place))
+        .collect();
+
+    places
+        .iter()
+        .map(|place| {
+            ids.iter()
+                .map(|id| {
+                    if (*place == 1) || (*id >= *place) {
+                        ((id / place) % 10).to_string()
+                    } else {
+                        " ".to_string()
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+//
+// Test Code
+//
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const HUBASIC: &str = "../sample/HuBASIC_Format_2D.d88";
+
+    fn get_report() -> ReportMap {
+        ReportMap {
+            path: Some(HUBASIC.to_string()),
+            nocolor_flg: true,
+            svg_path: None,
+            png_path: None,
+            format: OutputFormat::Text,
+        }
+    }
+
+    fn get_map() -> SurfaceMap {
+        let mut d88fileio = fileio::D88FileIO::open(Path::new(HUBASIC));
+        d88fileio.disk.track_tbl[0].sector_tbl[0].header.sector = 0xf5;
+        SurfaceMap::new(&d88fileio.disk)
+    }
+
+    #[test]
+    fn test_id_digits() {
+        assert_eq!(get_id_digits(&[1, 2, 3]), vec!["123"]);
+        assert_eq!(get_id_digits(&[0, 9, 10, 16]), vec!["  11", "0906"]);
+        assert_eq!(get_id_digits(&[1, 16, 245]), vec!["  2", " 14", "165"]);
+    }
+
+    #[test]
+    fn test_svg() {
+        let map = get_map();
+        let svg = get_report().get_svg(&map);
+
+        // 17 columns (R=1..16, F5), 80 rows
+        assert_eq!(map.ids.len(), 17);
+        assert!(svg
+            .starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"230\" height=\"810\""));
+        assert!(svg.ends_with("</svg>\n"));
+
+        let cells = map.rows.iter().flat_map(|row| row.cells.iter()).flatten();
+        assert_eq!(svg.matches("<title>").count(), cells.count());
+        assert!(svg.contains("<text x=\"220\" y=\"9\">245</text>"));
+        assert!(svg.contains("<title>Track 0 Side 0 Sector 1: Missing</title>"));
+        assert!(svg.contains("<title>Track 0 Side 0 Sector 245: "));
+        assert!(!svg.contains("<title>Track 0 Side 1 Sector 245: "));
+    }
+
+    #[test]
+    fn test_png() {
+        let map = get_map();
+        let path = std::env::temp_dir().join("d88dmp_test_map.png");
+        get_report().write_png(&map, &path).unwrap();
+
+        let decoder = png::Decoder::new(fs::File::open(&path).unwrap());
+        let mut reader = decoder.read_info().unwrap();
+        let mut img = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut img).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!((info.width, info.height), (170, 800));
+        assert_eq!(info.color_type, png::ColorType::Rgb);
+
+        // Track 0 Side 0 Sector 1 is Missing, Track 0 Side 1 has no Sector 245
+        let get_pixel = |x: usize, y: usize| {
+            let pos = ((y * info.width as usize) + x) * 3;
+            (img[pos], img[pos + 1], img[pos + 2])
+        };
+        let report = get_report();
+        assert_eq!(get_pixel(0, 0), report.get_rgb(SectorState::Missing));
+        assert_eq!(get_pixel(9, 9), (0xff, 0xff, 0xff));
+        assert_eq!(get_pixel(160, 10), (0xff, 0xff, 0xff));
+    }
+}
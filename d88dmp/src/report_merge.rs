@@ -0,0 +1,208 @@
+use serde::Serialize;
+use std::path::Path;
+
+use D88FileIO::disk::Disk;
+use D88FileIO::fileio;
+use D88FileIO::merge::{merge, MergedSector, Resolution};
+
+use crate::structured::{print_structured, OutputFormat};
+use crate::utility::ERROR;
+
+/// ReportMerge
+///
+/// 複数のダンプからセクタ毎に最良のコピーを選んで、1枚のD88ファイルに再構成する。
+///
+pub struct ReportMerge {
+    pub paths: Vec<String>,
+    pub output_path: Option<String>,
+    pub verbose_flg: bool,
+    pub format: OutputFormat,
+}
+
+impl ReportMerge {
+    /// Constructor
+    ///
+    pub fn new(_cmdline_info: clap::ArgMatches) -> Self {
+        let _paths = if let Some(paths) = _cmdline_info.values_of("*.D88") {
+            paths.map(|path| path.to_string()).collect()
+        } else {
+            Vec::new()
+        };
+
+        Self {
+            paths: _paths,
+            output_path: _cmdline_info
+                .value_of("OUTPUT")
+                .map(|path| path.to_string()),
+            verbose_flg: _cmdline_info.is_present("verbose"),
+            format: OutputFormat::from_cmdline(&_cmdline_info),
+        }
+    }
+
+    /// Report
+    ///
+    /// # Argument
+    ///   * (none)
+    ///
+    pub fn report(&self) {
+        let mut d88fileio_tbl: Vec<fileio::D88FileIO> = Vec::new();
+        for d88_path in self.paths.iter() {
+            let d88fileio = fileio::D88FileIO::open(Path::new(d88_path));
+            if !d88fileio.is_open() {
+                println!("File Not Found \"{}\"", d88_path);
+                return;
+            }
+            d88fileio_tbl.push(d88fileio);
+        }
+
+        let disks: Vec<&Disk> = d88fileio_tbl.iter().map(|f| &f.disk).collect();
+        let result = if let Some(result) = merge(&disks) {
+            result
+        } else {
+            return;
+        };
+
+        if !self.format.is_text() {
+            self.report_structured(&result.disk, &result.sectors);
+            self.save(&result.disk, false);
+            return;
+        }
+
+        // Source Disks
+        println!("Source");
+        for (src, d88_path) in self.paths.iter().enumerate() {
+            println!("  [{}] {}", src, d88_path);
+        }
+        println!();
+
+        // Sectors
+        for merged in result.sectors.iter() {
+            let trivial = (merged.source == 0)
+                && (merged.resolution == Resolution::Ok)
+                && (merged.copies == self.paths.len());
+            if self.verbose_flg || !trivial {
+                println!("{}", self.get_merged_sector(&result.disk, merged));
+            }
+        }
+
+        let unresolved = result.unresolved();
+        if !unresolved.is_empty() {
+            println!();
+            println!("Unresolved({})", unresolved.len());
+            for merged in unresolved {
+                println!("  {}", self.get_merged_sector(&result.disk, merged));
+            }
+        }
+
+        println!();
+        println!(
+            "Summary  Sectors({}), Resolved({}), Unresolved({})",
+            result.sectors.len(),
+            result.sectors.len() - result.unresolved().len(),
+            result.unresolved().len()
+        );
+
+        // Output
+        self.save(&result.disk, true);
+    }
+
+    /// Save Merged Disk (Helper function)
+    ///
+    fn save(&self, disk: &Disk, verbose: bool) {
+        if let Some(ref output_path) = self.output_path {
+            if let Ok(disk_size) = disk.save(Path::new(output_path)) {
+                if verbose {
+                    println!("Output  {} ({} byte)", output_path, disk_size);
+                }
+            } else {
+                ERROR(format!("Can't write \"{}\"", output_path).as_str());
+            }
+        }
+    }
+
+    /// Report as JSON/YAML/CSV (Helper function)
+    ///
+    /// 全セクタを出力する。CSVは1セクタ1行。
+    ///
+    fn report_structured(&self, disk: &Disk, sectors: &[MergedSector]) {
+        let rows: Vec<MergeRow> = sectors
+            .iter()
+            .map(|merged| {
+                let (track_no, side_no) = disk.track_position(merged.track_idx);
+                MergeRow {
+                    track: track_no,
+                    side: side_no,
+                    sector: merged.sector,
+                    nth: merged.nth,
+                    source: merged.source,
+                    copies: merged.copies,
+                    good_copies: merged.good_copies,
+                    votes: merged.votes,
+                    resolution: format!("{:?}", merged.resolution),
+                    resolved: merged.is_resolved(),
+                }
+            })
+            .collect();
+
+        let doc = MergeDoc {
+            sources: self.paths.clone(),
+            output: self.output_path.clone(),
+            unresolved: rows.iter().filter(|row| !row.resolved).count(),
+            sectors: rows,
+        };
+        print_structured(self.format, &doc, &doc.sectors);
+    }
+
+    /// Merged Sector (Helper function)
+    ///
+    fn get_merged_sector(&self, disk: &Disk, merged: &MergedSector) -> String {
+        let (track_no, side_no) = disk.track_position(merged.track_idx);
+
+        format!(
+            "Track({}), Side({}), Sector({}{}), Source([{}]), Copies({}), OK({}), Votes({}), {}",
+            track_no,
+            side_no,
+            merged.sector,
+            if merged.nth == 0 {
+                "".to_string()
+            } else {
+                format!("#{}", merged.nth + 1)
+            },
+            merged.source,
+            merged.copies,
+            merged.good_copies,
+            merged.votes,
+            match merged.resolution {
+                Resolution::Ok => "OK",
+                Resolution::Majority => "Majority",
+                Resolution::Conflict => "!! Conflict !!",
+                Resolution::NoGoodCopy => "!! No Good Copy !!",
+            }
+        )
+    }
+}
+
+/// Merge Result (Structured Output)
+///
+#[derive(Serialize)]
+struct MergeDoc {
+    sources: Vec<String>,
+    output: Option<String>,
+    unresolved: usize,
+    sectors: Vec<MergeRow>,
+}
+
+#[derive(Serialize)]
+struct MergeRow {
+    track: usize,
+    side: usize,
+    sector: u8,
+    nth: usize,
+    /// Index of Source Disk
+    source: usize,
+    copies: usize,
+    good_copies: usize,
+    votes: usize,
+    resolution: String,
+    resolved: bool,
+}
@@ -0,0 +1,195 @@
+use ansi_term::Color;
+use serde::Serialize;
+use std::path::Path;
+
+use D88FileIO::fileio;
+use D88FileIO::protect::{analyze, TrackAnalysis};
+
+use crate::structured::{print_structured, OutputFormat};
+
+/// ReportProtect
+///
+/// セクタヘッダからコピープロテクトの特徴を調べて、トラック毎に分類を表示する。
+///
+pub struct ReportProtect {
+    pub path: Option<String>,
+    pub nocolor_flg: bool,
+    pub all_flg: bool,
+    pub format: OutputFormat,
+}
+
+impl ReportProtect {
+    /// Constructor
+    ///
+    pub fn new(_cmdline_info: clap::ArgMatches) -> Self {
+        Self {
+            path: _cmdline_info.value_of("*.D88").map(|path| path.to_string()),
+            nocolor_flg: _cmdline_info.is_present("no-color"),
+            all_flg: _cmdline_info.is_present("all"),
+            format: OutputFormat::from_cmdline(&_cmdline_info),
+        }
+    }
+
+    /// Report
+    ///
+    /// # Argument
+    ///   * (none)
+    ///
+    pub fn report(&self) {
+        if let Some(ref d88_path) = self.path {
+            let d88fileio = fileio::D88FileIO::open(Path::new(d88_path));
+            if !d88fileio.is_open() {
+                println!("File Not Found \"{}\"", d88_path);
+                return;
+            }
+
+            let result = analyze(&d88fileio.disk);
+            if !self.format.is_text() {
+                self.report_structured(d88_path, &result);
+                return;
+            }
+
+            println!("Copy Protection Analysis");
+            println!();
+            println!("Track    Side Sector  Classification");
+            println!("hex dec  No.  Num     ");
+            println!("--- ---- ---  ------  ---------------------------------- ...");
+            for track in result.iter() {
+                if self.all_flg || !track.is_normal() {
+                    self.report_track(track);
+                }
+            }
+
+            self.report_protect_summary(&result);
+        }
+    }
+
+    /// Report as JSON/YAML/CSV (Helper function)
+    ///
+    /// CSVは1トラック1行(findingsは"; "区切り)。
+    ///
+    fn report_structured(&self, d88_path: &str, result: &[TrackAnalysis]) {
+        let tracks: Vec<ProtectTrack> = result
+            .iter()
+            .filter(|track| self.all_flg || !track.is_normal())
+            .map(|track| ProtectTrack {
+                track: track.track,
+                side: track.side,
+                number_of_sector: track.number_of_sector,
+                classification: track.get_classification(),
+                findings: track
+                    .findings
+                    .iter()
+                    .map(|finding| ProtectFinding {
+                        pattern: finding.get_pattern(),
+                        detail: finding.get_detail(),
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        let rows: Vec<ProtectRow> = tracks
+            .iter()
+            .map(|track| ProtectRow {
+                track: track.track,
+                side: track.side,
+                number_of_sector: track.number_of_sector,
+                classification: track.classification.clone(),
+                findings: track
+                    .findings
+                    .iter()
+                    .map(|f| format!("{}: {}", f.pattern, f.detail))
+                    .collect::<Vec<String>>()
+                    .join("; "),
+            })
+            .collect();
+
+        let doc = ProtectDoc {
+            path: d88_path.to_string(),
+            tracks,
+        };
+        print_structured(self.format, &doc, &rows);
+    }
+
+    /// Report a Track (Helper function)
+    ///
+    fn report_track(&self, track: &TrackAnalysis) {
+        let tso_formated = format!(
+            "{0:02x}h {0:3}d {1:3}  {2:3}    ",
+            track.track, track.side, track.number_of_sector
+        );
+        let classification = track.get_classification();
+
+        if self.nocolor_flg {
+            println!("{} {}", tso_formated, classification);
+        } else if track.is_normal() {
+            println!("{} {}", Color::Cyan.paint(tso_formated), classification);
+        } else {
+            println!(
+                "{} {}",
+                Color::Cyan.paint(tso_formated),
+                Color::Yellow.paint(classification)
+            );
+        }
+
+        for finding in track.findings.iter() {
+            println!(
+                "                         {}: {}",
+                finding.get_pattern(),
+                finding.get_detail()
+            );
+        }
+    }
+
+    /// Report Summary (Helper function)
+    ///
+    fn report_protect_summary(&self, result: &[TrackAnalysis]) {
+        let mut classes: Vec<(String, usize)> = Vec::new();
+        for track in result.iter() {
+            let classification = track.get_classification();
+            if let Some(entry) = classes.iter_mut().find(|(c, _)| *c == classification) {
+                entry.1 += 1;
+            } else {
+                classes.push((classification, 1));
+            }
+        }
+
+        println!();
+        println!("Summary");
+        for (classification, count) in classes.iter() {
+            println!("  {:3} tracks  {}", count, classification);
+        }
+    }
+}
+
+/// Copy Protection Analysis (Structured Output)
+///
+#[derive(Serialize)]
+struct ProtectDoc {
+    path: String,
+    tracks: Vec<ProtectTrack>,
+}
+
+#[derive(Serialize)]
+struct ProtectTrack {
+    track: usize,
+    side: usize,
+    number_of_sector: usize,
+    classification: String,
+    findings: Vec<ProtectFinding>,
+}
+
+#[derive(Serialize)]
+struct ProtectFinding {
+    pattern: &'static str,
+    detail: String,
+}
+
+#[derive(Serialize)]
+struct ProtectRow {
+    track: usize,
+    side: usize,
+    number_of_sector: usize,
+    classification: String,
+    findings: String,
+}
@@ -0,0 +1,398 @@
+use rayon::prelude::*;
+use serde::Serialize;
+use std::any::Any;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+
+use D88FileIO::archive::{read_source, ArchiveFormat, Entry};
+use D88FileIO::fileio;
+use D88FileIO::hash::Digest;
+use D88FileIO::summary::Summary;
+
+use crate::structured::{print_structured, OutputFormat};
+use crate::utility::ERROR;
+
+/// ReportScan
+///
+/// 複数のD88ファイルを並列に読み込んで、1つの集計表を表示。
+/// アーカイブは中のディスクイメージごとに1行になる。
+/// 読めないファイルはその行にエラーを記録して処理を続ける。
+///
+pub struct ReportScan {
+    pub paths: Vec<String>,
+    pub recursive_flg: bool,
+    pub jobs: Option<usize>,
+    pub format: OutputFormat,
+}
+
+impl ReportScan {
+    /// Constructor
+    ///
+    pub fn new(_cmdline_info: clap::ArgMatches) -> Self {
+        let _paths = if let Some(paths) = _cmdline_info.values_of("PATH") {
+            paths.map(|path| path.to_string()).collect()
+        } else {
+            Vec::new()
+        };
+
+        Self {
+            paths: _paths,
+            recursive_flg: _cmdline_info.is_present("recursive"),
+            jobs: _cmdline_info
+                .value_of("JOBS")
+                .and_then(|jobs| jobs.parse().ok()),
+            format: OutputFormat::from_cmdline(&_cmdline_info),
+        }
+    }
+
+    /// Report
+    ///
+    /// # Argument
+    ///   * (none)
+    ///
+    pub fn report(&self) {
+        let targets = expand_paths(&self.paths, self.recursive_flg);
+
+        let pool = match rayon::ThreadPoolBuilder::new()
+            .num_threads(self.jobs.unwrap_or(0))
+            .build()
+        {
+            Ok(pool) => pool,
+            Err(e) => {
+                ERROR(e.to_string().as_str());
+                return;
+            }
+        };
+        let rows: Vec<ScanRow> = pool.install(|| {
+            targets
+                .par_iter()
+                .flat_map(|target| match target {
+                    Ok(path) => ScanRow::of_source(path),
+                    Err((path, mes)) => vec![ScanRow::error(path, mes)],
+                })
+                .collect()
+        });
+
+        if !self.format.is_text() {
+            let doc = ScanDoc { files: rows };
+            print_structured(self.format, &doc, &doc.files);
+            return;
+        }
+
+        let or_dash = |s: &Option<String>| s.clone().unwrap_or_else(|| "-".to_string());
+        println!("#path\tdisk_name\ttype\tsize\ttracks\tcrc_errors\tfilesystem\tcrc32");
+        for row in rows.iter() {
+            if let Some(ref mes) = row.error {
+                println!("{}\t[ERROR] {}", row.path, mes);
+                continue;
+            }
+            println!(
+                "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                row.path,
+                or_dash(&row.disk_name),
+                or_dash(&row.disk_type),
+                row.size.map_or("-".to_string(), |n| n.to_string()),
+                row.tracks.map_or("-".to_string(), |n| n.to_string()),
+                row.crc_errors.map_or("-".to_string(), |n| n.to_string()),
+                or_dash(&row.filesystem),
+                or_dash(&row.crc32),
+            );
+        }
+
+        let errors = rows.iter().filter(|row| row.error.is_some()).count();
+        println!();
+        println!("{} file(s), {} error(s)", rows.len(), errors);
+    }
+}
+
+/// Expand Paths (Helper function)
+///
+/// 引数をファイル一覧に展開する。
+///
+///   * ディレクトリ     直下のディスクイメージとアーカイブ(`recursive`なら下位ディレクトリも)
+///   * "*", "?", "[]"   グロブパターン
+///   * その他           そのままファイルとして扱う
+///
+/// # Return
+///
+///   * Vec<Ok(path)>, Vec<Err((path, message))>
+///
+fn expand_paths(paths: &[String], recursive: bool) -> Vec<Result<String, (String, String)>> {
+    let mut targets = Vec::new();
+    for path in paths.iter() {
+        let is_pattern = path.contains(['*', '?', '[']) && !Path::new(path).exists();
+        if is_pattern {
+            match glob::glob(path) {
+                Ok(entries) => {
+                    let found: Vec<PathBuf> = entries.filter_map(|entry| entry.ok()).collect();
+                    if found.is_empty() {
+                        targets.push(Err((path.clone(), "No match".to_string())));
+                    }
+                    for found in found.iter() {
+                        expand_path(found, recursive, &mut targets);
+                    }
+                }
+                Err(e) => targets.push(Err((path.clone(), e.to_string()))),
+            }
+        } else {
+            expand_path(Path::new(path), recursive, &mut targets);
+        }
+    }
+    targets
+}
+
+/// Expand a Path (Helper function)
+///
+fn expand_path(path: &Path, recursive: bool, targets: &mut Vec<Result<String, (String, String)>>) {
+    if !path.is_dir() {
+        targets.push(Ok(path.to_string_lossy().to_string()));
+        return;
+    }
+
+    let mut entries: Vec<PathBuf> = match fs::read_dir(path) {
+        Ok(dir) => dir
+            .filter_map(|entry| entry.ok())
+            .map(|e| e.path())
+            .collect(),
+        Err(e) => {
+            targets.push(Err((path.to_string_lossy().to_string(), e.to_string())));
+            return;
+        }
+    };
+    entries.sort();
+
+    for entry in entries.iter() {
+        if entry.is_dir() {
+            if recursive {
+                expand_path(entry, recursive, targets);
+            }
+        } else if fileio::D88FileIO::image_format_from_path(entry).is_some()
+            || ArchiveFormat::from_path(entry).is_some()
+        {
+            targets.push(Ok(entry.to_string_lossy().to_string()));
+        }
+    }
+}
+
+/// "scan" Document (Structured Output)
+///
+#[derive(Debug, Serialize)]
+struct ScanDoc {
+    files: Vec<ScanRow>,
+}
+
+/// "scan" File (Structured Output)
+///
+/// 読めなかったファイルは`error`だけが入る。
+///
+#[derive(Debug, Serialize)]
+struct ScanRow {
+    path: String,
+    disk_name: Option<String>,
+    disk_type: Option<String>,
+    /// File Size (byte)
+    size: Option<u64>,
+    tracks: Option<usize>,
+    sectors: Option<usize>,
+    crc_errors: Option<usize>,
+    filesystem: Option<String>,
+    /// CRC32 of whole File
+    crc32: Option<String>,
+    /// CRC32 of Sector Data
+    content_crc32: Option<String>,
+    error: Option<String>,
+}
+
+impl ScanRow {
+    /// Rows of Source (File, Stdin, Archive)
+    ///
+    /// 壊れたファイルで読み込みがpanicしても、その行をエラーにして続ける。
+    ///
+    fn of_source(source: &str) -> Vec<Self> {
+        let entries = match catch_panic(|| read_source(source)).and_then(|result| result) {
+            Ok(entries) => entries,
+            Err(mes) => return vec![ScanRow::error(source, &mes)],
+        };
+
+        entries
+            .into_iter()
+            .map(|entry| {
+                let name = entry.name.clone();
+                catch_panic(|| ScanRow::new(entry))
+                    .unwrap_or_else(|mes| ScanRow::error(&name, &mes))
+            })
+            .collect()
+    }
+
+    fn new(entry: Entry) -> Self {
+        let file_digest = Digest::from_bytes(&entry.data);
+        let size = entry.data.len() as u64;
+        let format = fileio::D88FileIO::image_format_from_path(&entry.name);
+        let d88fileio = fileio::D88FileIO::from_bytes(entry.data, format);
+        if !d88fileio.is_open() {
+            return ScanRow::error(&entry.name, "Not a D88 disk image");
+        }
+
+        let summary = Summary::new(&d88fileio.disk);
+        Self {
+            path: entry.name,
+            disk_name: Some(summary.disk_name),
+            disk_type: Some(summary.disk_type),
+            size: Some(size),
+            tracks: Some(summary.tracks),
+            sectors: Some(summary.sectors),
+            crc_errors: Some(summary.crc_errors),
+            filesystem: summary.filesystem,
+            crc32: Some(file_digest.get_crc32()),
+            content_crc32: Some(summary.content_crc32),
+            error: None,
+        }
+    }
+
+    fn error(path: &str, mes: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            disk_name: None,
+            disk_type: None,
+            size: None,
+            tracks: None,
+            sectors: None,
+            crc_errors: None,
+            filesystem: None,
+            crc32: None,
+            content_crc32: None,
+            error: Some(mes.to_string()),
+        }
+    }
+}
+
+/// Catch Panic (Helper function)
+///
+/// # Argument
+///
+///   * `f` Function
+///
+/// # Return
+///
+///   * Ok(T)         Result of `f`
+///   * Err(String)   Error Message (`f` panicked)
+///
+fn catch_panic<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(get_panic_message)
+}
+
+/// Panic Message (Helper function)
+///
+fn get_panic_message(payload: Box<dyn Any + Send>) -> String {
+    let mes = if let Some(mes) = payload.downcast_ref::<&str>() {
+        mes.to_string()
+    } else if let Some(mes) = payload.downcast_ref::<String>() {
+        mes.clone()
+    } else {
+        "unknown error".to_string()
+    };
+    format!("Broken disk image ({})", mes)
+}
+
+//
+// Test Code
+//
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const HUBASIC: &str = "../sample/HuBASIC_Format_2D.d88";
+    const HUBASIC_LZH: &str = "../sample/HuBASIC_Format_2D.lzh";
+
+    /// Directory Tree for `expand_paths`
+    ///
+    ///   a.d88, b.txt, c.lzh, sub/d.d77
+    ///
+    fn make_tree(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        for file in ["a.d88", "b.txt", "c.lzh", "sub/d.d77"] {
+            fs::write(dir.join(file), b"").unwrap();
+        }
+        dir
+    }
+
+    fn get_names(targets: &[Result<String, (String, String)>]) -> Vec<String> {
+        targets
+            .iter()
+            .map(|target| match target {
+                Ok(path) => path.clone(),
+                Err((path, mes)) => format!("{}: {}", path, mes),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_expand_paths() {
+        let dir = make_tree("d88dmp_test_scan_expand");
+        let dirs = vec![dir.to_string_lossy().to_string()];
+        let join = |file: &str| dir.join(file).to_string_lossy().to_string();
+
+        // Directory
+        let targets = expand_paths(&dirs, false);
+        assert_eq!(get_names(&targets), vec![join("a.d88"), join("c.lzh")]);
+
+        // --recursive
+        let targets = expand_paths(&dirs, true);
+        assert_eq!(
+            get_names(&targets),
+            vec![join("a.d88"), join("c.lzh"), join("sub/d.d77")]
+        );
+
+        // Glob (a file that isn't an image is still a target)
+        let targets = expand_paths(&[join("*.txt"), join("*/*.d77")], false);
+        assert_eq!(get_names(&targets), vec![join("b.txt"), join("sub/d.d77")]);
+
+        // No match, File as is
+        let pattern = join("*.d99");
+        let targets = expand_paths(&[pattern.clone(), join("none.d88")], false);
+        assert_eq!(
+            get_names(&targets),
+            vec![format!("{}: No match", pattern), join("none.d88")]
+        );
+        assert!(targets[0].is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_scan_rows() {
+        let rows = ScanRow::of_source(HUBASIC);
+        assert_eq!(rows.len(), 1);
+        assert!(rows[0].error.is_none());
+        assert_eq!(rows[0].tracks, Some(80));
+
+        let rows = ScanRow::of_source(HUBASIC_LZH);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].crc32, ScanRow::of_source(HUBASIC)[0].crc32);
+
+        let rows = ScanRow::of_source("../sample/none.d88");
+        assert_eq!(rows.len(), 1);
+        assert!(rows[0].error.is_some() && rows[0].size.is_none());
+
+        let rows = ScanRow::of_source("../README.md");
+        assert_eq!(rows[0].error.as_deref(), Some("Not a D88 disk image"));
+    }
+
+    #[test]
+    fn test_scan_panic() {
+        let mes = catch_panic(|| -> usize { panic!("index out of bounds") }).unwrap_err();
+        assert_eq!(mes, "Broken disk image (index out of bounds)");
+        let mes = catch_panic(|| -> usize { panic!("{}", 42) }).unwrap_err();
+        assert_eq!(mes, "Broken disk image (42)");
+        assert_eq!(catch_panic(|| 1), Ok(1));
+
+        // Error Row
+        let row = ScanRow::error("broken.d88", &mes);
+        assert_eq!(row.path, "broken.d88");
+        assert_eq!(row.error.as_deref(), Some("Broken disk image (42)"));
+        assert!(row.crc32.is_none() && row.tracks.is_none());
+    }
+}
@@ -0,0 +1,165 @@
+use ansi_term::Color;
+use serde::Serialize;
+use std::path::Path;
+
+use D88FileIO::fileio;
+use D88FileIO::search::{search, Hit, Pattern};
+use D88FileIO::select::Selection;
+
+use crate::cli::get_selection;
+use crate::structured::{print_structured, OutputFormat};
+use crate::utility::ERROR;
+
+/// ReportSearch
+///
+/// 16進パターン、ASCII、Shift-JIS、JIS X 0201 の文字列をセクタデータから探す。
+///
+pub struct ReportSearch {
+    pub path: Option<String>,
+    pub pattern: Pattern,
+    pub selection: Selection,
+    pub nocolor_flg: bool,
+    pub format: OutputFormat,
+}
+
+impl ReportSearch {
+    /// Constructor
+    ///
+    pub fn new(_cmdline_info: clap::ArgMatches) -> Self {
+        let pattern = [
+            ("HEX", Pattern::from_hex as fn(&str) -> _),
+            ("ASCII", Pattern::from_ascii),
+            ("SJIS", Pattern::from_sjis),
+            ("JIS", Pattern::from_jisx0201),
+        ]
+        .iter()
+        .find_map(|(arg, parse)| _cmdline_info.value_of(arg).map(parse))
+        .unwrap_or_else(|| Err("No Search Pattern".to_string()))
+        .unwrap_or_else(|mes| {
+            ERROR(mes.as_str());
+            Pattern::default()
+        });
+
+        Self {
+            path: _cmdline_info.value_of("*.D88").map(|path| path.to_string()),
+            pattern,
+            selection: get_selection(&_cmdline_info),
+            nocolor_flg: _cmdline_info.is_present("no-color"),
+            format: OutputFormat::from_cmdline(&_cmdline_info),
+        }
+    }
+
+    /// Report
+    ///
+    /// # Argument
+    ///   * (none)
+    ///
+    pub fn report(&self) {
+        if let Some(ref d88_path) = self.path {
+            let d88fileio = fileio::D88FileIO::open(Path::new(d88_path));
+            if !d88fileio.is_open() {
+                println!("File Not Found \"{}\"", d88_path);
+                return;
+            }
+
+            let hits = search(&d88fileio.disk, &self.selection, &self.pattern);
+            if !self.format.is_text() {
+                self.report_structured(d88_path, &hits);
+                return;
+            }
+
+            println!(
+                "Search  {} ({} byte)",
+                self.get_pattern(),
+                self.pattern.len()
+            );
+            println!();
+            println!("Track    Side Sector  Offset      File Offset  Span");
+            println!("hex dec  No.  No.     in Sector                     ");
+            println!("--- ---- ---  ------  ----------  -----------  ----");
+            for hit in hits.iter() {
+                self.report_hit(hit);
+            }
+
+            println!();
+            println!("{} hit(s)", hits.len());
+        }
+    }
+
+    /// Pattern as Hex String (Helper function)
+    ///
+    fn get_pattern(&self) -> String {
+        self.pattern
+            .bytes
+            .iter()
+            .map(|byte| match byte {
+                Some(byte) => format!("{:02x}", byte),
+                None => "??".to_string(),
+            })
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    /// Report a Hit (Helper function)
+    ///
+    fn report_hit(&self, hit: &Hit) {
+        let tss_formated = format!(
+            "{0:02x}h {0:3}d {1:3}  {2:3}   ",
+            hit.track, hit.side, hit.sector
+        );
+        let offset_formated = format!(
+            "  0x{:04x}      0x{:08x}   {}",
+            hit.offset, hit.file_offset, hit.span
+        );
+
+        if self.nocolor_flg {
+            println!("{}{}", tss_formated, offset_formated);
+        } else {
+            println!("{}{}", Color::Cyan.paint(tss_formated), offset_formated);
+        }
+    }
+
+    /// Report as JSON/YAML/CSV (Helper function)
+    ///
+    /// CSVは1ヒット1行。
+    ///
+    fn report_structured(&self, d88_path: &str, hits: &[Hit]) {
+        let rows: Vec<SearchHit> = hits
+            .iter()
+            .map(|hit| SearchHit {
+                track: hit.track,
+                side: hit.side,
+                sector: hit.sector,
+                offset: hit.offset,
+                file_offset: hit.file_offset,
+                span: hit.span,
+            })
+            .collect();
+
+        let doc = SearchDoc {
+            path: d88_path.to_string(),
+            pattern: self.get_pattern(),
+            hits: rows.clone(),
+        };
+        print_structured(self.format, &doc, &rows);
+    }
+}
+
+/// Search Result (Structured Output)
+///
+#[derive(Serialize)]
+struct SearchDoc {
+    path: String,
+    pattern: String,
+    hits: Vec<SearchHit>,
+}
+
+#[derive(Serialize, Clone)]
+struct SearchHit {
+    track: usize,
+    side: usize,
+    sector: u8,
+    offset: usize,
+    file_offset: u64,
+    span: usize,
+}
@@ -0,0 +1,363 @@
+use base64::Engine;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io;
+
+use D88FileIO::boot::Boot;
+use D88FileIO::charset::Charset;
+use D88FileIO::classify::classify;
+use D88FileIO::disk::Disk;
+use D88FileIO::sector::Sector;
+
+use crate::utility::ERROR;
+
+/// Output Format (`--format`)
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+    Yaml,
+    Csv,
+}
+
+impl OutputFormat {
+    /// Output Format from Command Line
+    ///
+    pub fn from_cmdline(cmdline_info: &clap::ArgMatches) -> Self {
+        match cmdline_info.value_of("FORMAT") {
+            Some("json") => OutputFormat::Json,
+            Some("yaml") => OutputFormat::Yaml,
+            Some("csv") => OutputFormat::Csv,
+            _ => OutputFormat::Text,
+        }
+    }
+
+    pub fn is_text(&self) -> bool {
+        *self == OutputFormat::Text
+    }
+}
+
+/// Sector Data Encoding (`--data`)
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataEncoding {
+    None,
+    Base64,
+    Hex,
+}
+
+impl DataEncoding {
+    /// Sector Data Encoding from Command Line
+    ///
+    pub fn from_cmdline(cmdline_info: &clap::ArgMatches) -> Self {
+        match cmdline_info.value_of("DATA") {
+            Some("base64") => DataEncoding::Base64,
+            Some("hex") => DataEncoding::Hex,
+            _ => DataEncoding::None,
+        }
+    }
+
+    /// Encode Sector Data
+    ///
+    /// # Return
+    ///
+    ///   * Some(String)  Encoded Data
+    ///   * None          `DataEncoding::None`
+    ///
+    pub fn encode(&self, data: &[u8]) -> Option<String> {
+        match self {
+            DataEncoding::None => None,
+            DataEncoding::Base64 => Some(base64::engine::general_purpose::STANDARD.encode(data)),
+            DataEncoding::Hex => Some(data.iter().map(|b| format!("{:02x}", b)).collect()),
+        }
+    }
+}
+
+/// D88 File Header (Structured Output)
+///
+#[derive(Debug, Serialize)]
+pub struct HeaderDoc {
+    pub disk_name: String,
+    pub write_protect: u8,
+    pub disk_type: u8,
+    pub disk_size: u32,
+    pub image_format: String,
+    pub track_offset_tbl: Vec<u32>,
+}
+
+impl HeaderDoc {
+    pub fn new(disk: &Disk) -> Self {
+        let header = &disk.header;
+
+        Self {
+            disk_name: disk.get_disk_name_with(Charset::Sjis),
+            write_protect: header.write_protect,
+            disk_type: header.disk_type,
+            disk_size: header.disk_size,
+            image_format: format!("{:?}", disk.format),
+            track_offset_tbl: header.track_offset_tbl.to_vec(),
+        }
+    }
+}
+
+/// Sector Header and Data (Structured Output)
+///
+/// CSVでは1セクタ1行になる。
+///
+#[derive(Debug, Serialize)]
+pub struct SectorDoc {
+    pub track: u8,
+    pub side: u8,
+    pub sector: u8,
+    pub sector_size: u8,
+    pub number_of_sec: u16,
+    pub density: u8,
+    pub deleted_mark: u8,
+    pub status: u8,
+    pub size_of_data: u16,
+    /// Offset to Sector Data at D88 File
+    pub offset: u64,
+    /// Content Type ("Empty", "Text", "Code", ..)
+    pub content: String,
+    /// Shannon Entropy of Sector Data (bit/byte)
+    pub entropy: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<String>,
+}
+
+impl SectorDoc {
+    pub fn new(sector: &Sector, encoding: DataEncoding) -> Self {
+        let hdr = &sector.header;
+        let classification = classify(&sector.data);
+        Self {
+            track: hdr.track,
+            side: hdr.side,
+            sector: hdr.sector,
+            sector_size: hdr.sector_size,
+            number_of_sec: hdr.number_of_sec,
+            density: hdr.density,
+            deleted_mark: hdr.deleted_mark,
+            status: hdr.status,
+            size_of_data: hdr.size_of_data,
+            offset: sector.offset,
+            content: classification.content.get_name().to_string(),
+            entropy: (classification.entropy * 1000.0).round() / 1000.0,
+            data: encoding.encode(&sector.data),
+        }
+    }
+}
+
+/// Boot Loader (Structured Output)
+///
+#[derive(Debug, Serialize)]
+pub struct BootDoc {
+    pub loader: String,
+    pub fields: BTreeMap<String, String>,
+}
+
+impl BootDoc {
+    pub fn new(boot: &Boot) -> Self {
+        Self {
+            loader: boot.get_name().to_string(),
+            fields: boot
+                .get_fields()
+                .into_iter()
+                .map(|(name, value)| (name.to_string(), value))
+                .collect(),
+        }
+    }
+}
+
+/// D88 File (Structured Output)
+///
+#[derive(Debug, Serialize)]
+pub struct DiskDoc {
+    pub path: String,
+    pub header: HeaderDoc,
+    /// Detected Filesystem
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filesystem: Option<String>,
+    /// Boot Loader
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub boot: Option<BootDoc>,
+    pub sectors: Vec<SectorDoc>,
+}
+
+impl DiskDoc {
+    /// All Sectors in `track_tbl` Order
+    ///
+    pub fn new(path: &str, disk: &Disk, encoding: DataEncoding) -> Self {
+        Self {
+            path: path.to_string(),
+            header: HeaderDoc::new(disk),
+            filesystem: None,
+            boot: None,
+            sectors: disk
+                .track_tbl
+                .iter()
+                .flat_map(|track| track.sector_tbl.iter())
+                .map(|sector| SectorDoc::new(sector, encoding))
+                .collect(),
+        }
+    }
+}
+
+/// Print Structured Document
+///
+/// JSON/YAML は `doc` を、CSV は `rows` を1行1レコードで出力する。
+/// CSVは平らな表なので、`doc` にしか無い項目(D88ヘッダ、トラックオフセットテーブル等)は出力しない。
+///
+/// # Argument
+///
+///   * `format` Output Format (not `Text`)
+///   * `doc`    Whole Document
+///   * `rows`   Flat Records for CSV
+///
+pub fn print_structured<D: Serialize, R: Serialize>(format: OutputFormat, doc: &D, rows: &[R]) {
+    if let Err(mes) = write_structured(io::stdout(), format, doc, rows) {
+        ERROR(mes.as_str());
+    }
+}
+
+/// Write Structured Document
+///
+/// `print_structured()` の出力先を指定する版。
+///
+/// # Argument
+///
+///   * `out`    Output
+///   * `format` Output Format (`Text` writes nothing)
+///   * `doc`    Whole Document
+///   * `rows`   Flat Records for CSV
+///
+/// # Return
+///
+///   * Ok(())
+///   * Err(String) Error Message
+///
+pub fn write_structured<W: io::Write, D: Serialize, R: Serialize>(
+    mut out: W,
+    format: OutputFormat,
+    doc: &D,
+    rows: &[R],
+) -> Result<(), String> {
+    match format {
+        OutputFormat::Json => {
+            let s = serde_json::to_string_pretty(doc).map_err(|e| e.to_string())?;
+            writeln!(out, "{}", s).map_err(|e| e.to_string())
+        }
+        OutputFormat::Yaml => {
+            let s = serde_yaml::to_string(doc).map_err(|e| e.to_string())?;
+            write!(out, "{}", s).map_err(|e| e.to_string())
+        }
+        OutputFormat::Csv => write_csv(out, rows),
+        OutputFormat::Text => Ok(()),
+    }
+}
+
+/// Write CSV (Helper function)
+///
+fn write_csv<W: io::Write, R: Serialize>(out: W, rows: &[R]) -> Result<(), String> {
+    let mut writer = csv::Writer::from_writer(out);
+    for row in rows.iter() {
+        writer.serialize(row).map_err(|e| e.to_string())?;
+    }
+    writer.flush().map_err(|e| e.to_string())
+}
+
+//
+// Test Code
+//
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::path::Path;
+    use D88FileIO::fileio;
+
+    const SAMPLES: [&str; 2] = [
+        "../sample/HuBASIC_Format_2D.d88",
+        "../sample/CPM_Format_2D_turboCPM_X1turbo.d88",
+    ];
+
+    fn get_doc(path: &str, encoding: DataEncoding) -> (DiskDoc, usize) {
+        let d88fileio = fileio::D88FileIO::open(Path::new(path));
+        assert!(d88fileio.is_open());
+
+        let disk = &d88fileio.disk;
+        let count = disk
+            .track_tbl
+            .iter()
+            .map(|track| track.sector_tbl.len())
+            .sum();
+        (DiskDoc::new(path, disk, encoding), count)
+    }
+
+    fn get_output(format: OutputFormat, doc: &DiskDoc) -> String {
+        let mut out = Vec::new();
+        write_structured(&mut out, format, doc, &doc.sectors).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_json() {
+        for path in SAMPLES.iter() {
+            let (doc, count) = get_doc(path, DataEncoding::None);
+            let value: serde_json::Value =
+                serde_json::from_str(&get_output(OutputFormat::Json, &doc)).unwrap();
+
+            assert_eq!(value["path"], *path);
+            assert_eq!(value["header"]["disk_size"], doc.header.disk_size);
+            assert_eq!(
+                value["header"]["track_offset_tbl"]
+                    .as_array()
+                    .unwrap()
+                    .len(),
+                164
+            );
+            assert_eq!(value["sectors"].as_array().unwrap().len(), count);
+            assert_eq!(value["sectors"][0]["track"], 0);
+            assert!(value["sectors"][0].get("data").is_none());
+        }
+    }
+
+    #[test]
+    fn test_yaml() {
+        for path in SAMPLES.iter() {
+            let (doc, count) = get_doc(path, DataEncoding::Hex);
+            let value: serde_yaml::Value =
+                serde_yaml::from_str(&get_output(OutputFormat::Yaml, &doc)).unwrap();
+
+            assert_eq!(value["path"].as_str(), Some(*path));
+            assert_eq!(value["header"]["image_format"].as_str(), Some("D88"));
+            assert_eq!(value["sectors"].as_sequence().unwrap().len(), count);
+
+            let data = value["sectors"][0]["data"].as_str().unwrap();
+            assert_eq!(data.len(), doc.sectors[0].size_of_data as usize * 2);
+        }
+    }
+
+    #[test]
+    fn test_csv() {
+        for path in SAMPLES.iter() {
+            let (doc, count) = get_doc(path, DataEncoding::Base64);
+            let csv = get_output(OutputFormat::Csv, &doc);
+            let lines: Vec<&str> = csv.lines().collect();
+
+            // Column Names + 1 Row per Sector (no D88 header, no track offset table)
+            assert_eq!(lines.len(), count + 1);
+            assert_eq!(
+                lines[0],
+                "track,side,sector,sector_size,number_of_sec,density,deleted_mark,status,size_of_data,offset,content,entropy,data"
+            );
+            assert!(lines[1].starts_with("0,0,"));
+            assert!(!csv.contains("track_offset_tbl"));
+        }
+    }
+
+    #[test]
+    fn test_text() {
+        let (doc, _) = get_doc(SAMPLES[0], DataEncoding::None);
+        assert_eq!(get_output(OutputFormat::Text, &doc), "");
+    }
+}